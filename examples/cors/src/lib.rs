@@ -7,7 +7,7 @@ use lazy_static::lazy_static;
 use profile::Profile;
 use serde::Serialize;
 use worker::{event, Cors, Env, Method, Request, Response, Result, RouteContext, Router};
-use worker_route::{get, Configure, Query, Service};
+use worker_route::{get, Configure, Path, Query, Service};
 
 const PROFILES_BYTES: &[u8] = include_bytes!("profile.json");
 
@@ -31,8 +31,8 @@ struct Res<T> {
 // single path
 // eg: /profile/Foo
 #[get("/profile/:name", lazy_cors = CUSTOM_CORS)]
-async fn get_single(query: Query<ProfileSingle>, ctx: RouteContext<Profile>) -> Result<Response> {
-    let ProfileSingle { name } = query.into_inner();
+async fn get_single(path: Path<ProfileSingle>, ctx: RouteContext<Profile>) -> Result<Response> {
+    let ProfileSingle { name } = path.into_inner();
     let res = ctx.data.single(&name.to_lowercase());
 
     Response::from_json(&Res { data: res })
@@ -3,7 +3,7 @@ mod error;
 use crate::error::CustomError;
 use serde::Deserialize;
 use worker::{event, Env, Request, Response, RouteContext, Router};
-use worker_route::{get, http::StatusCode, Configure, Service};
+use worker_route::{fallback, get, http::StatusCode, Configure, Path, Service};
 
 #[allow(unused)]
 #[derive(Deserialize)]
@@ -30,8 +30,20 @@ async fn question_mark_operator(
     Ok(Response::empty()?)
 }
 
+// unmatched routes are answered with `CustomError` as well
+#[fallback]
+fn not_found(path: Path<String>) -> Result<Response, CustomError> {
+    Err(CustomError::new(
+        format!("{} not found", path.into_inner()),
+        StatusCode::NOT_FOUND,
+    ))
+}
+
 fn init_routes(router: Router<'_, ()>) -> Router<'_, ()> {
-    router.configure(question_mark_operator).configure(error_)
+    router
+        .configure(question_mark_operator)
+        .configure(error_)
+        .configure(not_found)
 }
 
 #[event(fetch)]
@@ -4,11 +4,10 @@ use std::fmt::Display;
 use worker::{event, Env, Request, Response, Result, RouteContext, Router};
 use worker_route::http::header::ACCEPT;
 use worker_route::http::{HttpRequest, HttpResponse, ResponseBuilder};
-use worker_route::{get, Configure, Query, Responder, Service};
+use worker_route::{get, Configure, Path, Query, Responder, Service};
 
 #[derive(Serialize, Deserialize)]
 struct Person {
-    name: String,
     age: usize,
 }
 
@@ -50,9 +49,13 @@ fn bytes_response(_: Request, _: RouteContext<()>) -> Result<&'static [u8]> {
 }
 
 #[get("/serde_value_response/:name")]
-fn serde_value_response(query: Query<Person>, _: RouteContext<()>) -> Result<Value> {
+fn serde_value_response(
+    name: Path<String>,
+    query: Query<Person>,
+    _: RouteContext<()>,
+) -> Result<Value> {
     Ok(json!({
-        "name": query.name,
+        "name": name.into_inner(),
         "age": query.age
     }))
 }
@@ -1,6 +1,6 @@
 use serde::{Deserialize, Serialize};
 use worker::{event, Env, Request, Response, Result, RouteContext, Router};
-use worker_route::{get, post, Configure, Query, Service};
+use worker_route::{get, post, Configure, Path, Query, Service};
 
 #[derive(Debug, Serialize, Deserialize)]
 struct Bar {
@@ -40,8 +40,8 @@ struct Person {
 }
 
 #[post("/person/:name/:age")]
-async fn person(query: Query<Person>, _: RouteContext<()>) -> Result<Response> {
-    Response::from_json(&query.into_inner())
+async fn person(path: Path<Person>, _: RouteContext<()>) -> Result<Response> {
+    Response::from_json(&path.into_inner())
 }
 
 fn init_routes(router: Router<'static, ()>) -> Router<'static, ()> {
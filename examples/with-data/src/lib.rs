@@ -1,6 +1,6 @@
 use serde::{Deserialize, Serialize};
 use worker::{console_log, event, Env, Request, Response, Result, RouteContext, Router};
-use worker_route::{get, post, Configure, Query, Service};
+use worker_route::{get, post, Configure, Path, Query, Service, State};
 
 #[derive(Debug, Serialize, Deserialize)]
 struct Bar {
@@ -19,9 +19,10 @@ struct Foo {
     foo: String,
 }
 
+// RouteContext<Data> is not needed to access the router's data
 #[get("/foo")]
-async fn foo(query: Query<Foo>, ctx: RouteContext<Data>) -> Result<Response> {
-    console_log!("It works. \n{:?}", ctx.data);
+async fn foo(query: Query<Foo>, data: State<Data>) -> Result<Response> {
+    console_log!("It works. \n{:?}", data);
 
     Response::from_json(&query.into_inner())
 }
@@ -46,10 +47,10 @@ struct Person {
 }
 
 #[post("/person/:name/:age")]
-async fn person(query: Query<Person>, ctx: RouteContext<Data>) -> Result<Response> {
+async fn person(path: Path<Person>, ctx: RouteContext<Data>) -> Result<Response> {
     console_log!("It works. \n{:?}", ctx.data);
 
-    Response::from_json(&query.into_inner())
+    Response::from_json(&path.into_inner())
 }
 
 fn init_routes(router: Router<'static, Data>) -> Router<'static, Data> {
@@ -61,7 +62,7 @@ fn init_routes(router: Router<'static, Data>) -> Router<'static, Data> {
 }
 
 #[allow(unused)]
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 struct Data {
     tls_version: String,
 }
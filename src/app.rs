@@ -0,0 +1,233 @@
+use crate::middleware::{DynMiddleware, Middleware};
+use crate::registry::{self, Routes};
+use crate::route::{self, AddHandler, Configure, RouteFactory, Service};
+
+use core::future::Future;
+use worker::{Env, Method, Request, Response, Result, Router};
+
+/// A [`worker::Router`](https://docs.rs/worker/latest/worker/struct.Router.html)
+/// with global middlewares.
+///
+/// The handlers are configured into its router, see [`Configure`] for the answers of a router
+/// to the requests without a handler.
+/// The global middlewares added with [`App::wrap`] run around every handler of the router and these answers.
+///
+/// # Example
+///
+/// ```
+/// use worker::{Env, Request, Response, Result};
+/// use worker_route::{get, post, App, Configure};
+///
+/// #[get("/users")]
+/// async fn users() -> Result<String> {
+///     Ok("users".to_owned())
+/// }
+///
+/// #[post("/users")]
+/// async fn create_user() -> Result<String> {
+///     Ok("created".to_owned())
+/// }
+///
+/// // called from the worker's `#[event(fetch)]`
+/// pub async fn fetch(req: Request, env: Env) -> Result<Response> {
+///     App::new()
+///         .configure(users)
+///         .configure(create_user)
+///         .run(req, env)
+///         .await
+/// }
+/// ```
+pub struct App<'a, D> {
+    router: Router<'a, D>,
+    // the global middlewares, in the order they were added
+    middleware: Vec<Box<dyn DynMiddleware<D>>>,
+}
+
+impl App<'_, ()> {
+    /// Creates an app without data, see [`Router::new`](https://docs.rs/worker/latest/worker/struct.Router.html#method.new).
+    pub fn new() -> Self {
+        Self::from(Router::new())
+    }
+}
+
+impl Default for App<'_, ()> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<D: 'static> App<'_, D> {
+    /// Creates an app with data, see [`Router::with_data`](https://docs.rs/worker/latest/worker/struct.Router.html#method.with_data).
+    pub fn with_data(data: D) -> Self {
+        Self::from(Router::with_data(data))
+    }
+
+    /// Runs the [`Middleware`] around every handler of the app,
+    /// before the middlewares of the handler's `wrap` attribute.
+    ///
+    /// The middlewares run in the order they were added, a middleware of the same type may be added again,
+    /// eg: a [`RateLimit`](crate::RateLimit) per minute and one per day.
+    /// They're set as the app runs, for the handlers of the routers with the app's data type.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use worker::Response;
+    /// use worker_route::{get, App, Configure, Error, HttpRequest, Middleware};
+    ///
+    /// struct NoSniff;
+    ///
+    /// impl<D> Middleware<D> for NoSniff {
+    ///     async fn after(&self, _: &HttpRequest, mut res: Response) -> Result<Response, Error> {
+    ///         res.headers_mut().set("x-content-type-options", "nosniff")?;
+    ///         Ok(res)
+    ///     }
+    /// }
+    ///
+    /// #[get("/users")]
+    /// async fn users() -> worker::Result<String> {
+    ///     Ok("users".to_owned())
+    /// }
+    ///
+    /// fn init_routes(app: App<'static, ()>) -> App<'static, ()> {
+    ///     app.wrap(NoSniff).configure(users)
+    /// }
+    /// ```
+    pub fn wrap<M: Middleware<D> + 'static>(mut self, middleware: M) -> Self {
+        self.middleware.push(Box::new(middleware));
+        self
+    }
+
+    /// Sets the global middlewares and runs the router.
+    ///
+    /// # Errors
+    ///
+    /// Returns the error of the handler.
+    #[allow(clippy::future_not_send)]
+    pub async fn run(self, req: Request, env: Env) -> Result<Response> {
+        let Self { router, middleware } = self;
+        registry::with(|routes: &mut Routes<D>| routes.wrap(middleware.into()));
+        router.run(req, env).await
+    }
+}
+
+impl<'a, D: 'static> From<Router<'a, D>> for App<'a, D> {
+    fn from(router: Router<'a, D>) -> Self {
+        Self {
+            router,
+            middleware: Vec::new(),
+        }
+    }
+}
+
+impl<D: 'static> Configure<D> for App<'_, D> {
+    fn configure<F: RouteFactory<D>>(self, f: F) -> Self {
+        f.register(self)
+    }
+}
+
+// the handlers are added to the app's router
+impl<D: 'static> AddHandler<D> for App<'_, D> {
+    fn register(
+        mut self,
+        pattern: &str,
+        method: Method,
+        sync_handler: route::Handler<D, Result<Response>>,
+    ) -> Self {
+        self.router = self.router.register(pattern, method, sync_handler);
+        self
+    }
+
+    fn register_async<U: Future<Output = Result<Response>> + 'static>(
+        mut self,
+        pattern: &str,
+        method: Method,
+        async_handler: route::Handler<D, U>,
+    ) -> Self {
+        self.router = self.router.register_async(pattern, method, async_handler);
+        self
+    }
+
+    fn register_any(
+        mut self,
+        pattern: &str,
+        methods: &[&str],
+        sync_handler: route::Handler<D, Result<Response>>,
+    ) -> Self {
+        self.router = self.router.register_any(pattern, methods, sync_handler);
+        self
+    }
+
+    fn register_any_async<U: Future<Output = Result<Response>> + 'static>(
+        mut self,
+        pattern: &str,
+        methods: &[&str],
+        async_handler: route::Handler<D, U>,
+    ) -> Self {
+        self.router = self
+            .router
+            .register_any_async(pattern, methods, async_handler);
+        self
+    }
+
+    fn fallback(mut self, sync_handler: route::Handler<D, Result<Response>>) -> Self {
+        self.router = self.router.fallback(sync_handler);
+        self
+    }
+
+    fn fallback_async<U: Future<Output = Result<Response>> + 'static>(
+        mut self,
+        async_handler: route::Handler<D, U>,
+    ) -> Self {
+        self.router = self.router.fallback_async(async_handler);
+        self
+    }
+}
+
+impl<D> Service for App<'_, D> {
+    fn service<F: FnOnce(Self) -> Self>(self, f: F) -> Self {
+        f(self)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::App;
+    use crate::registry::{self, Routes};
+    use crate::route::AddHandler;
+    use crate::Middleware;
+    use worker::{Method, Request, Response, Result, RouteContext};
+
+    struct Timing;
+
+    impl<D> Middleware<D> for Timing {}
+
+    #[test]
+    fn global_stack() {
+        let app = App::with_data(()).wrap(Timing).wrap(Timing);
+        // a middleware of the same type is added again
+        assert_eq!(app.middleware.len(), 2);
+        // and only to its app
+        assert!(App::with_data(()).middleware.is_empty());
+        assert!(App::with_data(0_u8).wrap(Timing).middleware.len() == 1);
+    }
+
+    #[test]
+    fn configured_handlers() {
+        #[allow(clippy::needless_pass_by_value)]
+        fn users(_: Request, _: RouteContext<u32>) -> Result<Response> {
+            Response::empty()
+        }
+
+        // added to the app's router, which records them
+        let _app = App::with_data(0_u32)
+            .register("/users", Method::Get, users)
+            .register_any("/users", &["PURGE"], users)
+            .fallback(users);
+        registry::with(|routes: &mut Routes<u32>| {
+            assert_eq!(routes.allowed("/users"), vec!["GET", "PURGE"]);
+            assert!(routes.handler("/users", "PURGE").is_some());
+            assert!(routes.fallback().is_some());
+        });
+    }
+}
@@ -0,0 +1,519 @@
+//! Authenticate requests with [`BearerAuth`], [`BasicAuth`] or [`ApiKey`].
+//!
+//! The credentials are read from the request and verified by the principal's
+//! [`FromBearer`], [`FromBasic`] or [`FromApiKey`], which has access to the environment.
+//! The extractor then holds the principal, an argument the handler doesn't use, eg: `_: BasicAuth<Admin>`,
+//! guards it all the same.
+//!
+//! A request whose credentials are missing or rejected is answered with `401 Unauthorized`
+//! and a `WWW-Authenticate` challenge, see [`AuthError`].
+use crate::error::{Error, ErrorCause};
+use crate::extract::FromRequest;
+use crate::http::{HttpRequest, HttpResponse, ResponseError};
+
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use core::fmt::{Debug, Display};
+use core::future::Future;
+use http::header::{AUTHORIZATION, COOKIE, WWW_AUTHENTICATE};
+use http::StatusCode;
+use std::ops::{Deref, DerefMut};
+use worker::{Env, Request, RouteContext};
+
+/// Where the credentials of [`BearerAuth`] or [`ApiKey`] are read from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Location {
+    /// The `Authorization` header, the value after the scheme for [`BearerAuth`], eg: `Bearer <token>`.
+    Authorization,
+    /// A header, eg: `x-api-key`.
+    Header(&'static str),
+    /// A parameter of the query, eg: `access_token`.
+    Query(&'static str),
+    /// A cookie, eg: `session`.
+    Cookie(&'static str),
+}
+
+// the `Authorization` header without its scheme, compared regardless of its case
+fn strip_scheme<'a>(value: &'a str, scheme: &str) -> Option<&'a str> {
+    let (found, credentials) = value.trim().split_once(' ')?;
+    found
+        .eq_ignore_ascii_case(scheme)
+        .then(|| credentials.trim())
+}
+
+// the value of a cookie in the `Cookie` header
+#[cfg(feature = "cookies")]
+fn cookie(header: &str, name: &str) -> Option<String> {
+    cookie::Cookie::split_parse(header)
+        .flatten()
+        .find(|v| v.name() == name)
+        .map(|v| v.value().trim_matches('"').to_owned())
+}
+
+// the value of a cookie in the `Cookie` header
+#[cfg(not(feature = "cookies"))]
+fn cookie(header: &str, name: &str) -> Option<String> {
+    header
+        .split(';')
+        .filter_map(|v| v.trim().split_once('='))
+        .find(|(key, _)| *key == name)
+        .map(|(_, value)| value.trim_matches('"').to_owned())
+}
+
+/// Compares two secrets in a time that doesn't depend on their content, only on their lengths,
+/// eg: a password or an API key against the one of the environment.
+pub fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0, |acc, (a, b)| acc | (a ^ b)) == 0
+}
+
+// `Ok(None)` if the credentials are missing, `Err` if they are malformed,
+// an empty scheme reads the whole `Authorization` header
+pub(crate) fn read(req: &Request, location: Location, scheme: &str) -> Result<Option<String>, ()> {
+    let header = |name: &str| req.headers().get(name).ok().flatten();
+    let value = match location {
+        Location::Authorization => match header(AUTHORIZATION.as_str()) {
+            Some(value) if scheme.is_empty() => Some(value),
+            Some(value) => Some(strip_scheme(&value, scheme).ok_or(())?.to_owned()),
+            None => None,
+        },
+        Location::Header(name) => header(name),
+        Location::Query(name) => req.url().ok().and_then(|url| {
+            url.query_pairs()
+                .find(|(key, _)| key == name)
+                .map(|(_, value)| value.into_owned())
+        }),
+        Location::Cookie(name) => header(COOKIE.as_str()).and_then(|v| cookie(&v, name)),
+    };
+
+    match value {
+        Some(value) if value.trim().is_empty() => Err(()),
+        value => Ok(value),
+    }
+}
+
+/// The error of [`BearerAuth`], [`BasicAuth`] and [`ApiKey`].
+///
+/// Missing or rejected credentials are a `401 Unauthorized` with a `WWW-Authenticate` challenge,
+/// eg: `Bearer realm="api", error="invalid_token"`.
+/// An error of the verifier is returned as is, without a challenge.
+#[derive(Debug)]
+pub struct AuthError {
+    error: Error,
+    challenge: Option<String>,
+}
+
+impl AuthError {
+    fn unauthorized(message: &str, challenge: String) -> Self {
+        Self {
+            error: Error::new(
+                message.to_owned(),
+                StatusCode::UNAUTHORIZED,
+                ErrorCause::Auth,
+            ),
+            challenge: Some(challenge),
+        }
+    }
+
+    /// The `WWW-Authenticate` challenge of the response, `None` if the verifier failed.
+    pub fn challenge(&self) -> Option<&str> {
+        self.challenge.as_deref()
+    }
+
+    /// Returns the underlying error's occurrence
+    pub fn cause(&self) -> &ErrorCause {
+        self.error.cause()
+    }
+}
+
+impl Display for AuthError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        Display::fmt(&self.error, f)
+    }
+}
+
+impl From<worker::Error> for AuthError {
+    fn from(err: worker::Error) -> Self {
+        Self {
+            error: err.into(),
+            challenge: None,
+        }
+    }
+}
+
+impl ResponseError for AuthError {
+    fn error_response(&self, req: HttpRequest) -> HttpResponse {
+        let mut res = self.error.error_response(req);
+        if let Some(challenge) = &self.challenge {
+            _ = res
+                .0
+                .headers_mut()
+                .set(WWW_AUTHENTICATE.as_str(), challenge);
+        }
+
+        res
+    }
+
+    fn status_code(&self) -> StatusCode {
+        self.error.status_code()
+    }
+
+    fn description(&self) -> String {
+        self.error.description()
+    }
+
+    fn status_codes() -> Vec<StatusCode> {
+        vec![StatusCode::UNAUTHORIZED, StatusCode::INTERNAL_SERVER_ERROR]
+    }
+}
+
+// `realm="api"`, and the error of a bearer token, see RFC 6750
+pub(crate) fn challenge(scheme: &str, realm: &str, error: Option<&str>) -> String {
+    let realm = realm.replace('\\', "\\\\").replace('"', "\\\"");
+    let error = error.map(|v| format!(r#", error="{v}""#));
+    format!(r#"{scheme} realm="{realm}"{}"#, error.unwrap_or_default())
+}
+
+/// Verifies the token of [`BearerAuth`] and returns its principal.
+pub trait FromBearer: Sized {
+    /// The realm of the `WWW-Authenticate` challenge.
+    const REALM: &'static str = "api";
+    /// Where the token is read from, the `Authorization` header by default.
+    const LOCATION: Location = Location::Authorization;
+
+    /// Returns the principal of the token, `None` if the token is rejected.
+    fn from_bearer(token: &str, env: &Env) -> impl Future<Output = worker::Result<Option<Self>>>;
+}
+
+/// The credentials of [`BasicAuth`], from `Authorization: Basic <base64 of user-id:password>`.
+#[derive(Clone, PartialEq, Eq)]
+pub struct BasicCredentials {
+    user_id: String,
+    password: String,
+}
+
+impl BasicCredentials {
+    // the base64 of `user-id:password`, the password may contain `:`
+    fn decode(encoded: &str) -> Option<Self> {
+        let decoded = String::from_utf8(STANDARD.decode(encoded).ok()?).ok()?;
+        let (user_id, password) = decoded.split_once(':')?;
+
+        Some(Self {
+            user_id: user_id.to_owned(),
+            password: password.to_owned(),
+        })
+    }
+
+    /// The user id.
+    pub fn user_id(&self) -> &str {
+        &self.user_id
+    }
+
+    /// The password.
+    pub fn password(&self) -> &str {
+        &self.password
+    }
+}
+
+impl Debug for BasicCredentials {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("BasicCredentials")
+            .field("user_id", &self.user_id)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Verifies the credentials of [`BasicAuth`] and returns its principal.
+pub trait FromBasic: Sized {
+    /// The realm of the `WWW-Authenticate` challenge.
+    const REALM: &'static str = "api";
+
+    /// Returns the principal of the credentials, `None` if they are rejected.
+    fn from_basic(
+        credentials: &BasicCredentials,
+        env: &Env,
+    ) -> impl Future<Output = worker::Result<Option<Self>>>;
+}
+
+/// Verifies the key of [`ApiKey`] and returns its principal.
+pub trait FromApiKey: Sized {
+    /// The realm of the `WWW-Authenticate` challenge.
+    const REALM: &'static str = "api";
+    /// Where the key is read from, the `x-api-key` header by default.
+    const LOCATION: Location = Location::Header("x-api-key");
+
+    /// Returns the principal of the key, `None` if the key is rejected.
+    fn from_api_key(key: &str, env: &Env) -> impl Future<Output = worker::Result<Option<Self>>>;
+}
+
+macro_rules! impl_auth {
+    ($(#[$meta:meta])* $name:ident) => {
+        $(#[$meta])*
+        pub struct $name<P>(P);
+
+        impl<P> $name<P> {
+            #[allow(clippy::missing_const_for_fn)]
+            /// Acess the owned principal
+            pub fn into_inner(self) -> P {
+                self.0
+            }
+        }
+
+        impl<P> Deref for $name<P> {
+            type Target = P;
+
+            fn deref(&self) -> &Self::Target {
+                &self.0
+            }
+        }
+
+        impl<P> DerefMut for $name<P> {
+            fn deref_mut(&mut self) -> &mut P {
+                &mut self.0
+            }
+        }
+
+        impl<P> AsRef<P> for $name<P> {
+            fn as_ref(&self) -> &P {
+                &self.0
+            }
+        }
+
+        impl<P: Debug> Debug for $name<P> {
+            fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+                f.debug_tuple(stringify!($name)).field(&self.0).finish()
+            }
+        }
+    };
+}
+
+// also the extractor of `Jwt`, whose principal is its claims
+#[cfg(feature = "jwt")]
+pub(crate) use impl_auth;
+
+impl_auth!(
+    /// Authenticate the request with a bearer token, verified by the principal's [`FromBearer`].
+    ///
+    /// # Example
+    /// ```
+    /// use worker::{Env, Result};
+    /// use worker_route::auth::FromBearer;
+    /// use worker_route::{get, BearerAuth};
+    ///
+    /// struct User {
+    ///     id: String,
+    /// }
+    ///
+    /// impl FromBearer for User {
+    ///     async fn from_bearer(token: &str, env: &Env) -> Result<Option<Self>> {
+    ///         let sessions = env.kv("SESSIONS")?;
+    ///         Ok(sessions.get(token).text().await?.map(|id| Self { id }))
+    ///     }
+    /// }
+    ///
+    /// #[get("/me")]
+    /// async fn me(user: BearerAuth<User>) -> Result<String> {
+    ///     Ok(user.into_inner().id)
+    /// }
+    /// ```
+    BearerAuth
+);
+impl_auth!(
+    /// Authenticate the request with a user id and a password, verified by the principal's [`FromBasic`].
+    ///
+    /// # Example
+    /// ```
+    /// use worker::{Env, Result};
+    /// use worker_route::auth::{constant_time_eq, BasicCredentials, FromBasic};
+    /// use worker_route::{get, BasicAuth};
+    ///
+    /// struct Admin;
+    ///
+    /// impl FromBasic for Admin {
+    ///     const REALM: &'static str = "admin";
+    ///
+    ///     async fn from_basic(credentials: &BasicCredentials, env: &Env) -> Result<Option<Self>> {
+    ///         let password = env.secret("ADMIN_PASSWORD")?.to_string();
+    ///         let allowed = credentials.user_id() == "admin"
+    ///             && constant_time_eq(credentials.password().as_bytes(), password.as_bytes());
+    ///         Ok(allowed.then_some(Self))
+    ///     }
+    /// }
+    ///
+    /// #[get("/admin/stats")]
+    /// async fn stats(_: BasicAuth<Admin>) -> Result<String> {
+    ///     Ok("stats".to_owned())
+    /// }
+    /// ```
+    BasicAuth
+);
+impl_auth!(
+    /// Authenticate the request with an API key, verified by the principal's [`FromApiKey`].
+    ///
+    /// # Example
+    /// ```
+    /// use worker::{Env, Result};
+    /// use worker_route::auth::{FromApiKey, Location};
+    /// use worker_route::{get, ApiKey};
+    ///
+    /// struct Client {
+    ///     name: String,
+    /// }
+    ///
+    /// impl FromApiKey for Client {
+    ///     // `?api_key=..`
+    ///     const LOCATION: Location = Location::Query("api_key");
+    ///
+    ///     async fn from_api_key(key: &str, env: &Env) -> Result<Option<Self>> {
+    ///         let clients = env.kv("CLIENTS")?;
+    ///         Ok(clients.get(key).text().await?.map(|name| Self { name }))
+    ///     }
+    /// }
+    ///
+    /// #[get("/reports")]
+    /// async fn reports(client: ApiKey<Client>) -> Result<String> {
+    ///     Ok(format!("reports of {}", client.name))
+    /// }
+    /// ```
+    ApiKey
+);
+
+pub(crate) const BEARER: &str = "Bearer";
+const BASIC: &str = "Basic";
+const API_KEY: &str = "ApiKey";
+
+impl<D, P: FromBearer> FromRequest<D> for BearerAuth<P> {
+    type Error = AuthError;
+
+    #[allow(clippy::future_not_send)]
+    async fn from_request(req: &Request, ctx: &RouteContext<D>) -> Result<Self, Self::Error> {
+        let unauthorized = |message: &str, error: Option<&str>| {
+            AuthError::unauthorized(message, challenge(BEARER, P::REALM, error))
+        };
+        let token = match read(req, P::LOCATION, BEARER) {
+            Ok(Some(token)) => token,
+            Ok(None) => return Err(unauthorized("missing bearer token", None)),
+            Err(()) => {
+                return Err(unauthorized(
+                    "malformed bearer token",
+                    Some("invalid_request"),
+                ))
+            }
+        };
+
+        P::from_bearer(&token, &ctx.env)
+            .await?
+            .map(Self)
+            .ok_or_else(|| unauthorized("invalid bearer token", Some("invalid_token")))
+    }
+}
+
+impl<D, P: FromBasic> FromRequest<D> for BasicAuth<P> {
+    type Error = AuthError;
+
+    #[allow(clippy::future_not_send)]
+    async fn from_request(req: &Request, ctx: &RouteContext<D>) -> Result<Self, Self::Error> {
+        let unauthorized = |message: &str| {
+            let challenge = format!(r#"{}, charset="UTF-8""#, challenge(BASIC, P::REALM, None));
+            AuthError::unauthorized(message, challenge)
+        };
+        let credentials = match read(req, Location::Authorization, BASIC) {
+            Ok(Some(encoded)) => BasicCredentials::decode(&encoded)
+                .ok_or_else(|| unauthorized("malformed credentials"))?,
+            Ok(None) => return Err(unauthorized("missing credentials")),
+            Err(()) => return Err(unauthorized("malformed credentials")),
+        };
+
+        P::from_basic(&credentials, &ctx.env)
+            .await?
+            .map(Self)
+            .ok_or_else(|| unauthorized("invalid credentials"))
+    }
+}
+
+impl<D, P: FromApiKey> FromRequest<D> for ApiKey<P> {
+    type Error = AuthError;
+
+    #[allow(clippy::future_not_send)]
+    async fn from_request(req: &Request, ctx: &RouteContext<D>) -> Result<Self, Self::Error> {
+        let unauthorized =
+            |message: &str| AuthError::unauthorized(message, challenge(API_KEY, P::REALM, None));
+        let key = match read(req, P::LOCATION, "") {
+            Ok(Some(key)) => key,
+            Ok(None) => return Err(unauthorized("missing api key")),
+            Err(()) => return Err(unauthorized("malformed api key")),
+        };
+
+        P::from_api_key(&key, &ctx.env)
+            .await?
+            .map(Self)
+            .ok_or_else(|| unauthorized("invalid api key"))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{challenge, constant_time_eq, cookie, strip_scheme, AuthError, BasicCredentials};
+    use crate::http::{ResponseError, StatusCode};
+    use crate::ErrorCause;
+
+    #[test]
+    fn scheme() {
+        assert_eq!(strip_scheme("Bearer abc.def", "Bearer"), Some("abc.def"));
+        assert_eq!(strip_scheme("bearer  abc ", "Bearer"), Some("abc"));
+        assert_eq!(strip_scheme("Basic abc", "Bearer"), None);
+        assert_eq!(strip_scheme("Bearer", "Bearer"), None);
+    }
+
+    #[test]
+    fn cookies() {
+        let header = r#"theme=dark; session="s3cr3t"; lang=en"#;
+
+        assert_eq!(cookie(header, "session").as_deref(), Some("s3cr3t"));
+        assert_eq!(cookie(header, "lang").as_deref(), Some("en"));
+        assert_eq!(cookie(header, "sess"), None);
+    }
+
+    #[test]
+    fn constant_time() {
+        assert!(constant_time_eq(b"s3cr3t", b"s3cr3t"));
+        assert!(!constant_time_eq(b"s3cr3t", b"s3cr3T"));
+        assert!(!constant_time_eq(b"s3cr3t", b"s3cr3"));
+        assert!(constant_time_eq(b"", b""));
+    }
+
+    #[test]
+    fn basic() {
+        // admin:pa:ss
+        let credentials = BasicCredentials::decode("YWRtaW46cGE6c3M=").unwrap();
+
+        assert_eq!(credentials.user_id(), "admin");
+        assert_eq!(credentials.password(), "pa:ss");
+        assert!(!format!("{credentials:?}").contains("pa:ss"));
+        // no `:`
+        assert!(BasicCredentials::decode("YWRtaW4=").is_none());
+        assert!(BasicCredentials::decode("not base64!").is_none());
+    }
+
+    #[test]
+    fn challenges() {
+        let err = AuthError::unauthorized(
+            "invalid bearer token",
+            challenge("Bearer", "api", Some("invalid_token")),
+        );
+
+        assert_eq!(err.status_code(), StatusCode::UNAUTHORIZED);
+        assert!(matches!(err.cause(), ErrorCause::Auth));
+        assert_eq!(
+            err.challenge(),
+            Some(r#"Bearer realm="api", error="invalid_token""#)
+        );
+        assert_eq!(
+            challenge("Basic", r#"my "app""#, None),
+            r#"Basic realm="my \"app\"""#
+        );
+
+        let err = AuthError::from(worker::Error::RustError("kv".into()));
+        assert_eq!(err.status_code(), StatusCode::INTERNAL_SERVER_ERROR);
+        assert!(err.challenge().is_none());
+    }
+}
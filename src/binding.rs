@@ -0,0 +1,167 @@
+use crate::error::{Error, ErrorCause};
+use crate::extract::FromRequest;
+
+use core::fmt::{Debug, Display};
+use core::marker::PhantomData;
+use http::StatusCode;
+use std::ops::Deref;
+use worker::{Request, RouteContext};
+
+/// A marker for the name of an environment binding, declared in `wrangler.toml`.
+///
+/// Used to extract bindings with [`Secret`], [`Var`], [`Kv`], [`Bucket`] and [`D1`](crate::D1).
+///
+/// ```
+/// use worker::{Response, Result};
+/// use worker_route::{get, Binding, Kv, Secret};
+///
+/// struct ApiKey;
+///
+/// impl Binding for ApiKey {
+///     const NAME: &'static str = "API_KEY";
+/// }
+///
+/// struct Cache;
+///
+/// impl Binding for Cache {
+///     const NAME: &'static str = "CACHE";
+/// }
+///
+/// #[get("/cached")]
+/// async fn cached(api_key: Secret<ApiKey>, cache: Kv<Cache>) -> Result<Response> {
+///     let value = cache.get("key").text().await?;
+///     // rest code
+///     Response::empty()
+/// }
+/// ```
+pub trait Binding {
+    /// The name of the binding.
+    const NAME: &'static str;
+}
+
+pub fn missing<B: Binding>(err: &worker::Error) -> Error {
+    Error::new(
+        format!("failed to get binding `{}`: {err}", B::NAME),
+        StatusCode::INTERNAL_SERVER_ERROR,
+        ErrorCause::Binding,
+    )
+}
+
+macro_rules! impl_binding {
+    ($(#[$meta:meta])* $name:ident, $inner:ty, $get:ident) => {
+        $(#[$meta])*
+        ///
+        /// A `500 Internal Server Error` is returned if the binding is missing.
+        pub struct $name<B: Binding>($inner, PhantomData<B>);
+
+        impl<B: Binding> $name<B> {
+            #[allow(clippy::missing_const_for_fn)]
+            /// Acess the owned binding
+            pub fn into_inner(self) -> $inner {
+                self.0
+            }
+        }
+
+        impl<D, B: Binding> FromRequest<D> for $name<B> {
+            type Error = Error;
+
+            #[allow(clippy::future_not_send)]
+            async fn from_request(_: &Request, ctx: &RouteContext<D>) -> Result<Self, Self::Error> {
+                match ctx.env.$get(B::NAME) {
+                    Ok(binding) => Ok(Self(binding, PhantomData)),
+                    Err(err) => Err(missing::<B>(&err)),
+                }
+            }
+
+            fn status_codes() -> Vec<StatusCode> {
+                vec![StatusCode::INTERNAL_SERVER_ERROR]
+            }
+        }
+
+        impl<B: Binding> Deref for $name<B> {
+            type Target = $inner;
+
+            fn deref(&self) -> &Self::Target {
+                &self.0
+            }
+        }
+
+        impl<B: Binding> AsRef<$inner> for $name<B> {
+            fn as_ref(&self) -> &$inner {
+                &self.0
+            }
+        }
+
+        impl<B: Binding> Debug for $name<B> {
+            fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+                f.debug_tuple(stringify!($name)).field(&B::NAME).finish()
+            }
+        }
+    };
+}
+
+impl_binding!(
+    /// Extract a secret from the environment, see [`Binding`].
+    Secret,
+    worker::Secret,
+    secret
+);
+impl_binding!(
+    /// Extract a variable from the environment, see [`Binding`].
+    Var,
+    worker::Var,
+    var
+);
+impl_binding!(
+    /// Extract a KV namespace from the environment, see [`Binding`].
+    Kv,
+    worker::kv::KvStore,
+    kv
+);
+impl_binding!(
+    /// Extract a R2 bucket from the environment, see [`Binding`].
+    Bucket,
+    worker::Bucket,
+    bucket
+);
+#[cfg(feature = "d1")]
+impl_binding!(
+    /// Extract a D1 database from the environment, see [`Binding`].
+    #[cfg_attr(docsrs, doc(cfg(feature = "d1")))]
+    D1,
+    worker::d1::D1Database,
+    d1
+);
+
+impl<B: Binding> Display for Secret<B> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str(&self.0.to_string())
+    }
+}
+
+impl<B: Binding> Display for Var<B> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str(&self.0.to_string())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{missing, Binding};
+    use crate::{http::StatusCode, ErrorCause, ResponseError};
+
+    struct ApiKey;
+
+    impl Binding for ApiKey {
+        const NAME: &'static str = "API_KEY";
+    }
+
+    #[test]
+    fn missing_binding() {
+        let err = missing::<ApiKey>(&worker::Error::RustError("not found".into()));
+
+        assert_eq!(err.status_code(), StatusCode::INTERNAL_SERVER_ERROR);
+        assert!(matches!(err.cause(), ErrorCause::Binding));
+        assert!(err.description().contains("API_KEY"));
+    }
+}
@@ -0,0 +1,152 @@
+use crate::error::Error;
+use crate::route::Params;
+
+use percent_encoding::percent_decode_str;
+use regex_lite::Regex;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::str::FromStr;
+
+#[doc(hidden)]
+/// Used for code generation, not for public usage.
+///
+/// The constraint of a path parameter, eg: `:age<u8>` or `:id<[0-9a-f]{8}>`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Constraint {
+    Bool,
+    Char,
+    I8,
+    I16,
+    I32,
+    I64,
+    I128,
+    Isize,
+    U8,
+    U16,
+    U32,
+    U64,
+    U128,
+    Usize,
+    F32,
+    F64,
+    /// Validated by the route macros, the whole value must match.
+    Regex(&'static str),
+}
+
+thread_local! {
+    // compiled once per pattern, a worker handles its requests on a single thread
+    static REGEXES: RefCell<HashMap<&'static str, Regex>> = RefCell::new(HashMap::new());
+}
+
+fn parses<T: FromStr>(value: &str) -> bool {
+    value.parse::<T>().is_ok()
+}
+
+impl Constraint {
+    /// Returns `true` if the percent-decoded `value` satisfies the constraint.
+    ///
+    /// # Panics
+    /// If the regex is invalid, which the route macros reject at compile time.
+    pub fn matches(&self, value: &str) -> bool {
+        let Ok(value) = percent_decode_str(value).decode_utf8() else {
+            return false;
+        };
+        match self {
+            Self::Bool => parses::<bool>(&value),
+            Self::Char => parses::<char>(&value),
+            Self::I8 => parses::<i8>(&value),
+            Self::I16 => parses::<i16>(&value),
+            Self::I32 => parses::<i32>(&value),
+            Self::I64 => parses::<i64>(&value),
+            Self::I128 => parses::<i128>(&value),
+            Self::Isize => parses::<isize>(&value),
+            Self::U8 => parses::<u8>(&value),
+            Self::U16 => parses::<u16>(&value),
+            Self::U32 => parses::<u32>(&value),
+            Self::U64 => parses::<u64>(&value),
+            Self::U128 => parses::<u128>(&value),
+            Self::Usize => parses::<usize>(&value),
+            Self::F32 => parses::<f32>(&value),
+            Self::F64 => parses::<f64>(&value),
+            Self::Regex(pattern) => REGEXES.with(|v| {
+                v.borrow_mut()
+                    .entry(pattern)
+                    .or_insert_with(|| {
+                        Regex::new(&format!("^(?:{pattern})$"))
+                            .expect("constraints are validated by the route macros")
+                    })
+                    .is_match(&value)
+            }),
+        }
+    }
+}
+
+#[doc(hidden)]
+/// Used for code generation, not for public usage.
+///
+/// Returns a `404 Not Found` error if a parameter doesn't satisfy its constraint,
+/// as the route doesn't match the request's path.
+pub fn constrain<D: Params>(
+    path: &str,
+    ctx: &D,
+    constraints: &[(&str, Constraint)],
+) -> Result<(), Error> {
+    let matches = constraints.iter().all(|(name, constraint)| {
+        ctx.param_(name)
+            .is_some_and(|value| constraint.matches(value))
+    });
+    if matches {
+        return Ok(());
+    }
+
+    Err(Error::not_found(path))
+}
+
+#[cfg(test)]
+mod test {
+    use super::{constrain, Constraint};
+    use crate::{http::StatusCode, route::Params, ResponseError};
+    use std::collections::HashMap;
+
+    struct CustomParam(HashMap<String, String>);
+
+    impl Params for CustomParam {
+        fn param_(&self, key: &str) -> Option<&String> {
+            self.0.get(key)
+        }
+    }
+
+    #[test]
+    fn matches() {
+        assert!(Constraint::U8.matches("255"));
+        assert!(!Constraint::U8.matches("256"));
+        assert!(!Constraint::U8.matches("abc"));
+        assert!(Constraint::Bool.matches("true"));
+        assert!(Constraint::F64.matches("1.5"));
+        assert!(Constraint::Char.matches("%C3%A9"));
+
+        let hex = Constraint::Regex("[0-9a-f]{8}");
+        assert!(hex.matches("deadbeef"));
+        assert!(!hex.matches("deadbeef0"));
+        assert!(!hex.matches("xdeadbeef"));
+    }
+
+    #[test]
+    fn not_found() {
+        let ctx = CustomParam(
+            [("name", "bob"), ("age", "abc")]
+                .iter()
+                .map(|(k, v)| ((*k).to_owned(), (*v).to_owned()))
+                .collect(),
+        );
+
+        assert!(constrain(
+            "/person/bob/abc",
+            &ctx,
+            &[("name", Constraint::Regex("[a-z]+"))]
+        )
+        .is_ok());
+        let err = constrain("/person/bob/abc", &ctx, &[("age", Constraint::U8)]).unwrap_err();
+        assert_eq!(err.status_code(), StatusCode::NOT_FOUND);
+    }
+}
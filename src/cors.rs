@@ -0,0 +1,439 @@
+use crate::error::{Error, ErrorCause};
+use crate::http::{HttpRequest, HttpResponse, ResponseError};
+use crate::middleware::Middleware;
+
+use http::header::{
+    HeaderName, ACCESS_CONTROL_ALLOW_CREDENTIALS, ACCESS_CONTROL_ALLOW_HEADERS,
+    ACCESS_CONTROL_ALLOW_METHODS, ACCESS_CONTROL_ALLOW_ORIGIN, ACCESS_CONTROL_EXPOSE_HEADERS,
+    ACCESS_CONTROL_MAX_AGE, ACCESS_CONTROL_REQUEST_HEADERS, ACCESS_CONTROL_REQUEST_METHOD, ORIGIN,
+    VARY,
+};
+use http::StatusCode;
+use worker::{Method, Request, Response, RouteContext};
+
+// an origin of the allowlist
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum AllowOrigin {
+    // `*`
+    Any,
+    // `https://example.com`
+    Exact(String),
+    // `https://*.example.com`, the scheme and `.example.com` with its port
+    Subdomain(String, String),
+}
+
+impl AllowOrigin {
+    fn new(origin: &str) -> Self {
+        let origin = origin.trim().trim_end_matches('/').to_ascii_lowercase();
+        if origin == "*" {
+            return Self::Any;
+        }
+        match origin.split_once("://*.") {
+            Some((scheme, host)) => Self::Subdomain(format!("{scheme}://"), format!(".{host}")),
+            None => Self::Exact(origin),
+        }
+    }
+
+    fn matches(&self, origin: &str) -> bool {
+        match self {
+            Self::Any => true,
+            Self::Exact(exact) => exact.eq_ignore_ascii_case(origin),
+            Self::Subdomain(scheme, host) => {
+                let origin = origin.to_ascii_lowercase();
+                origin
+                    .strip_prefix(scheme.as_str())
+                    .and_then(|v| v.strip_suffix(host.as_str()))
+                    .is_some_and(|subdomain| {
+                        !subdomain.is_empty()
+                            && subdomain.split('.').all(|label| {
+                                !label.is_empty()
+                                    && label.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+                            })
+                    })
+            }
+        }
+    }
+}
+
+/// A CORS policy, applied as a [`Middleware`] to a route with `wrap = [..]`
+/// or to every route of an app with [`App::wrap`](crate::App::wrap).
+///
+/// - The `Origin` is matched against an allowlist of exact origins, eg: `https://example.com`,
+///   and subdomain wildcards, eg: `https://*.example.com`, which doesn't match `https://example.com`.
+/// - `Access-Control-Allow-Origin` echoes the request's origin, unless any origin is allowed
+///   without credentials, `Vary: Origin` is then added to the response.
+/// - A preflight's `Access-Control-Request-Method` and `Access-Control-Request-Headers`
+///   are checked against the policy, a disallowed preflight is answered with `403 Forbidden`.
+///
+/// A preflight only reaches the policy of a route if an `OPTIONS` handler is registered for its path,
+/// with the `preflight` attribute of the route. An [`App`](crate::App) runs its global policy
+/// for the `OPTIONS` requests of every configured path, a preflight is answered by the policy
+/// and any other request with `204 No Content`.
+///
+/// # Examples
+/// ```
+/// use worker::{Method, Result};
+/// use worker_route::{get, App, Configure, CorsPolicy};
+///
+/// fn cors() -> CorsPolicy {
+///     CorsPolicy::new()
+///         .with_origins(["https://example.com", "https://*.example.com"])
+///         .with_methods([Method::Get, Method::Post])
+///         .with_allowed_headers(["content-type", "authorization"])
+///         .with_credentials(true)
+///         .with_max_age(600)
+/// }
+///
+/// // `OPTIONS /users` runs the route's middlewares
+/// #[get("/users", wrap = [cors()], preflight)]
+/// async fn users() -> Result<String> {
+///     Ok("users".to_owned())
+/// }
+///
+/// #[get("/health")]
+/// async fn health() -> Result<String> {
+///     Ok("ok".to_owned())
+/// }
+///
+/// fn init_routes(app: App<'static, ()>) -> App<'static, ()> {
+///     // or globally, `OPTIONS /health` is answered by the app
+///     app.wrap(cors()).configure(users).configure(health)
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct CorsPolicy {
+    origins: Vec<AllowOrigin>,
+    methods: Vec<String>,
+    allowed_headers: Vec<String>,
+    exposed_headers: Vec<String>,
+    credentials: bool,
+    max_age: Option<u32>,
+}
+
+impl Default for CorsPolicy {
+    fn default() -> Self {
+        Self {
+            origins: Vec::new(),
+            methods: vec!["GET".to_owned(), "HEAD".to_owned(), "POST".to_owned()],
+            allowed_headers: Vec::new(),
+            exposed_headers: Vec::new(),
+            credentials: false,
+            max_age: None,
+        }
+    }
+}
+
+fn lowercase<I: IntoIterator<Item = S>, S: AsRef<str>>(values: I) -> impl Iterator<Item = String> {
+    values
+        .into_iter()
+        .map(|v| v.as_ref().trim().to_ascii_lowercase())
+}
+
+impl CorsPolicy {
+    /// Creates a policy allowing no origin, and the `GET`, `HEAD` and `POST` methods.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Allows the origins, `*` for any origin or `https://*.example.com` for any subdomain of `example.com`.
+    pub fn with_origins<I: IntoIterator<Item = S>, S: AsRef<str>>(mut self, origins: I) -> Self {
+        self.origins
+            .extend(origins.into_iter().map(|v| AllowOrigin::new(v.as_ref())));
+        self
+    }
+
+    /// Sets the methods allowed by a preflight, eg: `[Method::Get, Method::Put]` or `["PURGE"]`.
+    pub fn with_methods<I: IntoIterator<Item = M>, M: AsRef<str>>(mut self, methods: I) -> Self {
+        self.methods = methods
+            .into_iter()
+            .map(|v| v.as_ref().to_ascii_uppercase())
+            .collect();
+        self
+    }
+
+    /// Allows the request headers of a preflight, `*` for any header.
+    pub fn with_allowed_headers<I: IntoIterator<Item = S>, S: AsRef<str>>(
+        mut self,
+        headers: I,
+    ) -> Self {
+        self.allowed_headers.extend(lowercase(headers));
+        self
+    }
+
+    /// Exposes the response headers to the browser, with `Access-Control-Expose-Headers`.
+    pub fn with_exposed_headers<I: IntoIterator<Item = S>, S: AsRef<str>>(
+        mut self,
+        headers: I,
+    ) -> Self {
+        self.exposed_headers.extend(lowercase(headers));
+        self
+    }
+
+    /// Allows the requests with credentials, the origin is then always echoed.
+    pub fn with_credentials(mut self, credentials: bool) -> Self {
+        self.credentials = credentials;
+        self
+    }
+
+    /// Sets how long a preflight may be cached, in seconds.
+    pub fn with_max_age(mut self, max_age: u32) -> Self {
+        self.max_age = Some(max_age);
+        self
+    }
+
+    /// Returns `true` if the origin is allowed.
+    pub fn allows_origin(&self, origin: &str) -> bool {
+        self.origins.iter().any(|v| v.matches(origin))
+    }
+
+    // the response is the same for every origin, `Vary: Origin` is left out
+    fn is_static(&self) -> bool {
+        !self.credentials && self.origins.contains(&AllowOrigin::Any)
+    }
+
+    fn allow_origin(&self, origin: &str) -> Option<String> {
+        if self.is_static() {
+            return Some("*".to_owned());
+        }
+
+        self.allows_origin(origin).then(|| origin.to_owned())
+    }
+
+    fn allows_header(&self, header: &str) -> bool {
+        self.allowed_headers
+            .iter()
+            .any(|v| v == "*" || v.eq_ignore_ascii_case(header))
+    }
+
+    fn credentials(&self, headers: &mut Vec<(HeaderName, String)>) {
+        if self.credentials {
+            headers.push((ACCESS_CONTROL_ALLOW_CREDENTIALS, "true".to_owned()));
+        }
+    }
+
+    /// The headers of an actual request's response, from its `Origin`.
+    fn headers(&self, origin: Option<&str>) -> Vec<(HeaderName, String)> {
+        let mut headers = Vec::new();
+        if !self.is_static() {
+            headers.push((VARY, ORIGIN.as_str().to_owned()));
+        }
+        let Some(allow_origin) = origin.and_then(|v| self.allow_origin(v)) else {
+            return headers;
+        };
+        headers.push((ACCESS_CONTROL_ALLOW_ORIGIN, allow_origin));
+        self.credentials(&mut headers);
+        if !self.exposed_headers.is_empty() {
+            headers.push((
+                ACCESS_CONTROL_EXPOSE_HEADERS,
+                self.exposed_headers.join(", "),
+            ));
+        }
+
+        headers
+    }
+
+    /// The headers of a preflight's response, or the reason it's rejected.
+    fn preflight(
+        &self,
+        origin: &str,
+        method: &str,
+        request_headers: Option<&str>,
+    ) -> Result<Vec<(HeaderName, String)>, Error> {
+        let Some(allow_origin) = self.allow_origin(origin) else {
+            return Err(forbidden(format!("origin `{origin}` is not allowed")));
+        };
+        let method = method.trim();
+        if !self.methods.iter().any(|v| v.eq_ignore_ascii_case(method)) {
+            return Err(forbidden(format!("method `{method}` is not allowed")));
+        }
+        let request_headers = request_headers
+            .into_iter()
+            .flat_map(|v| v.split(','))
+            .map(|v| v.trim().to_ascii_lowercase())
+            .filter(|v| !v.is_empty())
+            .collect::<Vec<_>>();
+        if let Some(header) = request_headers.iter().find(|v| !self.allows_header(v)) {
+            return Err(forbidden(format!("header `{header}` is not allowed")));
+        }
+
+        let mut headers = vec![
+            preflight_vary(),
+            (ACCESS_CONTROL_ALLOW_ORIGIN, allow_origin),
+            (ACCESS_CONTROL_ALLOW_METHODS, self.methods.join(", ")),
+        ];
+        if !request_headers.is_empty() {
+            headers.push((ACCESS_CONTROL_ALLOW_HEADERS, request_headers.join(", ")));
+        }
+        self.credentials(&mut headers);
+        if let Some(max_age) = self.max_age {
+            headers.push((ACCESS_CONTROL_MAX_AGE, max_age.to_string()));
+        }
+
+        Ok(headers)
+    }
+}
+
+// the answer to a preflight, allowed or rejected, depends on these headers of the request
+fn preflight_vary() -> (HeaderName, String) {
+    (
+        VARY,
+        [
+            ORIGIN.as_str(),
+            ACCESS_CONTROL_REQUEST_METHOD.as_str(),
+            ACCESS_CONTROL_REQUEST_HEADERS.as_str(),
+        ]
+        .join(", "),
+    )
+}
+
+fn forbidden(message: String) -> Error {
+    Error::new(message, StatusCode::FORBIDDEN, ErrorCause::Cors)
+}
+
+// `Vary` may already be set by the handler, the others are replaced
+fn set_headers(res: &mut Response, headers: Vec<(HeaderName, String)>) -> worker::Result<()> {
+    for (name, value) in headers {
+        if name == VARY {
+            res.headers_mut().append(name.as_str(), &value)?;
+        } else {
+            res.headers_mut().set(name.as_str(), &value)?;
+        }
+    }
+
+    Ok(())
+}
+
+impl<D> Middleware<D> for CorsPolicy {
+    /// Answers a preflight, `204 No Content` if it's allowed or `403 Forbidden`.
+    #[allow(clippy::future_not_send)]
+    async fn before(
+        &self,
+        req: &mut Request,
+        _: &RouteContext<D>,
+    ) -> Result<Option<HttpResponse>, Error> {
+        let headers = req.headers();
+        let (Some(origin), Some(method)) = (
+            headers.get(ORIGIN.as_str())?,
+            headers.get(ACCESS_CONTROL_REQUEST_METHOD.as_str())?,
+        ) else {
+            return Ok(None);
+        };
+        if req.method() != Method::Options {
+            return Ok(None);
+        }
+
+        let request_headers = headers.get(ACCESS_CONTROL_REQUEST_HEADERS.as_str())?;
+        let headers = match self.preflight(&origin, &method, request_headers.as_deref()) {
+            Ok(headers) => headers,
+            // a cache mustn't serve the rejection to an allowed origin
+            Err(err) => {
+                let mut res = err.error_response(HttpRequest::from(&*req)).into_res();
+                set_headers(&mut res, vec![preflight_vary()])?;
+                return Ok(Some(HttpResponse::from_response(res)));
+            }
+        };
+        let mut res = Response::empty()?.with_status(StatusCode::NO_CONTENT.as_u16());
+        set_headers(&mut res, headers)?;
+        Ok(Some(HttpResponse::from_response(res)))
+    }
+
+    #[allow(clippy::future_not_send)]
+    async fn after(&self, req: &HttpRequest, mut res: Response) -> Result<Response, Error> {
+        let origin = req.headers().get(&ORIGIN);
+        set_headers(&mut res, self.headers(origin.as_deref()))?;
+        Ok(res)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{preflight_vary, CorsPolicy};
+    use crate::http::ResponseError;
+    use http::header::{
+        HeaderName, ACCESS_CONTROL_ALLOW_CREDENTIALS, ACCESS_CONTROL_ALLOW_HEADERS,
+        ACCESS_CONTROL_ALLOW_ORIGIN, VARY,
+    };
+    use http::StatusCode;
+
+    fn get<'a>(headers: &'a [(HeaderName, String)], name: &HeaderName) -> Option<&'a str> {
+        headers.iter().find(|v| v.0 == name).map(|v| v.1.as_str())
+    }
+
+    #[test]
+    fn origins() {
+        let policy =
+            CorsPolicy::new().with_origins(["https://Example.com/", "https://*.example.com:8443"]);
+
+        assert!(policy.allows_origin("https://example.com"));
+        assert!(policy.allows_origin("https://api.example.com:8443"));
+        assert!(policy.allows_origin("https://a.b.example.com:8443"));
+        assert!(!policy.allows_origin("https://api.example.com"));
+        assert!(!policy.allows_origin("https://example.com:8443"));
+        assert!(!policy.allows_origin("http://example.com"));
+        assert!(!policy.allows_origin("https://evil.com/.example.com:8443"));
+        assert!(!policy.allows_origin("https://..example.com:8443"));
+    }
+
+    #[test]
+    fn actual() {
+        let any = CorsPolicy::new().with_origins(["*"]);
+        let headers = any.headers(Some("https://a.com"));
+        assert_eq!(get(&headers, &ACCESS_CONTROL_ALLOW_ORIGIN), Some("*"));
+        assert_eq!(get(&headers, &VARY), None);
+
+        let credentials = any.with_credentials(true);
+        let headers = credentials.headers(Some("https://a.com"));
+        assert_eq!(
+            get(&headers, &ACCESS_CONTROL_ALLOW_ORIGIN),
+            Some("https://a.com")
+        );
+        assert_eq!(
+            get(&headers, &ACCESS_CONTROL_ALLOW_CREDENTIALS),
+            Some("true")
+        );
+        assert_eq!(get(&headers, &VARY), Some("origin"));
+
+        let headers = CorsPolicy::new().headers(Some("https://a.com"));
+        assert_eq!(get(&headers, &ACCESS_CONTROL_ALLOW_ORIGIN), None);
+        assert_eq!(get(&headers, &VARY), Some("origin"));
+    }
+
+    #[test]
+    fn preflight() {
+        let policy = CorsPolicy::new()
+            .with_origins(["https://*.example.com"])
+            .with_methods(["get", "PURGE"])
+            .with_allowed_headers(["Content-Type"]);
+
+        let headers = policy
+            .preflight("https://api.example.com", "PURGE", Some("content-type"))
+            .unwrap();
+        assert_eq!(
+            get(&headers, &ACCESS_CONTROL_ALLOW_ORIGIN),
+            Some("https://api.example.com")
+        );
+        assert_eq!(
+            get(&headers, &ACCESS_CONTROL_ALLOW_HEADERS),
+            Some("content-type")
+        );
+        // the same as a rejection's
+        assert_eq!(
+            get(&headers, &VARY),
+            Some("origin, access-control-request-method, access-control-request-headers")
+        );
+        assert_eq!(get(&[preflight_vary()], &VARY), get(&headers, &VARY));
+
+        for (origin, method, headers) in [
+            ("https://example.com", "GET", None),
+            ("https://api.example.com", "PUT", None),
+            (
+                "https://api.example.com",
+                "GET",
+                Some("content-type, x-api-key"),
+            ),
+        ] {
+            let err = policy.preflight(origin, method, headers).unwrap_err();
+            assert_eq!(err.status_code(), StatusCode::FORBIDDEN);
+        }
+    }
+}
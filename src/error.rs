@@ -1,6 +1,7 @@
 use crate::http::{
     ContentType, ResponseError, {HttpRequest, HttpResponse, ResponseBuilder},
 };
+use core::convert::Infallible;
 use core::fmt::{Debug, Display};
 use http::{
     header::{ToStrError, ACCEPT, CONTENT_TYPE},
@@ -20,15 +21,36 @@ pub struct Error {
 
 /// All possible Error variants that may occur when working with [`worker_route`](crate).
 #[derive(Debug)]
+#[non_exhaustive]
 pub enum ErrorCause {
     /// Errors occured from [`worker::Error`](https://docs.rs/worker/latest/worker/enum.Error.html)
     Worker(worker::Error),
-    /// Errors occured from [`Query`](crate::Query)
+    /// Errors occured from [`Query`](crate::Query) or [`Form`](crate::Form)
     Query,
+    /// Errors occured from [`Path`](crate::Path)
+    Path,
+    /// Errors occured from [`Multipart`](crate::Multipart) or [`MultipartForm`](crate::MultipartForm)
+    Multipart,
     /// Errors occured from [`HttpHeaders`](crate::http::headers::HttpHeaders) operations
     Header,
     /// Errors occured from [`ResponseBuilder`](crate::http::ResponseBuilder)
     Json,
+    /// Errors occured from getting an environment binding (eg: [`Kv`](crate::Kv))
+    Binding,
+    /// Errors occured from reading or deserializing the request's body (eg: [`Json`](crate::Json))
+    Body,
+    /// Errors occured from routing the request (eg: method not allowed or not found)
+    Route,
+    /// Errors occured from a preflight rejected by a [`CorsPolicy`](crate::CorsPolicy)
+    Cors,
+    /// Errors occured from authenticating the request (eg: [`BearerAuth`](crate::BearerAuth))
+    Auth,
+    /// Errors occured from a request over the limit of a [`RateLimit`](crate::RateLimit)
+    RateLimit,
+    /// Errors occured from validating an extracted value with [`Valid`](crate::Valid)
+    #[cfg_attr(docsrs, doc(cfg(feature = "validate")))]
+    #[cfg(feature = "validate")]
+    Validation(validator::ValidationErrors),
 }
 
 impl Display for Error {
@@ -47,6 +69,26 @@ impl Error {
         }
     }
 
+    /// Creates a `404 Not Found` error for a path which no handler matches,
+    /// eg: from a [`fallback`](crate::fallback) handler.
+    pub fn not_found(path: &str) -> Self {
+        Self::new(
+            format!("no route matches `{path}`"),
+            StatusCode::NOT_FOUND,
+            ErrorCause::Route,
+        )
+    }
+
+    /// Creates a `500 Internal Server Error` for a body extractor run after the body was read,
+    /// eg: a [`Json`](crate::Json) extracted after a [`Multipart`](crate::Multipart).
+    pub(crate) fn body_used() -> Self {
+        Self::new(
+            "the body was already read, a handler can only have one body extractor".into(),
+            StatusCode::INTERNAL_SERVER_ERROR,
+            ErrorCause::Body,
+        )
+    }
+
     /// Returns the underlying error's occurrence
     pub fn cause(&self) -> &ErrorCause {
         &self.cause
@@ -62,11 +104,18 @@ impl Error {
     }
 
     pub(super) fn to_json(&self) -> Value {
-        json!({
+        #[allow(unused_mut)]
+        let mut json = json!({
             "message": self.message,
             "statusCode": self.status_code.as_u16(),
             "success": false
-        })
+        });
+        #[cfg(feature = "validate")]
+        if let ErrorCause::Validation(errors) = &self.cause {
+            json["fields"] = crate::valid::fields(errors).into();
+        }
+
+        json
     }
 }
 
@@ -84,6 +133,11 @@ impl ResponseError for Error {
     fn description(&self) -> String {
         self.message.clone()
     }
+
+    // an extractor lists its own, see `FromRequest::status_codes`
+    fn status_codes() -> Vec<StatusCode> {
+        vec![StatusCode::BAD_REQUEST, StatusCode::INTERNAL_SERVER_ERROR]
+    }
 }
 
 pub fn accept_json(req: &HttpRequest, headers: &mut Headers) {
@@ -104,6 +158,12 @@ impl From<Error> for worker::Error {
     }
 }
 
+impl From<Infallible> for Error {
+    fn from(err: Infallible) -> Self {
+        match err {}
+    }
+}
+
 impl From<serde_qs::Error> for Error {
     fn from(err: serde_qs::Error) -> Self {
         Self {
@@ -124,6 +184,24 @@ impl From<worker::Error> for Error {
     }
 }
 
+impl From<serde_json::Error> for Error {
+    fn from(err: serde_json::Error) -> Self {
+        use serde_json::error::Category;
+
+        let status_code = match err.classify() {
+            Category::Io => StatusCode::INTERNAL_SERVER_ERROR,
+            Category::Syntax | Category::Eof => StatusCode::BAD_REQUEST,
+            Category::Data => StatusCode::UNPROCESSABLE_ENTITY,
+        };
+
+        Self {
+            message: err.to_string(),
+            status_code,
+            cause: ErrorCause::Body,
+        }
+    }
+}
+
 impl From<serde::de::value::Error> for Error {
     fn from(err: serde::de::value::Error) -> Self {
         Self {
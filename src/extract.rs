@@ -0,0 +1,106 @@
+use crate::http::ResponseError;
+
+use core::convert::Infallible;
+use core::future::Future;
+use http::StatusCode;
+use worker::{Request, RouteContext};
+
+/// Types that can be extracted from a request, used as a handler's argument.
+///
+/// Every argument of a handler, other than [`worker::Request`](https://docs.rs/worker/latest/worker/struct.Request.html)
+/// and [`worker::RouteContext`](https://docs.rs/worker/latest/worker/struct.RouteContext.html),
+/// is extracted with this trait. Arguments can be declared in any count and order.
+///
+/// If the extraction fails, the error's [`ResponseError::error_response`] is returned
+/// and the handler is not called.
+///
+/// # Examples
+/// ```
+/// use worker::{Request, Response, Result, RouteContext};
+/// use worker_route::{get, FromRequest};
+///
+/// struct ApiVersion(String);
+///
+/// impl<D> FromRequest<D> for ApiVersion {
+///     type Error = worker::Error;
+///
+///     async fn from_request(req: &Request, _: &RouteContext<D>) -> Result<Self> {
+///         match req.headers().get("x-api-version")? {
+///             Some(version) => Ok(Self(version)),
+///             None => Err(worker::Error::Json(("missing api version".into(), 400))),
+///         }
+///     }
+/// }
+///
+/// #[get("/version")]
+/// async fn api_version(_: RouteContext<()>, version: ApiVersion, _: Request) -> Result<String> {
+///     Ok(version.0)
+/// }
+/// ```
+pub trait FromRequest<D>: Sized {
+    /// The error returned when the extraction fails.
+    type Error: ResponseError + 'static;
+
+    /// Extract `Self` from the request and its route context.
+    fn from_request(
+        req: &Request,
+        ctx: &RouteContext<D>,
+    ) -> impl Future<Output = Result<Self, Self::Error>>;
+
+    #[doc(hidden)]
+    /// Used for code generation, the route's `pattern` is supplied by the macro attribute.
+    fn from_route(
+        req: &Request,
+        ctx: &RouteContext<D>,
+        pattern: &'static str,
+    ) -> impl Future<Output = Result<Self, Self::Error>> {
+        let _ = pattern;
+        Self::from_request(req, ctx)
+    }
+
+    /// Returns every status code a failed extraction responds with,
+    /// documented as the error responses of a handler with the `openapi` feature.
+    ///
+    /// Defaults to the [`status_codes`](ResponseError::status_codes) of [`Self::Error`](FromRequest::Error).
+    fn status_codes() -> Vec<StatusCode> {
+        Self::Error::status_codes()
+    }
+}
+
+/// Optional extraction, `None` is returned if `T` fails to be extracted.
+impl<D, T: FromRequest<D>> FromRequest<D> for Option<T> {
+    type Error = Infallible;
+
+    #[allow(clippy::future_not_send)]
+    async fn from_request(req: &Request, ctx: &RouteContext<D>) -> Result<Self, Self::Error> {
+        Ok(T::from_request(req, ctx).await.ok())
+    }
+
+    #[allow(clippy::future_not_send)]
+    async fn from_route(
+        req: &Request,
+        ctx: &RouteContext<D>,
+        pattern: &'static str,
+    ) -> Result<Self, Self::Error> {
+        Ok(T::from_route(req, ctx, pattern).await.ok())
+    }
+}
+
+/// Fallible extraction, the error is handed over to the handler instead of being responded.
+impl<D, T: FromRequest<D>> FromRequest<D> for Result<T, T::Error> {
+    type Error = Infallible;
+
+    #[allow(clippy::future_not_send)]
+    async fn from_request(req: &Request, ctx: &RouteContext<D>) -> Result<Self, Self::Error> {
+        Ok(T::from_request(req, ctx).await)
+    }
+
+    #[allow(clippy::future_not_send)]
+    async fn from_route(
+        req: &Request,
+        ctx: &RouteContext<D>,
+        pattern: &'static str,
+    ) -> Result<Self, Self::Error> {
+        Ok(T::from_route(req, ctx, pattern).await)
+    }
+}
@@ -0,0 +1,213 @@
+use crate::error::{Error, ErrorCause};
+use crate::extract::FromRequest;
+use crate::http::ContentType;
+
+use core::fmt::Display;
+use http::header::CONTENT_TYPE;
+use http::StatusCode;
+use mime::Mime;
+use serde::de::DeserializeOwned;
+use std::ops::{Deref, DerefMut};
+use worker::{Request, RouteContext};
+
+/// Extract typed information from an urlencoded request's body.
+///
+/// To extract typed data from the request's body, `T` must implement
+/// the [`DeserializeOwned`](serde::de::DeserializeOwned) trait.
+///
+/// The request must have a `Content-Type: application/x-www-form-urlencoded` header,
+/// otherwise a `415 Unsupported Media Type` error is returned.
+///
+/// ```
+/// use serde::{Deserialize, Serialize};
+/// use worker::{Response, Result, RouteContext};
+/// use worker_route::{post, Form};
+///
+/// #[derive(Debug, Serialize, Deserialize)]
+/// struct Login {
+///     username: String,
+///     password: String,
+/// }
+///
+/// #[post("/login")]
+/// async fn login(req: Form<Login>, _: RouteContext<()>) -> Result<Response> {
+///     let Login { username, password } = req.into_inner();
+///     // rest code
+///     Response::empty()
+/// }
+/// ```
+///
+/// # Notes
+/// `Form<T>` reads the body from a clone of the request,
+/// the `Request` is still readable if the handler takes it as well.
+#[derive(Debug, Clone)]
+pub struct Form<T>(T);
+
+impl<T> Form<T> {
+    #[allow(clippy::missing_const_for_fn)]
+    /// Acess the owned `T`
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+fn is_form(content_type: Option<&str>) -> bool {
+    content_type
+        .and_then(|v| v.parse::<Mime>().ok())
+        .is_some_and(|mime| mime.essence_str() == ContentType::form_url_encoded().as_str())
+}
+
+impl<T: DeserializeOwned> Form<T> {
+    fn new(content_type: Option<&str>, body: &str) -> Result<Self, Error> {
+        if !is_form(content_type) {
+            return Err(Error::new(
+                format!(
+                    "expected `{CONTENT_TYPE}: {}`",
+                    ContentType::form_url_encoded()
+                ),
+                StatusCode::UNSUPPORTED_MEDIA_TYPE,
+                ErrorCause::Query,
+            ));
+        }
+
+        Ok(Self(serde_qs::from_str::<T>(body)?))
+    }
+
+    /// Deserialize the given `T` from the request's urlencoded body.
+    ///
+    /// ```
+    /// use serde::{Deserialize, Serialize};
+    /// use worker::{console_log, Request, Response, Result, RouteContext};
+    /// use worker_route::{post, Form};
+    ///
+    /// #[derive(Debug, Deserialize, Serialize)]
+    /// struct Login {
+    ///     username: String,
+    ///     password: String,
+    /// }
+    ///
+    /// #[post("/login")]
+    /// async fn login(mut req: Request, _: RouteContext<()>) -> Result<Response> {
+    ///     let login = Form::<Login>::from_body(&mut req).await;
+    ///     let Login { username, .. } = login.unwrap().into_inner();
+    ///     console_log!("username: {username}");
+    ///     Response::empty()
+    /// }
+    /// ```
+    ///
+    /// # Errors
+    /// - `415 Unsupported Media Type` if the `Content-Type` is not `application/x-www-form-urlencoded`.
+    /// - `400 Bad Request` if the body can't be read or does not match `T`.
+    #[allow(clippy::future_not_send)]
+    pub async fn from_body(req: &mut Request) -> Result<Self, Error> {
+        let content_type = req.headers().get(CONTENT_TYPE.as_str()).unwrap_or(None);
+        // check the content type first, there's no point reading the body otherwise
+        if !is_form(content_type.as_deref()) {
+            return Self::new(content_type.as_deref(), "");
+        }
+
+        match req.text().await {
+            Ok(body) => Self::new(content_type.as_deref(), &body),
+            Err(err) => Err(Error::new(
+                err.to_string(),
+                StatusCode::BAD_REQUEST,
+                ErrorCause::Query,
+            )),
+        }
+    }
+}
+
+impl<D, T: DeserializeOwned> FromRequest<D> for Form<T> {
+    type Error = Error;
+
+    #[allow(clippy::future_not_send)]
+    async fn from_request(req: &Request, _: &RouteContext<D>) -> Result<Self, Self::Error> {
+        if req.inner().body_used() {
+            return Err(Error::body_used());
+        }
+
+        Self::from_body(&mut req.clone()?).await
+    }
+
+    fn status_codes() -> Vec<StatusCode> {
+        vec![StatusCode::BAD_REQUEST, StatusCode::UNSUPPORTED_MEDIA_TYPE]
+    }
+}
+
+impl<T: Display> Display for Form<T> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl<T> Deref for Form<T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<T> DerefMut for Form<T> {
+    fn deref_mut(&mut self) -> &mut T {
+        &mut self.0
+    }
+}
+
+impl<T> AsRef<T> for Form<T> {
+    fn as_ref(&self) -> &T {
+        &self.0
+    }
+}
+
+impl<T> AsMut<T> for Form<T> {
+    fn as_mut(&mut self) -> &mut T {
+        &mut self.0
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{http::StatusCode, Form, ResponseError};
+    use serde::Deserialize;
+
+    const FORM: Option<&str> = Some("application/x-www-form-urlencoded");
+
+    #[allow(unused)]
+    #[derive(Deserialize, Debug)]
+    struct Login {
+        username: String,
+        remember: Option<bool>,
+    }
+
+    #[test]
+    fn form_ok() {
+        let login = Form::<Login>::new(FORM, "username=Foo%20Bar&remember=true").unwrap();
+
+        assert_eq!(login.username, "Foo Bar");
+        assert_eq!(login.remember, Some(true));
+    }
+
+    #[test]
+    fn charset_ok() {
+        let content_type = Some("application/x-www-form-urlencoded; charset=utf-8");
+
+        assert!(Form::<Login>::new(content_type, "username=Foo").is_ok());
+    }
+
+    #[test]
+    fn content_type_err() {
+        let missing = Form::<Login>::new(None, "username=Foo").unwrap_err();
+        let invalid = Form::<Login>::new(Some("application/json"), "username=Foo").unwrap_err();
+
+        assert_eq!(missing.status_code(), StatusCode::UNSUPPORTED_MEDIA_TYPE);
+        assert_eq!(invalid.status_code(), StatusCode::UNSUPPORTED_MEDIA_TYPE);
+    }
+
+    #[test]
+    fn data_err() {
+        let err = Form::<Login>::new(FORM, "remember=maybe").unwrap_err();
+
+        assert_eq!(err.status_code(), StatusCode::BAD_REQUEST);
+    }
+}
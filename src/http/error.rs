@@ -1,6 +1,7 @@
 use super::response::{HttpResponse, ResponseBuilder};
 use super::HttpRequest;
 use crate::error::accept_json;
+use core::convert::Infallible;
 use core::fmt;
 use http::header::ToStrError;
 use http::StatusCode;
@@ -17,6 +18,16 @@ pub trait ResponseError: fmt::Debug + fmt::Display {
     }
     /// Get the underlying error message.
     fn description(&self) -> String;
+    /// Returns every status code [`status_code`](ResponseError::status_code) may return,
+    /// documented as the error responses of a handler with the `openapi` feature.
+    ///
+    /// Defaults to 500 Internal Server Error.
+    fn status_codes() -> Vec<StatusCode>
+    where
+        Self: Sized,
+    {
+        vec![StatusCode::INTERNAL_SERVER_ERROR]
+    }
 }
 
 impl ResponseError for worker::Error {
@@ -56,6 +67,10 @@ impl ResponseError for worker::Error {
 
         self.to_string()
     }
+
+    fn status_codes() -> Vec<StatusCode> {
+        vec![StatusCode::BAD_REQUEST, StatusCode::INTERNAL_SERVER_ERROR]
+    }
 }
 
 impl ResponseError for serde::de::value::Error {
@@ -94,3 +109,19 @@ impl ResponseError for serde_qs::Error {
         }
     }
 }
+
+// a `&Infallible` can't be made, so it is never dereferenced
+#[allow(clippy::uninhabited_references)]
+impl ResponseError for Infallible {
+    fn error_response(&self, _: HttpRequest) -> HttpResponse {
+        match *self {}
+    }
+
+    fn description(&self) -> String {
+        match *self {}
+    }
+
+    fn status_codes() -> Vec<StatusCode> {
+        Vec::new()
+    }
+}
@@ -14,6 +14,7 @@ mod impl_trait;
 mod request;
 mod responder;
 mod response;
+mod typed_header;
 
 pub use body::Body;
 pub use content_type::ContentType;
@@ -24,6 +25,10 @@ pub use request::HttpRequest;
 pub(crate) use responder::InternalResponder;
 pub use responder::Responder;
 pub use response::{HttpResponse, ResponseBuilder};
+pub use typed_header::{
+    invalid, invalid_value, Accept, Authorization, CacheControl, ContentLength, Header, Host,
+    IfNoneMatch, TypedHeader, UserAgent,
+};
 
 #[cfg_attr(docsrs, doc(cfg(feature = "cookies")))]
 #[cfg(feature = "cookies")]
@@ -31,4 +36,4 @@ pub(crate) mod cookies;
 
 #[cfg_attr(docsrs, doc(cfg(feature = "cookies")))]
 #[cfg(feature = "cookies")]
-pub use cookie::Cookie;
+pub use cookie::{Cookie, Key};
@@ -4,7 +4,7 @@ use worker::{Method, Request, Url};
 
 /// Extracted from [`worker::Request`](https://docs.rs/worker/latest/worker/struct.Request.html) mainly used for [`Responder`](crate::Responder) trait.
 #[allow(clippy::module_name_repetitions)]
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct HttpRequest {
     headers: HttpHeaders,
     method: Method,
@@ -1,6 +1,8 @@
 use super::HttpRequest;
 
+use super::body::Body;
 use super::response::{HttpResponse, ResponseBuilder};
+use http::StatusCode;
 use serde_json::Value;
 use std::borrow::Cow;
 use worker::{Cors, Response};
@@ -98,6 +100,13 @@ macro_rules! impl_responder_for {
     };
 }
 
+// nothing to answer with
+impl Responder for () {
+    fn to_response(self, _: HttpRequest) -> HttpResponse {
+        ResponseBuilder::new(StatusCode::NO_CONTENT).body(Body::Empty)
+    }
+}
+
 impl_responder_for!(Value, json);
 impl_responder_for!(String, text);
 impl_responder_for!(Cow<'_, str>, text);
@@ -3,6 +3,7 @@ use super::body::{Body, SetBody};
 use super::content_type::ContentType;
 use super::headers::{HeadersOp, HttpHeaders};
 use super::impl_trait::ToResponse;
+use super::typed_header::Header;
 use crate::error::Error;
 
 use http::header::{HeaderName, HeaderValue, CONTENT_TYPE};
@@ -205,6 +206,40 @@ impl ResponseBuilder {
         self
     }
 
+    /// Insert a typed header, replacing any that were set with the same name.
+    ///
+    /// # Errors
+    /// Errors are returned to the response if the header can't be encoded, see [`Header::encode`].
+    ///
+    /// # Examples
+    /// ```
+    /// use worker::{Request, RouteContext};
+    /// use worker_route::get;
+    /// use worker_route::http::{CacheControl, HttpResponse, ResponseBuilder};
+    ///
+    /// #[get("/account")]
+    /// fn account(_: Request, _: RouteContext<()>) -> worker::Result<HttpResponse> {
+    ///     let mut res = ResponseBuilder::init();
+    ///     res.typed_header(CacheControl {
+    ///         no_store: true,
+    ///         ..Default::default()
+    ///     });
+    ///
+    ///     Ok(res.body(String::from("account")))
+    /// }
+    /// ```
+    // takes the header by value, as the other setters of the builder
+    #[allow(clippy::needless_pass_by_value)]
+    pub fn typed_header<H: Header>(&mut self, header: H) -> &mut Self {
+        match header.encode() {
+            Ok(value) => self.insert_header(H::name().clone(), value),
+            Err(err) => {
+                self.error(err);
+                self
+            }
+        }
+    }
+
     #[cfg_attr(docsrs, doc(cfg(feature = "cookies")))]
     #[cfg(feature = "cookies")]
     /// Add a cookie to this response.
@@ -214,6 +249,18 @@ impl ResponseBuilder {
         self
     }
 
+    #[cfg_attr(docsrs, doc(cfg(feature = "cookies")))]
+    #[cfg(feature = "cookies")]
+    /// Add the changes made to a cookie jar to this response,
+    /// eg: [`CookieJar`](crate::CookieJar) or [`SignedCookieJar`](crate::SignedCookieJar).
+    pub fn cookie_jar<J: AsRef<cookie::CookieJar>>(&mut self, jar: &J) -> &mut Self {
+        use super::cookies::CookieHelper;
+        for cookie in jar.as_ref().delta() {
+            CookieHelper::Set.set(self, cookie);
+        }
+        self
+    }
+
     #[cfg_attr(docsrs, doc(cfg(feature = "cookies")))]
     #[cfg(feature = "cookies")]
     /// Get an iterator for the cookies set by this response.
@@ -0,0 +1,620 @@
+use super::HttpHeaders;
+use crate::error::{Error, ErrorCause};
+use crate::extract::FromRequest;
+
+use core::fmt::{Debug, Display};
+use http::header::{
+    HeaderName, HeaderValue, ACCEPT, AUTHORIZATION, CACHE_CONTROL, CONTENT_LENGTH, HOST,
+    IF_NONE_MATCH, USER_AGENT,
+};
+use http::StatusCode;
+use mime::Mime;
+use std::ops::{Deref, DerefMut};
+use worker::{Request, RouteContext};
+
+/// A typed HTTP header, decoded from and encoded to its raw value.
+///
+/// ```
+/// use worker_route::http::header::{HeaderName, HeaderValue};
+/// use worker_route::http::{invalid_value, Header};
+/// use worker_route::Error;
+///
+/// struct RequestId(String);
+///
+/// static X_REQUEST_ID: HeaderName = HeaderName::from_static("x-request-id");
+///
+/// impl Header for RequestId {
+///     fn name() -> &'static HeaderName {
+///         &X_REQUEST_ID
+///     }
+///
+///     fn decode(value: &str) -> Result<Self, Error> {
+///         Ok(Self(value.to_owned()))
+///     }
+///
+///     fn encode(&self) -> Result<HeaderValue, Error> {
+///         HeaderValue::from_str(&self.0).map_err(|_| invalid_value::<Self>())
+///     }
+/// }
+/// ```
+pub trait Header: Sized {
+    /// The name of the header.
+    fn name() -> &'static HeaderName;
+
+    /// Decodes the header from its raw value.
+    ///
+    /// # Errors
+    /// Errors are returned if the value is malformed, [`invalid`] can be used to create one.
+    fn decode(value: &str) -> Result<Self, Error>;
+
+    /// Encodes the header to its raw value.
+    ///
+    /// # Errors
+    /// Errors are returned if the value can't be sent in a header, [`invalid_value`] can be used to create one.
+    fn encode(&self) -> Result<HeaderValue, Error>;
+}
+
+/// Creates a `400 Bad Request` error for a malformed header `H`.
+pub fn invalid<H: Header>() -> Error {
+    Error::new(
+        format!("invalid header `{}`", H::name()),
+        StatusCode::BAD_REQUEST,
+        ErrorCause::Header,
+    )
+}
+
+/// Creates a `500 Internal Server Error` error for a header `H` whose value can't be encoded,
+/// eg: it contains a line break.
+pub fn invalid_value<H: Header>() -> Error {
+    Error::new(
+        format!("invalid value of header `{}`", H::name()),
+        StatusCode::INTERNAL_SERVER_ERROR,
+        ErrorCause::Header,
+    )
+}
+
+impl HttpHeaders {
+    /// Returns the decoded header `H`, `None` if the header is missing.
+    ///
+    /// # Errors
+    /// A `400 Bad Request` error is returned if the header is malformed.
+    pub fn typed_get<H: Header>(&self) -> Result<Option<H>, Error> {
+        self.get(H::name()).as_deref().map(H::decode).transpose()
+    }
+
+    /// Sets the encoded header `H`, replacing any existing value.
+    ///
+    /// # Errors
+    /// See [`Header::encode`] and [`HttpHeaders::set`].
+    pub fn typed_insert<H: Header>(&mut self, header: &H) -> Result<(), Error> {
+        self.set(H::name(), &header.encode()?)
+    }
+}
+
+/// Extract a typed header from the request, see [`Header`].
+///
+/// A `400 Bad Request` error is returned if the header is missing or malformed,
+/// use `Option<TypedHeader<H>>` for optional headers.
+///
+/// ```
+/// use worker::{Response, Result};
+/// use worker_route::{get, http::{ContentLength, UserAgent}, TypedHeader};
+///
+/// #[get("/agent")]
+/// async fn agent(
+///     user_agent: TypedHeader<UserAgent>,
+///     length: Option<TypedHeader<ContentLength>>,
+/// ) -> Result<String> {
+///     Ok(user_agent.to_string())
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct TypedHeader<H>(H);
+
+impl<H> TypedHeader<H> {
+    #[allow(clippy::missing_const_for_fn)]
+    /// Acess the owned `H`
+    pub fn into_inner(self) -> H {
+        self.0
+    }
+}
+
+impl<D, H: Header> FromRequest<D> for TypedHeader<H> {
+    type Error = Error;
+
+    #[allow(clippy::future_not_send)]
+    async fn from_request(req: &Request, _: &RouteContext<D>) -> Result<Self, Self::Error> {
+        match req.headers().get(H::name().as_str()).unwrap_or(None) {
+            Some(value) => Ok(Self(H::decode(&value)?)),
+            None => Err(Error::new(
+                format!("missing header `{}`", H::name()),
+                StatusCode::BAD_REQUEST,
+                ErrorCause::Header,
+            )),
+        }
+    }
+
+    fn status_codes() -> Vec<StatusCode> {
+        vec![StatusCode::BAD_REQUEST]
+    }
+}
+
+impl<H: Display> Display for TypedHeader<H> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl<H> Deref for TypedHeader<H> {
+    type Target = H;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<H> DerefMut for TypedHeader<H> {
+    fn deref_mut(&mut self) -> &mut H {
+        &mut self.0
+    }
+}
+
+fn to_value<H: Header>(value: &str) -> Result<HeaderValue, Error> {
+    HeaderValue::from_str(value).map_err(|_| invalid_value::<H>())
+}
+
+macro_rules! string_header {
+    ($(#[$meta:meta])* $name:ident, $header:ident) => {
+        $(#[$meta])*
+        #[derive(Debug, Clone, PartialEq, Eq)]
+        pub struct $name(String);
+
+        impl $name {
+            /// Creates the header from its value.
+            pub fn new<T: Into<String>>(value: T) -> Self {
+                Self(value.into())
+            }
+
+            /// The value of the header.
+            pub fn as_str(&self) -> &str {
+                &self.0
+            }
+        }
+
+        impl Header for $name {
+            fn name() -> &'static HeaderName {
+                &$header
+            }
+
+            fn decode(value: &str) -> Result<Self, Error> {
+                Ok(Self(value.to_owned()))
+            }
+
+            fn encode(&self) -> Result<HeaderValue, Error> {
+                to_value::<Self>(&self.0)
+            }
+        }
+
+        impl Display for $name {
+            fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+                f.write_str(&self.0)
+            }
+        }
+    };
+}
+
+string_header!(
+    /// The `User-Agent` header.
+    UserAgent,
+    USER_AGENT
+);
+string_header!(
+    /// The `Host` header.
+    Host,
+    HOST
+);
+
+impl Host {
+    // `[::1]:8787` into `::1` and `8787`, an IPv6 address is only followed by a port in brackets
+    fn split(&self) -> (&str, Option<&str>) {
+        let host = self.0.as_str();
+        if let Some(ipv6) = host.strip_prefix('[') {
+            return ipv6
+                .split_once(']')
+                .map_or((host, None), |(hostname, rest)| {
+                    (hostname, rest.strip_prefix(':'))
+                });
+        }
+
+        match host.split_once(':') {
+            Some((hostname, port)) if !port.contains(':') => (hostname, Some(port)),
+            _ => (host, None),
+        }
+    }
+
+    /// The host name, without the port nor the brackets of an IPv6 address.
+    pub fn hostname(&self) -> &str {
+        self.split().0
+    }
+
+    /// The port, if any.
+    pub fn port(&self) -> Option<u16> {
+        self.split().1.and_then(|v| v.parse().ok())
+    }
+}
+
+/// The `Content-Length` header.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ContentLength(pub u64);
+
+impl Header for ContentLength {
+    fn name() -> &'static HeaderName {
+        &CONTENT_LENGTH
+    }
+
+    fn decode(value: &str) -> Result<Self, Error> {
+        value
+            .trim()
+            .parse()
+            .map(Self)
+            .map_err(|_| invalid::<Self>())
+    }
+
+    fn encode(&self) -> Result<HeaderValue, Error> {
+        Ok(HeaderValue::from(self.0))
+    }
+}
+
+impl Display for ContentLength {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        Display::fmt(&self.0, f)
+    }
+}
+
+/// The `Authorization` header, eg: `Bearer <token>`.
+#[derive(Clone, PartialEq, Eq)]
+pub struct Authorization {
+    scheme: String,
+    credentials: String,
+}
+
+impl Authorization {
+    /// Creates the header from a scheme and its credentials.
+    pub fn new<S: Into<String>, C: Into<String>>(scheme: S, credentials: C) -> Self {
+        Self {
+            scheme: scheme.into(),
+            credentials: credentials.into(),
+        }
+    }
+
+    /// Creates a `Bearer` authorization.
+    pub fn bearer<T: Into<String>>(token: T) -> Self {
+        Self::new("Bearer", token)
+    }
+
+    /// The authentication scheme, eg: `Bearer`.
+    pub fn scheme(&self) -> &str {
+        &self.scheme
+    }
+
+    /// The credentials following the scheme.
+    pub fn credentials(&self) -> &str {
+        &self.credentials
+    }
+
+    /// Returns `true` if the scheme matches, case insensitively.
+    pub fn is_scheme(&self, scheme: &str) -> bool {
+        self.scheme.eq_ignore_ascii_case(scheme)
+    }
+}
+
+// credentials are never printed
+impl Debug for Authorization {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("Authorization")
+            .field("scheme", &self.scheme)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Header for Authorization {
+    fn name() -> &'static HeaderName {
+        &AUTHORIZATION
+    }
+
+    fn decode(value: &str) -> Result<Self, Error> {
+        match value.trim().split_once(' ') {
+            Some((scheme, credentials)) if !credentials.trim().is_empty() => {
+                Ok(Self::new(scheme, credentials.trim()))
+            }
+            _ => Err(invalid::<Self>()),
+        }
+    }
+
+    fn encode(&self) -> Result<HeaderValue, Error> {
+        let mut value = to_value::<Self>(&format!("{} {}", self.scheme, self.credentials))?;
+        value.set_sensitive(true);
+        Ok(value)
+    }
+}
+
+/// The `Accept` header, media types are ordered by their quality (`q`), highest first.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Accept(Vec<Mime>);
+
+fn quality(mime: &Mime) -> f32 {
+    mime.get_param("q")
+        .and_then(|v| v.as_str().parse().ok())
+        .unwrap_or(1.0)
+}
+
+impl Accept {
+    /// Creates the header from media types, in order of preference.
+    pub fn new(mimes: Vec<Mime>) -> Self {
+        Self(mimes)
+    }
+
+    /// The accepted media types, highest quality first.
+    pub fn mimes(&self) -> &[Mime] {
+        &self.0
+    }
+
+    /// Returns `true` if the given media type is accepted, wildcards included.
+    pub fn accepts(&self, mime: &Mime) -> bool {
+        self.0.iter().filter(|v| quality(v) > 0.0).any(|v| {
+            (v.type_() == mime::STAR || v.type_() == mime.type_())
+                && (v.subtype() == mime::STAR || v.subtype() == mime.subtype())
+        })
+    }
+}
+
+impl Header for Accept {
+    fn name() -> &'static HeaderName {
+        &ACCEPT
+    }
+
+    fn decode(value: &str) -> Result<Self, Error> {
+        let mut mimes = value
+            .split(',')
+            .filter(|v| !v.trim().is_empty())
+            .map(|v| v.trim().parse::<Mime>().map_err(|_| invalid::<Self>()))
+            .collect::<Result<Vec<_>, _>>()?;
+        // stable sort, keeps the order of media types with the same quality
+        mimes.sort_by(|a, b| quality(b).total_cmp(&quality(a)));
+        Ok(Self(mimes))
+    }
+
+    fn encode(&self) -> Result<HeaderValue, Error> {
+        let value = self
+            .0
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>()
+            .join(", ");
+        to_value::<Self>(&value)
+    }
+}
+
+/// The `If-None-Match` header.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum IfNoneMatch {
+    /// `*`, matches any entity tag.
+    Any,
+    /// A list of entity tags, eg: `"abc", W/"def"`.
+    Tags(Vec<String>),
+}
+
+// weak comparison, `W/"abc"` matches `"abc"`
+fn strip_weak(tag: &str) -> &str {
+    tag.trim().strip_prefix("W/").unwrap_or_else(|| tag.trim())
+}
+
+impl IfNoneMatch {
+    /// Returns `true` if the given entity tag matches, using the weak comparison.
+    pub fn matches(&self, etag: &str) -> bool {
+        match self {
+            Self::Any => true,
+            Self::Tags(tags) => tags.iter().any(|v| strip_weak(v) == strip_weak(etag)),
+        }
+    }
+}
+
+impl Header for IfNoneMatch {
+    fn name() -> &'static HeaderName {
+        &IF_NONE_MATCH
+    }
+
+    fn decode(value: &str) -> Result<Self, Error> {
+        if value.trim() == "*" {
+            return Ok(Self::Any);
+        }
+
+        let tags = value
+            .split(',')
+            .map(|v| v.trim().to_owned())
+            .collect::<Vec<_>>();
+        if tags
+            .iter()
+            .any(|v| !strip_weak(v).starts_with('"') || !v.ends_with('"'))
+        {
+            return Err(invalid::<Self>());
+        }
+
+        Ok(Self::Tags(tags))
+    }
+
+    fn encode(&self) -> Result<HeaderValue, Error> {
+        match self {
+            Self::Any => Ok(HeaderValue::from_static("*")),
+            Self::Tags(tags) => to_value::<Self>(&tags.join(", ")),
+        }
+    }
+}
+
+/// The `Cache-Control` header.
+///
+/// ```
+/// use worker::{Request, RouteContext};
+/// use worker_route::get;
+/// use worker_route::http::{CacheControl, HttpResponse, ResponseBuilder};
+///
+/// #[get("/logo")]
+/// fn logo(_: Request, _: RouteContext<()>) -> worker::Result<HttpResponse> {
+///     let mut res = ResponseBuilder::init();
+///     res.typed_header(CacheControl {
+///         public: true,
+///         max_age: Some(3600),
+///         ..Default::default()
+///     });
+///
+///     Ok(res.body(String::from("logo")))
+/// }
+/// ```
+#[allow(clippy::struct_excessive_bools)]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CacheControl {
+    pub no_cache: bool,
+    pub no_store: bool,
+    pub no_transform: bool,
+    pub must_revalidate: bool,
+    pub public: bool,
+    pub private: bool,
+    pub immutable: bool,
+    pub max_age: Option<u64>,
+    pub s_max_age: Option<u64>,
+}
+
+impl Header for CacheControl {
+    fn name() -> &'static HeaderName {
+        &CACHE_CONTROL
+    }
+
+    fn decode(value: &str) -> Result<Self, Error> {
+        let mut header = Self::default();
+        for directive in value.split(',').map(str::trim).filter(|v| !v.is_empty()) {
+            let (key, arg) = directive
+                .split_once('=')
+                .map_or((directive, None), |v| (v.0, Some(v.1)));
+            let seconds = || {
+                arg.and_then(|v| v.trim_matches('"').parse().ok())
+                    .ok_or_else(invalid::<Self>)
+            };
+            match key.to_ascii_lowercase().as_str() {
+                "no-cache" => header.no_cache = true,
+                "no-store" => header.no_store = true,
+                "no-transform" => header.no_transform = true,
+                "must-revalidate" => header.must_revalidate = true,
+                "public" => header.public = true,
+                "private" => header.private = true,
+                "immutable" => header.immutable = true,
+                "max-age" => header.max_age = Some(seconds()?),
+                "s-maxage" => header.s_max_age = Some(seconds()?),
+                // unknown extensions are ignored
+                _ => {}
+            }
+        }
+
+        Ok(header)
+    }
+
+    fn encode(&self) -> Result<HeaderValue, Error> {
+        let flags = [
+            (self.no_cache, "no-cache"),
+            (self.no_store, "no-store"),
+            (self.no_transform, "no-transform"),
+            (self.must_revalidate, "must-revalidate"),
+            (self.public, "public"),
+            (self.private, "private"),
+            (self.immutable, "immutable"),
+        ];
+        let directives = flags
+            .iter()
+            .filter(|v| v.0)
+            .map(|v| v.1.to_owned())
+            .chain(self.max_age.map(|v| format!("max-age={v}")))
+            .chain(self.s_max_age.map(|v| format!("s-maxage={v}")))
+            .collect::<Vec<_>>();
+
+        to_value::<Self>(&directives.join(", "))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{
+        Accept, Authorization, CacheControl, ContentLength, Header, Host, IfNoneMatch, UserAgent,
+    };
+    use crate::{http::StatusCode, ResponseError};
+
+    #[test]
+    fn authorization() {
+        let auth = Authorization::decode("Bearer abc.def").unwrap();
+
+        assert!(auth.is_scheme("bearer"));
+        assert_eq!(auth.credentials(), "abc.def");
+        assert_eq!(auth.encode().unwrap(), "Bearer abc.def");
+        assert_eq!(
+            Authorization::decode("Bearer").unwrap_err().status_code(),
+            StatusCode::BAD_REQUEST
+        );
+    }
+
+    #[test]
+    fn accept() {
+        let accept = Accept::decode("text/html;q=0.5, application/json, image/*;q=0").unwrap();
+
+        assert_eq!(accept.mimes()[0], mime::APPLICATION_JSON);
+        assert!(accept.accepts(&mime::TEXT_HTML));
+        assert!(!accept.accepts(&mime::IMAGE_PNG));
+        assert!(Accept::decode("not a mime").is_err());
+    }
+
+    #[test]
+    fn content_length() {
+        assert_eq!(ContentLength::decode("42").unwrap(), ContentLength(42));
+        assert!(ContentLength::decode("-1").is_err());
+    }
+
+    #[test]
+    fn if_none_match() {
+        let tags = IfNoneMatch::decode(r#""abc", W/"def""#).unwrap();
+
+        assert!(tags.matches(r#""def""#));
+        assert!(!tags.matches(r#""xyz""#));
+        assert!(IfNoneMatch::decode("*").unwrap().matches(r#""xyz""#));
+        assert!(IfNoneMatch::decode("abc").is_err());
+    }
+
+    #[test]
+    fn cache_control() {
+        let header = CacheControl::decode("public, max-age=3600, x-custom").unwrap();
+
+        assert!(header.public);
+        assert_eq!(header.max_age, Some(3600));
+        assert_eq!(header.encode().unwrap(), "public, max-age=3600");
+        assert!(CacheControl::decode("max-age=abc").is_err());
+    }
+
+    #[test]
+    fn host() {
+        let host = Host::decode("example.com:8787").unwrap();
+
+        assert_eq!(host.hostname(), "example.com");
+        assert_eq!(host.port(), Some(8787));
+        assert_eq!(Host::new("example.com").port(), None);
+
+        let ipv6 = Host::new("[::1]:8787");
+        assert_eq!(ipv6.hostname(), "::1");
+        assert_eq!(ipv6.port(), Some(8787));
+        assert_eq!(Host::new("[2001:db8::1]").hostname(), "2001:db8::1");
+        assert_eq!(Host::new("[2001:db8::1]").port(), None);
+    }
+
+    #[test]
+    fn invalid_value() {
+        let err = UserAgent::new("agent\r\nx-injected: 1")
+            .encode()
+            .unwrap_err();
+        assert_eq!(err.status_code(), StatusCode::INTERNAL_SERVER_ERROR);
+    }
+}
@@ -1,11 +1,10 @@
 use crate::{
+    extract::FromRequest,
     http::HttpRequest,
     http::ResponseError,
     http::{InternalResponder, Responder},
-    Query,
 };
 use futures::Future;
-use serde::de::DeserializeOwned;
 use worker::{Cors, Request, Response, RouteContext};
 
 pub fn responder<T, E>(
@@ -20,13 +19,16 @@ where
     res.res(req, cors)
 }
 
-type WithQuery<D, T, U> = fn(Query<T>, RouteContext<D>) -> U;
-type WithReq<D, T, U> = fn(Query<T>, Request, RouteContext<D>) -> U;
-
-#[derive(Copy, Clone)]
-pub enum FnType<D, T, U> {
-    WithQuery(WithQuery<D, T, U>),
-    WithReq(WithReq<D, T, U>),
+#[allow(clippy::future_not_send)]
+pub async fn extract<D, T: FromRequest<D>>(
+    req: &Request,
+    ctx: &RouteContext<D>,
+    pattern: &'static str,
+) -> Result<T, Box<dyn ResponseError>> {
+    match T::from_route(req, ctx, pattern).await {
+        Ok(extracted) => Ok(extracted),
+        Err(err) => Err(Box::new(err)),
+    }
 }
 
 #[allow(clippy::future_not_send)]
@@ -57,54 +59,3 @@ where
         }
     }
 }
-
-macro_rules! impl_wrap_query {
-    ($wrap:ident, $query:ident, $ctx:ident, $req:ident) => {
-        match $wrap {
-            Self::WithQuery(fn_) => fn_($query, $ctx),
-            Self::WithReq(fn_) => fn_($query, $req, $ctx),
-        }
-    };
-
-    ($ctx:ident, $req:ident) => {
-        Query::_internal_query::<RouteContext<D>>($req.url(), &$ctx)
-    };
-}
-
-impl<D, T, U, E> FnType<D, T, Result<U, E>>
-where
-    T: DeserializeOwned,
-    U: Responder,
-    E: ResponseError,
-{
-    pub fn wrap(
-        wrap_: &Self,
-        req: Request,
-        ctx: RouteContext<D>,
-        cors: Option<&Cors>,
-    ) -> worker::Result<Response> {
-        let http = HttpRequest::from(&req);
-        match impl_wrap_query!(ctx, req) {
-            Ok(query) => impl_wrap_query!(wrap_, query, ctx, req).res(http, cors),
-            Err(err) => Ok(err.error_response(http).into_response(cors).into_res()),
-        }
-    }
-}
-
-impl<D, Q: DeserializeOwned, U> FnType<D, Q, U> {
-    pub fn wrap_async<T, E>(
-        wrap_: &Self,
-        req: Request,
-        ctx: RouteContext<D>,
-    ) -> Result<U, Box<dyn ResponseError>>
-    where
-        U: Future<Output = Result<T, E>>,
-        T: Responder,
-        E: ResponseError,
-    {
-        match impl_wrap_query!(ctx, req) {
-            Ok(query) => Ok(impl_wrap_query!(wrap_, query, ctx, req)),
-            Err(err) => Err(Box::new(err)),
-        }
-    }
-}
@@ -0,0 +1,285 @@
+use crate::binding::Binding;
+use crate::error::{Error, ErrorCause};
+use crate::extract::FromRequest;
+
+use cookie::{Cookie, Key};
+use core::convert::Infallible;
+use core::fmt::Debug;
+use core::marker::PhantomData;
+use http::header::COOKIE;
+use http::StatusCode;
+use worker::{Request, RouteContext};
+
+// the minimum length of the secret a `Key` is derived from
+const MIN_SECRET_LEN: usize = 32;
+
+fn parse_jar(req: &Request) -> cookie::CookieJar {
+    let mut jar = cookie::CookieJar::new();
+    if let Ok(Some(header)) = req.headers().get(COOKIE.as_str()) {
+        for cookie in Cookie::split_parse(header).flatten() {
+            jar.add_original(cookie.into_owned());
+        }
+    }
+
+    jar
+}
+
+fn derive_key<B: Binding>(secret: &str) -> Result<Key, Error> {
+    if secret.len() < MIN_SECRET_LEN {
+        return Err(Error::new(
+            format!(
+                "binding `{}` must be at least {MIN_SECRET_LEN} bytes to sign cookies",
+                B::NAME
+            ),
+            StatusCode::INTERNAL_SERVER_ERROR,
+            ErrorCause::Binding,
+        ));
+    }
+
+    Ok(Key::derive_from(secret.as_bytes()))
+}
+
+#[allow(clippy::future_not_send)]
+async fn load_key<D, B: Binding>(req: &Request, ctx: &RouteContext<D>) -> Result<Key, Error> {
+    let secret = crate::Secret::<B>::from_request(req, ctx).await?;
+    derive_key::<B>(&secret.to_string())
+}
+
+/// Extract the request's cookies into a jar.
+///
+/// Changes made to the jar are written back with
+/// [`ResponseBuilder::cookie_jar`](crate::http::ResponseBuilder::cookie_jar).
+///
+/// ```
+/// use worker::Result;
+/// use worker_route::{get, http::{Cookie, HttpResponse, ResponseBuilder}, CookieJar};
+///
+/// #[get("/logout")]
+/// async fn logout(mut jar: CookieJar) -> Result<HttpResponse> {
+///     jar.remove(Cookie::named("theme"));
+///
+///     let mut res = ResponseBuilder::init();
+///     res.cookie_jar(&jar);
+///     Ok(res.text("bye"))
+/// }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct CookieJar(cookie::CookieJar);
+
+impl CookieJar {
+    /// Returns the cookie named `name`.
+    pub fn get(&self, name: &str) -> Option<&Cookie<'static>> {
+        self.0.get(name)
+    }
+
+    /// Adds a cookie, it is sent back to the client.
+    pub fn add(&mut self, cookie: Cookie<'static>) {
+        self.0.add(cookie);
+    }
+
+    /// Removes a cookie, the client is told to expire it.
+    ///
+    /// The `path` and `domain` of the cookie must match the ones it was set with.
+    pub fn remove(&mut self, cookie: Cookie<'static>) {
+        self.0.remove(cookie);
+    }
+
+    /// Returns an iterator over every cookie in the jar.
+    pub fn iter(&self) -> impl Iterator<Item = &Cookie<'static>> {
+        self.0.iter()
+    }
+
+    /// Acess the owned jar
+    pub fn into_inner(self) -> cookie::CookieJar {
+        self.0
+    }
+}
+
+impl<D> FromRequest<D> for CookieJar {
+    type Error = Infallible;
+
+    #[allow(clippy::future_not_send)]
+    async fn from_request(req: &Request, _: &RouteContext<D>) -> Result<Self, Self::Error> {
+        Ok(Self(parse_jar(req)))
+    }
+}
+
+impl AsRef<cookie::CookieJar> for CookieJar {
+    fn as_ref(&self) -> &cookie::CookieJar {
+        &self.0
+    }
+}
+
+macro_rules! impl_secure_jar {
+    ($(#[$meta:meta])* $name:ident, $jar:ident, $jar_mut:ident) => {
+        $(#[$meta])*
+        ///
+        /// The key is derived from the secret named by `B`, which must be at least 32 bytes,
+        /// a `500 Internal Server Error` is returned otherwise.
+        /// Changes made to the jar are written back with
+        /// [`ResponseBuilder::cookie_jar`](crate::http::ResponseBuilder::cookie_jar).
+        pub struct $name<B: Binding> {
+            jar: cookie::CookieJar,
+            key: Key,
+            _binding: PhantomData<B>,
+        }
+
+        impl<B: Binding> $name<B> {
+            /// Creates an empty jar from a key.
+            pub fn new(key: Key) -> Self {
+                Self {
+                    jar: cookie::CookieJar::new(),
+                    key,
+                    _binding: PhantomData,
+                }
+            }
+
+            /// Returns the cookie named `name`, `None` if it's missing or was tampered with.
+            pub fn get(&self, name: &str) -> Option<Cookie<'static>> {
+                self.jar.$jar(&self.key).get(name)
+            }
+
+            /// Adds a cookie, it is sent back to the client.
+            pub fn add(&mut self, cookie: Cookie<'static>) {
+                self.jar.$jar_mut(&self.key).add(cookie);
+            }
+
+            /// Removes a cookie, the client is told to expire it.
+            ///
+            /// The `path` and `domain` of the cookie must match the ones it was set with.
+            pub fn remove(&mut self, cookie: Cookie<'static>) {
+                self.jar.$jar_mut(&self.key).remove(cookie);
+            }
+        }
+
+        impl<D, B: Binding> FromRequest<D> for $name<B> {
+            type Error = Error;
+
+            #[allow(clippy::future_not_send)]
+            async fn from_request(req: &Request, ctx: &RouteContext<D>) -> Result<Self, Self::Error> {
+                Ok(Self {
+                    jar: parse_jar(req),
+                    key: load_key::<D, B>(req, ctx).await?,
+                    _binding: PhantomData,
+                })
+            }
+
+            fn status_codes() -> Vec<StatusCode> {
+                vec![StatusCode::INTERNAL_SERVER_ERROR]
+            }
+        }
+
+        impl<B: Binding> AsRef<cookie::CookieJar> for $name<B> {
+            fn as_ref(&self) -> &cookie::CookieJar {
+                &self.jar
+            }
+        }
+
+        // the key is never printed
+        impl<B: Binding> Debug for $name<B> {
+            fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+                f.debug_struct(stringify!($name))
+                    .field("jar", &self.jar)
+                    .finish_non_exhaustive()
+            }
+        }
+    };
+}
+
+impl_secure_jar!(
+    /// Extract the request's cookies into a jar whose cookies are signed,
+    /// they can be read by the client but not tampered with.
+    ///
+    /// ```
+    /// use worker::Result;
+    /// use worker_route::http::{Cookie, HttpResponse, ResponseBuilder};
+    /// use worker_route::{post, Binding, SignedCookieJar};
+    ///
+    /// struct CookieSecret;
+    ///
+    /// impl Binding for CookieSecret {
+    ///     const NAME: &'static str = "COOKIE_SECRET";
+    /// }
+    ///
+    /// #[post("/login")]
+    /// async fn login(mut jar: SignedCookieJar<CookieSecret>) -> Result<HttpResponse> {
+    ///     jar.add(Cookie::new("session_id", "abc"));
+    ///
+    ///     let mut res = ResponseBuilder::init();
+    ///     res.cookie_jar(&jar);
+    ///     Ok(res.text("welcome"))
+    /// }
+    /// ```
+    SignedCookieJar,
+    signed,
+    signed_mut
+);
+impl_secure_jar!(
+    /// Extract the request's cookies into a jar whose cookies are encrypted,
+    /// they can neither be read nor tampered with by the client.
+    ///
+    /// See [`SignedCookieJar`] for an example, both jars are used the same way.
+    PrivateCookieJar,
+    private,
+    private_mut
+);
+
+#[cfg(test)]
+mod test {
+    use super::{derive_key, Binding, PrivateCookieJar, SignedCookieJar};
+    use crate::{http::StatusCode, ResponseError};
+    use cookie::Cookie;
+
+    struct CookieSecret;
+
+    impl Binding for CookieSecret {
+        const NAME: &'static str = "COOKIE_SECRET";
+    }
+
+    const SECRET: &str = "a secret which is long enough to derive a key";
+
+    #[test]
+    fn short_secret() {
+        let Err(err) = derive_key::<CookieSecret>("short") else {
+            panic!("expected an error");
+        };
+
+        assert_eq!(err.status_code(), StatusCode::INTERNAL_SERVER_ERROR);
+        assert!(err.description().contains("COOKIE_SECRET"));
+    }
+
+    #[test]
+    fn signed_jar() {
+        let key = derive_key::<CookieSecret>(SECRET).unwrap();
+        let mut jar = SignedCookieJar::<CookieSecret>::new(key.clone());
+        jar.add(Cookie::new("session_id", "abc"));
+
+        let signed = jar.jar.delta().next().unwrap().clone();
+        assert_ne!(signed.value(), "abc");
+
+        let mut received = SignedCookieJar::<CookieSecret>::new(key);
+        received.jar.add_original(signed.clone());
+        assert_eq!(received.get("session_id").unwrap().value(), "abc");
+
+        let tampered = Cookie::new("session_id", signed.value().replace("abc", "xyz"));
+        received.jar.add_original(tampered);
+        assert!(received.get("session_id").is_none());
+    }
+
+    #[test]
+    fn private_jar() {
+        let key = derive_key::<CookieSecret>(SECRET).unwrap();
+        let mut jar = PrivateCookieJar::<CookieSecret>::new(key.clone());
+        jar.add(Cookie::new("session_id", "abc"));
+
+        let encrypted = jar.jar.delta().next().unwrap().clone();
+        assert!(!encrypted.value().contains("abc"));
+
+        let mut received = PrivateCookieJar::<CookieSecret>::new(key);
+        received.jar.add_original(encrypted);
+        assert_eq!(received.get("session_id").unwrap().value(), "abc");
+
+        received.remove(Cookie::named("session_id"));
+        assert!(received.get("session_id").is_none());
+    }
+}
@@ -0,0 +1,239 @@
+use crate::error::{Error, ErrorCause};
+use crate::extract::FromRequest;
+use crate::http::{ContentType, HttpRequest, HttpResponse, ResponseBuilder};
+use crate::Responder;
+
+use core::fmt::Display;
+use http::header::CONTENT_TYPE;
+use http::StatusCode;
+use mime::Mime;
+use serde::{de::DeserializeOwned, Serialize};
+use std::ops::{Deref, DerefMut};
+use worker::{Request, RouteContext};
+
+/// Extract typed information from the request's body.
+///
+/// To extract typed data from the request's body, `T` must implement
+/// the [`DeserializeOwned`](serde::de::DeserializeOwned) trait.
+///
+/// The request must have a `Content-Type: application/json` header (or any `+json` suffix),
+/// otherwise a `415 Unsupported Media Type` error is returned.
+///
+/// ```
+/// use serde::{Deserialize, Serialize};
+/// use worker::{Response, Result, RouteContext};
+/// use worker_route::{post, Json};
+///
+/// #[derive(Debug, Serialize, Deserialize)]
+/// struct Person {
+///     name: String,
+///     age: usize,
+/// }
+///
+/// #[post("/person")]
+/// async fn person(req: Json<Person>, _: RouteContext<()>) -> Result<Response> {
+///     let Person { name, age } = req.into_inner();
+///     // rest code
+///     Response::empty()
+/// }
+/// ```
+///
+/// # Notes
+/// `Json<T>` reads the body from a clone of the request,
+/// the `Request` is still readable if the handler takes it as well.
+///
+/// `Json<T>` can also be returned from a handler as it implements [`Responder`](crate::Responder),
+/// eg: `Ok(Json(person))`.
+#[derive(Debug, Clone)]
+pub struct Json<T>(pub T);
+
+impl<T> Json<T> {
+    #[allow(clippy::missing_const_for_fn)]
+    /// Acess the owned `T`
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+fn is_json(content_type: Option<&str>) -> bool {
+    content_type
+        .and_then(|v| v.parse::<Mime>().ok())
+        .is_some_and(|mime| {
+            mime.type_() == mime::APPLICATION
+                && (mime.subtype() == mime::JSON || mime.suffix() == Some(mime::JSON))
+        })
+}
+
+impl<T: DeserializeOwned> Json<T> {
+    fn new(content_type: Option<&str>, body: &str) -> Result<Self, Error> {
+        if !is_json(content_type) {
+            return Err(Error::new(
+                format!("expected `{CONTENT_TYPE}: {}`", ContentType::json()),
+                StatusCode::UNSUPPORTED_MEDIA_TYPE,
+                ErrorCause::Body,
+            ));
+        }
+
+        Ok(Self(serde_json::from_str::<T>(body)?))
+    }
+
+    /// Deserialize the given `T` from the request's body.
+    ///
+    /// ```
+    /// use serde::{Deserialize, Serialize};
+    /// use worker::{console_log, Request, Response, Result, RouteContext};
+    /// use worker_route::{post, Json};
+    ///
+    /// #[derive(Debug, Deserialize, Serialize)]
+    /// struct Person {
+    ///     name: String,
+    ///     age: usize,
+    /// }
+    ///
+    /// #[post("/persons")]
+    /// async fn person(mut req: Request, _: RouteContext<()>) -> Result<Response> {
+    ///     let person = Json::<Person>::from_body(&mut req).await;
+    ///     let Person { name, age } = person.unwrap().into_inner();
+    ///     console_log!("name: {name}, age: {age}");
+    ///     Response::empty()
+    /// }
+    /// ```
+    ///
+    /// # Errors
+    /// - `415 Unsupported Media Type` if the `Content-Type` is not JSON.
+    /// - `400 Bad Request` if the body can't be read or is not a valid JSON.
+    /// - `422 Unprocessable Entity` if the JSON does not match `T`.
+    #[allow(clippy::future_not_send)]
+    pub async fn from_body(req: &mut Request) -> Result<Self, Error> {
+        let content_type = req.headers().get(CONTENT_TYPE.as_str()).unwrap_or(None);
+        // check the content type first, there's no point reading the body otherwise
+        if !is_json(content_type.as_deref()) {
+            return Self::new(content_type.as_deref(), "");
+        }
+
+        match req.text().await {
+            Ok(body) => Self::new(content_type.as_deref(), &body),
+            Err(err) => Err(Error::new(
+                err.to_string(),
+                StatusCode::BAD_REQUEST,
+                ErrorCause::Body,
+            )),
+        }
+    }
+}
+
+impl<D, T: DeserializeOwned> FromRequest<D> for Json<T> {
+    type Error = Error;
+
+    #[allow(clippy::future_not_send)]
+    async fn from_request(req: &Request, _: &RouteContext<D>) -> Result<Self, Self::Error> {
+        // a clone of a used body throws, which would only tell it's a `TypeError`
+        if req.inner().body_used() {
+            return Err(Error::body_used());
+        }
+
+        Self::from_body(&mut req.clone()?).await
+    }
+
+    fn status_codes() -> Vec<StatusCode> {
+        vec![
+            StatusCode::BAD_REQUEST,
+            StatusCode::UNSUPPORTED_MEDIA_TYPE,
+            StatusCode::UNPROCESSABLE_ENTITY,
+        ]
+    }
+}
+
+impl<T: Serialize> Responder for Json<T> {
+    fn to_response(self, _: HttpRequest) -> HttpResponse {
+        ResponseBuilder::init().json(self.0)
+    }
+}
+
+impl<T: Display> Display for Json<T> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl<T> Deref for Json<T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<T> DerefMut for Json<T> {
+    fn deref_mut(&mut self) -> &mut T {
+        &mut self.0
+    }
+}
+
+impl<T> AsRef<T> for Json<T> {
+    fn as_ref(&self) -> &T {
+        &self.0
+    }
+}
+
+impl<T> AsMut<T> for Json<T> {
+    fn as_mut(&mut self) -> &mut T {
+        &mut self.0
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{http::StatusCode, Json, ResponseError};
+    use serde::Deserialize;
+
+    #[allow(unused)]
+    #[derive(Deserialize, Debug)]
+    struct Person {
+        name: String,
+        age: usize,
+    }
+
+    #[test]
+    fn json_ok() {
+        let body = r#"{"name":"Foo","age":20}"#;
+        let person = Json::<Person>::new(Some("application/json"), body).unwrap();
+
+        assert_eq!(person.name, "Foo");
+        assert_eq!(person.age, 20);
+    }
+
+    #[test]
+    fn json_suffix_ok() {
+        let body = r#"{"name":"Foo","age":20}"#;
+        let content_type = Some("application/merge-patch+json; charset=utf-8");
+
+        assert!(Json::<Person>::new(content_type, body).is_ok());
+    }
+
+    #[test]
+    fn content_type_err() {
+        let body = r#"{"name":"Foo","age":20}"#;
+        let missing = Json::<Person>::new(None, body).unwrap_err();
+        let invalid = Json::<Person>::new(Some("text/plain"), body).unwrap_err();
+
+        assert_eq!(missing.status_code(), StatusCode::UNSUPPORTED_MEDIA_TYPE);
+        assert_eq!(invalid.status_code(), StatusCode::UNSUPPORTED_MEDIA_TYPE);
+    }
+
+    #[test]
+    fn syntax_err() {
+        let body = r#"{"name":"Foo","age":"#;
+        let err = Json::<Person>::new(Some("application/json"), body).unwrap_err();
+
+        assert_eq!(err.status_code(), StatusCode::BAD_REQUEST);
+    }
+
+    #[test]
+    fn data_err() {
+        let body = r#"{"name":"Foo","age":"twenty"}"#;
+        let err = Json::<Person>::new(Some("application/json"), body).unwrap_err();
+
+        assert_eq!(err.status_code(), StatusCode::UNPROCESSABLE_ENTITY);
+    }
+}
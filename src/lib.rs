@@ -5,7 +5,7 @@
 //! ```
 //! use serde::{Deserialize, Serialize};
 //! use worker::{event, Env, Request, Response, Result, RouteContext, Router};
-//! use worker_route::{get, Configure, Query, Service};
+//! use worker_route::{get, Configure, Path, Query, Service};
 //!
 //! #[derive(Debug, Serialize, Deserialize)]
 //! struct Bar {
@@ -34,7 +34,8 @@
 //!     bar: String,
 //! }
 //!
-//! // your function can consist of (Query<T>, Request, RouteContext<()>) too
+//! // your function can take any number of extractors, in any order
+//! // alongside Request and RouteContext<()>
 //! #[get("/foo-bar")]
 //! async fn foo_bar(req: Query<FooBar>, _req: Request, _: RouteContext<()>) -> Result<Response> {
 //!     Response::from_json(&req.into_inner())
@@ -47,7 +48,7 @@
 //! }
 //!
 //! #[get("/person/:name/:age")]
-//! async fn person(req: Query<Person>, _: RouteContext<()>) -> Result<Response> {
+//! async fn person(req: Path<Person>, _: RouteContext<()>) -> Result<Response> {
 //!     Response::from_json(&req.into_inner())
 //! }
 //!
@@ -68,7 +69,30 @@
 //!
 //! # Features
 //! - Add routes to handler with macro attribute
+//! - Group routes under a shared prefix and cors with `scope`
+//! - Register every handler of a module tree with `handlers` and `routes!`
+//! - Route `CONNECT`, `TRACE` and custom methods like `PURGE`
+//! - Answer unmatched methods with `405 Method Not Allowed` and an `Allow` header
+//! - Customize the response of unmatched routes with `fallback`
+//! - Run middlewares before and after handlers, per route with `wrap = [..]` or to every route of an `App`
+//! - Allow origins, subdomain wildcards included, and check preflights with `CorsPolicy`
+//! - Limit the rate of requests by IP, header or a custom key with `RateLimit`, eg: `rate_limit = "100/min"`
+//! - Inspect every configured route with `RouteTable`
+//! - Generate an [OpenAPI 3.1](https://spec.openapis.org/oas/v3.1.0) document, with the `openapi` feature
+//! - Constrain path parameters to a type or a regex, eg: `/person/:age<u8>`
+//! - Check the path parameters against the fields of a `PathParams` struct at compile time
+//! - Generate the URL of a handler from its pattern, eg: `person::url(name, age)`
 //! - Extract query parameters or path from URL
+//! - Extract JSON or urlencoded forms from the request's body
+//! - Stream `multipart/form-data` uploads or collect them into a struct
+//! - Extract the router's data with `State`, without taking `RouteContext`
+//! - Extract environment bindings (secrets, vars, KV, R2 and D1)
+//! - Authenticate requests with `BearerAuth`, `BasicAuth` or `ApiKey`, verified against the environment
+//! - Verify JSON Web Tokens with `Jwt`, signed with HS256, RS256 or ES256 keys or a JWKS, with the `jwt` feature
+//! - Read and write typed headers with `TypedHeader`
+//! - Cookie jars, signed or encrypted, with the `cookies` feature
+//! - Validate extracted values with `Valid`, with the `validate` feature
+//! - Implement your own extractors with `FromRequest`
 //!
 #![warn(clippy::pedantic, clippy::nursery)]
 #![allow(
@@ -81,25 +105,83 @@
 )]
 #![cfg_attr(docsrs, feature(doc_cfg))]
 
+mod app;
+pub mod auth;
+mod binding;
+mod constraint;
+mod cors;
 mod error;
+mod extract;
+mod form;
 pub mod http;
+#[cfg(feature = "cookies")]
+mod jar;
+mod json;
+#[cfg_attr(docsrs, doc(cfg(feature = "jwt")))]
+#[cfg(feature = "jwt")]
+pub mod jwt;
 mod middleware;
+pub mod multipart;
+#[cfg_attr(docsrs, doc(cfg(feature = "openapi")))]
+#[cfg(feature = "openapi")]
+pub mod openapi;
+mod path;
 mod query;
+pub mod rate_limit;
+mod registry;
 mod route;
+mod state;
+mod table;
+mod url;
 mod utils;
+#[cfg(feature = "validate")]
+mod valid;
 
 #[doc(hidden)]
 mod internal;
 
-pub use crate::http::{HttpHeaders, HttpRequest, HttpResponse, Responder, ResponseError};
+pub use crate::http::{
+    HttpHeaders, HttpRequest, HttpResponse, Responder, ResponseError, TypedHeader,
+};
+pub use app::App;
+pub use auth::{ApiKey, BasicAuth, BearerAuth};
+#[cfg_attr(docsrs, doc(cfg(feature = "d1")))]
+#[cfg(feature = "d1")]
+pub use binding::D1;
+pub use binding::{Binding, Bucket, Kv, Secret, Var};
+pub use cors::CorsPolicy;
 pub use error::{Error, ErrorCause};
-pub use middleware::Wrap;
+pub use extract::FromRequest;
+pub use form::Form;
+#[cfg_attr(docsrs, doc(cfg(feature = "cookies")))]
+#[cfg(feature = "cookies")]
+pub use jar::{CookieJar, PrivateCookieJar, SignedCookieJar};
+pub use json::Json;
+#[cfg_attr(docsrs, doc(cfg(feature = "jwt")))]
+#[cfg(feature = "jwt")]
+pub use jwt::Jwt;
+pub use middleware::{Middleware, Wrap};
+pub use multipart::{Multipart, MultipartForm};
+pub use path::{Path, PathParams};
 pub use query::Query;
+pub use rate_limit::RateLimit;
 pub use route::{Configure, Service};
-pub use worker_route_macro::{delete, get, head, options, patch, post, put, route};
+pub use state::{FromRef, State};
+pub use table::{CorsMode, RouteInfo, RouteTable};
+pub use url::RouteUrl;
+#[cfg_attr(docsrs, doc(cfg(feature = "validate")))]
+#[cfg(feature = "validate")]
+pub use valid::Valid;
+pub use worker_route_macro::{
+    connect, delete, fallback, get, handlers, head, options, patch, post, put, route, scope, trace,
+    MultipartForm, PathParams,
+};
 
 #[doc(hidden)]
 pub mod __private {
-    pub use crate::internal::{respond_async, responder, FnType};
-    pub use crate::route::{AddHandler, RouteFactory};
+    pub use crate::constraint::{constrain, Constraint};
+    pub use crate::internal::{extract, respond_async, responder};
+    pub use crate::middleware::{middleware, wrap, DynMiddleware};
+    pub use crate::path::{has_param, NoPathParams, PathParamsOf};
+    pub use crate::route::{dispatch, is_custom, AddHandler, RouteFactory};
 }
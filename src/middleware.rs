@@ -1,8 +1,14 @@
-use worker::Request;
+use crate::error::Error;
+use crate::http::{HttpRequest, HttpResponse, ResponseError};
+
+use core::future::Future;
+use futures::future::LocalBoxFuture;
+use worker::{Cors, Request, Response, RouteContext};
 
 /// A handler middleware provides an access to [`worker::Request`](https://docs.rs/worker/latest/worker/struct.Request.html)
 ///
-/// Currently this is only used to return [`Cors`](https://docs.rs/worker/latest/worker/struct.Cors.html)
+/// Used to return the [`Cors`](https://docs.rs/worker/latest/worker/struct.Cors.html) of the `cors` attribute,
+/// see [`Middleware`] to run code around a handler.
 ///
 /// # Examples
 /// ```
@@ -32,3 +38,441 @@ pub trait Wrap {
 
     fn wrap(req: &Request) -> Self::Output;
 }
+
+/// Runs before and after a handler, attached to a route with `wrap = [..]`
+/// or to every route of an app with [`App::wrap`](crate::App::wrap).
+///
+/// The global middlewares run first, then the route's in the declared order.
+/// Their [`after`](Middleware::after) runs in the reverse order,
+/// only for the middlewares whose [`before`](Middleware::before) ran.
+///
+/// # Examples
+/// ```
+/// use worker::{Request, Response, RouteContext};
+/// use worker_route::{get, http::StatusCode, Error, HttpRequest, HttpResponse, Middleware};
+/// use worker_route::http::ResponseBuilder;
+///
+/// struct RequireKey;
+///
+/// impl<D> Middleware<D> for RequireKey {
+///     async fn before(
+///         &self,
+///         req: &mut Request,
+///         _: &RouteContext<D>,
+///     ) -> Result<Option<HttpResponse>, Error> {
+///         if req.headers().get("x-api-key")?.is_some() {
+///             return Ok(None);
+///         }
+///
+///         Ok(Some(ResponseBuilder::new(StatusCode::UNAUTHORIZED).text("missing api key")))
+///     }
+/// }
+///
+/// struct PoweredBy;
+///
+/// impl<D> Middleware<D> for PoweredBy {
+///     async fn after(&self, _: &HttpRequest, mut res: Response) -> Result<Response, Error> {
+///         res.headers_mut().set("x-powered-by", "worker-route")?;
+///         Ok(res)
+///     }
+/// }
+///
+/// #[get("/secret", wrap = [PoweredBy, RequireKey])]
+/// async fn secret() -> worker::Result<String> {
+///     Ok("secret".to_owned())
+/// }
+/// ```
+pub trait Middleware<D> {
+    /// Runs before the handler, the request can be modified.
+    ///
+    /// Returning a response skips the handler and the middlewares after this one.
+    fn before(
+        &self,
+        req: &mut Request,
+        ctx: &RouteContext<D>,
+    ) -> impl Future<Output = Result<Option<HttpResponse>, Error>> {
+        let _ = (req, ctx);
+        async { Ok(None) }
+    }
+
+    /// Runs after the handler, or after a middleware returned a response.
+    ///
+    /// `req` is the request as it was given to the handler.
+    fn after(
+        &self,
+        req: &HttpRequest,
+        res: Response,
+    ) -> impl Future<Output = Result<Response, Error>> {
+        let _ = req;
+        async { Ok(res) }
+    }
+}
+
+/// Applies the `Cors` to every response, eg: `app.wrap(Cors::new().with_origins(["*"]))`.
+impl<D> Middleware<D> for Cors {
+    #[allow(clippy::future_not_send)]
+    async fn after(&self, _: &HttpRequest, res: Response) -> Result<Response, Error> {
+        Ok(res.with_cors(self)?)
+    }
+}
+
+#[doc(hidden)]
+/// Used for code generation, not for public usage.
+///
+/// A [`Middleware`] as a trait object.
+pub trait DynMiddleware<D> {
+    /// Used for code generation, not for public usage.
+    fn before<'a>(
+        &'a self,
+        req: &'a mut Request,
+        ctx: &'a RouteContext<D>,
+    ) -> LocalBoxFuture<'a, Result<Option<HttpResponse>, Error>>;
+    /// Used for code generation, not for public usage.
+    fn after<'a>(
+        &'a self,
+        req: &'a HttpRequest,
+        res: Response,
+    ) -> LocalBoxFuture<'a, Result<Response, Error>>;
+}
+
+impl<D: 'static, M: Middleware<D>> DynMiddleware<D> for M {
+    fn before<'a>(
+        &'a self,
+        req: &'a mut Request,
+        ctx: &'a RouteContext<D>,
+    ) -> LocalBoxFuture<'a, Result<Option<HttpResponse>, Error>> {
+        Box::pin(Middleware::before(self, req, ctx))
+    }
+
+    fn after<'a>(
+        &'a self,
+        req: &'a HttpRequest,
+        res: Response,
+    ) -> LocalBoxFuture<'a, Result<Response, Error>> {
+        Box::pin(Middleware::after(self, req, res))
+    }
+}
+
+#[doc(hidden)]
+/// Used for code generation, not for public usage.
+///
+/// The middleware of a route's `wrap` attribute, its type is checked against [`Middleware`].
+pub fn middleware<D: 'static, M: Middleware<D>>(middleware: &M) -> &dyn DynMiddleware<D> {
+    middleware
+}
+
+/// A step of the stack run by [`wrap`], generic over the request and response
+/// so the order of the hooks doesn't depend on a worker runtime.
+trait Layer {
+    type Request;
+    type Context;
+    /// The request as it was given to the handler.
+    type Snapshot;
+    type Response;
+
+    fn before<'a>(
+        &'a self,
+        req: &'a mut Self::Request,
+        ctx: &'a Self::Context,
+    ) -> LocalBoxFuture<'a, Result<Option<Self::Response>, Error>>;
+    fn after<'a>(
+        &'a self,
+        req: &'a Self::Snapshot,
+        res: Self::Response,
+    ) -> LocalBoxFuture<'a, Result<Self::Response, Error>>;
+    fn snapshot(req: &Self::Request) -> Self::Snapshot;
+    fn error_response(req: &Self::Snapshot, err: &Error) -> Self::Response;
+}
+
+impl<D: 'static> Layer for dyn DynMiddleware<D> + '_ {
+    type Request = Request;
+    type Context = RouteContext<D>;
+    type Snapshot = HttpRequest;
+    type Response = Response;
+
+    fn before<'a>(
+        &'a self,
+        req: &'a mut Request,
+        ctx: &'a RouteContext<D>,
+    ) -> LocalBoxFuture<'a, Result<Option<Response>, Error>> {
+        Box::pin(async move {
+            let res = DynMiddleware::before(self, req, ctx).await?;
+            Ok(res.map(HttpResponse::into_res))
+        })
+    }
+
+    fn after<'a>(
+        &'a self,
+        req: &'a HttpRequest,
+        res: Response,
+    ) -> LocalBoxFuture<'a, Result<Response, Error>> {
+        DynMiddleware::after(self, req, res)
+    }
+
+    fn snapshot(req: &Request) -> HttpRequest {
+        HttpRequest::from(req)
+    }
+
+    fn error_response(req: &HttpRequest, err: &Error) -> Response {
+        err.error_response(req.clone()).into_res()
+    }
+}
+
+/// Runs the `before` of the stack until one returns a response or an error,
+/// the handler if none did, then the `after` of those that ran in the reverse order.
+///
+/// An error of a hook or of the handler becomes its response, so every `after` that
+/// should run does.
+#[allow(clippy::future_not_send)]
+async fn around<L, F, U>(
+    stack: &[&L],
+    mut req: L::Request,
+    ctx: L::Context,
+    handler: F,
+) -> L::Response
+where
+    L: Layer + ?Sized,
+    F: FnOnce(L::Request, L::Context) -> U,
+    U: Future<Output = Result<L::Response, Error>>,
+{
+    let mut ran = 0;
+    let mut early = None;
+    for layer in stack {
+        match layer.before(&mut req, &ctx).await {
+            Ok(None) => ran += 1,
+            Ok(Some(res)) => {
+                early = Some(res);
+                break;
+            }
+            Err(err) => {
+                early = Some(L::error_response(&L::snapshot(&req), &err));
+                break;
+            }
+        }
+    }
+
+    let snapshot = L::snapshot(&req);
+    let mut res = match early {
+        Some(res) => res,
+        None => match handler(req, ctx).await {
+            Ok(res) => res,
+            Err(err) => L::error_response(&snapshot, &err),
+        },
+    };
+    for layer in stack[..ran].iter().rev() {
+        res = match layer.after(&snapshot, res).await {
+            Ok(res) => res,
+            Err(err) => L::error_response(&snapshot, &err),
+        };
+    }
+
+    res
+}
+
+#[doc(hidden)]
+/// Used for code generation, not for public usage.
+///
+/// Runs the global middlewares of the [`App`](crate::App) whose router runs and the route's around the handler.
+#[allow(clippy::future_not_send)]
+pub async fn wrap<D: 'static, F, U>(
+    req: Request,
+    ctx: RouteContext<D>,
+    route: &[&dyn DynMiddleware<D>],
+    handler: F,
+) -> worker::Result<Response>
+where
+    F: FnOnce(Request, RouteContext<D>) -> U,
+    U: Future<Output = worker::Result<Response>>,
+{
+    let global = crate::registry::middleware::<D>();
+    if global.is_empty() && route.is_empty() {
+        return handler(req, ctx).await;
+    }
+
+    let stack = global
+        .iter()
+        .map(|v| &**v)
+        .chain(route.iter().copied())
+        .collect::<Vec<_>>();
+    let res = around(&stack, req, ctx, |req, ctx| async move {
+        handler(req, ctx).await.map_err(Error::from)
+    })
+    .await;
+
+    Ok(res)
+}
+
+#[cfg(test)]
+mod test {
+    use super::{around, Layer};
+    use crate::error::Error;
+    use crate::http::ResponseError;
+    use core::cell::RefCell;
+    use futures::executor::block_on;
+    use futures::future::LocalBoxFuture;
+    use worker::Error as WorkerError;
+
+    /// Records its hooks into `log`, `stop` makes its `before` return a response or an error.
+    struct Step<'l> {
+        name: &'static str,
+        log: &'l RefCell<Vec<String>>,
+        stop: Option<Result<String, String>>,
+    }
+
+    impl<'l> Step<'l> {
+        const fn new(name: &'static str, log: &'l RefCell<Vec<String>>) -> Self {
+            Self {
+                name,
+                log,
+                stop: None,
+            }
+        }
+    }
+
+    impl Layer for Step<'_> {
+        type Request = String;
+        type Context = ();
+        type Snapshot = String;
+        type Response = String;
+
+        fn before<'a>(
+            &'a self,
+            req: &'a mut String,
+            (): &'a (),
+        ) -> LocalBoxFuture<'a, Result<Option<String>, Error>> {
+            Box::pin(async move {
+                self.log.borrow_mut().push(format!("before {}", self.name));
+                req.push_str(self.name);
+                match &self.stop {
+                    None => Ok(None),
+                    Some(Ok(res)) => Ok(Some(res.clone())),
+                    Some(Err(err)) => Err(WorkerError::RustError(err.clone()).into()),
+                }
+            })
+        }
+
+        fn after<'a>(
+            &'a self,
+            req: &'a String,
+            res: String,
+        ) -> LocalBoxFuture<'a, Result<String, Error>> {
+            Box::pin(async move {
+                self.log
+                    .borrow_mut()
+                    .push(format!("after {} {req} {res}", self.name));
+                Ok(format!("{res}{}", self.name))
+            })
+        }
+
+        fn snapshot(req: &String) -> String {
+            req.clone()
+        }
+
+        fn error_response(_: &String, err: &Error) -> String {
+            format!("error {}", err.status_code().as_u16())
+        }
+    }
+
+    fn handler<'l>(
+        log: &'l RefCell<Vec<String>>,
+        res: Result<&'static str, &'static str>,
+    ) -> impl FnOnce(String, ()) -> LocalBoxFuture<'static, Result<String, Error>> + 'l {
+        move |req, ()| {
+            log.borrow_mut().push(format!("handler {req}"));
+            Box::pin(async move {
+                res.map(ToOwned::to_owned)
+                    .map_err(|err| WorkerError::RustError(err.to_owned()).into())
+            })
+        }
+    }
+
+    #[test]
+    fn order() {
+        let log = RefCell::new(vec![]);
+        let (a, b) = (Step::new("a", &log), Step::new("b", &log));
+
+        let res = block_on(around(
+            &[&a, &b],
+            "/".to_owned(),
+            (),
+            handler(&log, Ok("ok")),
+        ));
+        assert_eq!(res, "okba");
+        assert_eq!(
+            log.into_inner(),
+            [
+                "before a",
+                "before b",
+                "handler /ab",
+                "after b /ab ok",
+                "after a /ab okb"
+            ]
+        );
+    }
+
+    #[test]
+    fn early_exit() {
+        let log = RefCell::new(vec![]);
+        let a = Step::new("a", &log);
+        let b = Step {
+            stop: Some(Ok("denied".to_owned())),
+            ..Step::new("b", &log)
+        };
+        let c = Step::new("c", &log);
+
+        let res = block_on(around(
+            &[&a, &b, &c],
+            "/".to_owned(),
+            (),
+            handler(&log, Ok("ok")),
+        ));
+        assert_eq!(res, "denieda");
+        assert_eq!(
+            log.into_inner(),
+            ["before a", "before b", "after a /ab denied"]
+        );
+
+        let log = RefCell::new(vec![]);
+        let a = Step::new("a", &log);
+        let b = Step {
+            stop: Some(Err("invalid".to_owned())),
+            ..Step::new("b", &log)
+        };
+
+        let res = block_on(around(
+            &[&a, &b],
+            "/".to_owned(),
+            (),
+            handler(&log, Ok("ok")),
+        ));
+        assert_eq!(res, "error 500a");
+        assert_eq!(
+            log.into_inner(),
+            ["before a", "before b", "after a /ab error 500"]
+        );
+    }
+
+    #[test]
+    fn handler_error() {
+        let log = RefCell::new(vec![]);
+        let (a, b) = (Step::new("a", &log), Step::new("b", &log));
+
+        let res = block_on(around(
+            &[&a, &b],
+            "/".to_owned(),
+            (),
+            handler(&log, Err("failed")),
+        ));
+        assert_eq!(res, "error 500ba");
+        assert_eq!(
+            log.into_inner(),
+            [
+                "before a",
+                "before b",
+                "handler /ab",
+                "after b /ab error 500",
+                "after a /ab error 500b"
+            ]
+        );
+    }
+}
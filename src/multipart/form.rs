@@ -0,0 +1,253 @@
+use super::{error, Field, Multipart, MultipartLimits};
+use crate::error::Error;
+use crate::extract::FromRequest;
+
+use core::future::Future;
+use http::StatusCode;
+use std::ops::{Deref, DerefMut};
+use worker::{Request, RouteContext};
+
+/// Extract a `multipart/form-data` body into a typed struct.
+///
+/// `T` must implement [`MultipartCollect`], which is usually derived with
+/// [`#[derive(MultipartForm)]`](worker_route_macro::MultipartForm).
+/// Every field of `T` must implement [`FieldCollect`]:
+/// - `String`, `Vec<u8>` and [`File`] are required, exactly one part is expected.
+/// - `Option<T>` may be missing.
+/// - `Vec<T>` collects every part of the same name.
+///
+/// Parts which don't match any field are skipped.
+///
+/// ```
+/// use worker::{Response, Result, RouteContext};
+/// use worker_route::{post, multipart::File, MultipartForm};
+///
+/// #[derive(MultipartForm)]
+/// #[multipart(max_body = 1048576, max_parts = 10)]
+/// struct Upload {
+///     title: String,
+///     description: Option<String>,
+///     #[multipart(rename = "file")]
+///     files: Vec<File>,
+/// }
+///
+/// #[post("/upload")]
+/// async fn upload(req: MultipartForm<Upload>, _: RouteContext<()>) -> Result<Response> {
+///     let Upload { title, files, .. } = req.into_inner();
+///     // rest code
+///     Response::empty()
+/// }
+/// ```
+///
+/// # Attributes
+/// - `#[multipart(max_body = usize, max_parts = usize)]` on the struct: see [`MultipartLimits`].
+/// - `#[multipart(rename = "name")]` on a field: the name of the part, defaults to the field's name.
+///
+/// # Notes
+/// The body is streamed like a [`Multipart`]'s, it must be the handler's only body extractor.
+#[derive(Debug, Clone)]
+pub struct MultipartForm<T>(T);
+
+impl<T> MultipartForm<T> {
+    #[allow(clippy::missing_const_for_fn)]
+    /// Acess the owned `T`
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+/// Types that can be collected from a [`Multipart`] body, see [`MultipartForm`].
+pub trait MultipartCollect: Sized {
+    /// The limits applied while reading the body.
+    fn limits() -> MultipartLimits {
+        MultipartLimits::default()
+    }
+
+    /// Collect `Self` from every part of the body.
+    fn collect(multipart: Multipart) -> impl Future<Output = Result<Self, Error>>;
+}
+
+impl<D, T: MultipartCollect> FromRequest<D> for MultipartForm<T> {
+    type Error = Error;
+
+    #[allow(clippy::future_not_send)]
+    async fn from_request(req: &Request, _: &RouteContext<D>) -> Result<Self, Self::Error> {
+        let multipart = Multipart::from_request_body(req)?.limits(T::limits());
+        Ok(Self(T::collect(multipart).await?))
+    }
+
+    fn status_codes() -> Vec<StatusCode> {
+        <Multipart as FromRequest<D>>::status_codes()
+    }
+}
+
+/// An uploaded file, collected into memory.
+#[derive(Debug, Clone)]
+pub struct File {
+    name: Option<String>,
+    content_type: Option<String>,
+    bytes: Vec<u8>,
+}
+
+impl File {
+    /// The file name of the part.
+    pub fn file_name(&self) -> Option<&str> {
+        self.name.as_deref()
+    }
+
+    /// The `Content-Type` of the part.
+    pub fn content_type(&self) -> Option<&str> {
+        self.content_type.as_deref()
+    }
+
+    /// The content of the file.
+    pub fn bytes(&self) -> &[u8] {
+        &self.bytes
+    }
+
+    /// Acess the owned content of the file.
+    pub fn into_bytes(self) -> Vec<u8> {
+        self.bytes
+    }
+}
+
+/// Types that can be read from a single [`Field`].
+pub trait FromField: Sized {
+    /// Reads `Self` from the field.
+    fn from_field(field: Field<'_>) -> impl Future<Output = Result<Self, Error>>;
+}
+
+impl FromField for String {
+    #[allow(clippy::future_not_send)]
+    async fn from_field(field: Field<'_>) -> Result<Self, Error> {
+        field.text().await
+    }
+}
+
+impl FromField for Vec<u8> {
+    #[allow(clippy::future_not_send)]
+    async fn from_field(field: Field<'_>) -> Result<Self, Error> {
+        field.bytes().await
+    }
+}
+
+impl FromField for File {
+    #[allow(clippy::future_not_send)]
+    async fn from_field(field: Field<'_>) -> Result<Self, Error> {
+        Ok(Self {
+            name: field.file_name().map(ToOwned::to_owned),
+            content_type: field.content_type().map(ToOwned::to_owned),
+            bytes: field.bytes().await?,
+        })
+    }
+}
+
+/// Types that can be built from every part of the same name.
+pub trait FieldCollect: Sized {
+    /// The type read from each part.
+    type Item: FromField;
+
+    /// Builds `Self` from the parts named `name`.
+    ///
+    /// # Errors
+    /// A `400 Bad Request` is returned if the number of parts is not what `Self` expects.
+    fn collect(name: &str, items: Vec<Self::Item>) -> Result<Self, Error>;
+}
+
+impl<T: FromField> FieldCollect for T {
+    type Item = T;
+
+    fn collect(name: &str, mut items: Vec<Self::Item>) -> Result<Self, Error> {
+        match items.len() {
+            0 => Err(error(
+                format!("missing field `{name}`"),
+                StatusCode::BAD_REQUEST,
+            )),
+            1 => Ok(items.remove(0)),
+            _ => Err(error(
+                format!("duplicate field `{name}`"),
+                StatusCode::BAD_REQUEST,
+            )),
+        }
+    }
+}
+
+impl<T: FromField> FieldCollect for Option<T> {
+    type Item = T;
+
+    fn collect(name: &str, items: Vec<Self::Item>) -> Result<Self, Error> {
+        if items.is_empty() {
+            Ok(None)
+        } else {
+            T::collect(name, items).map(Some)
+        }
+    }
+}
+
+impl<T: FromField> FieldCollect for Vec<T> {
+    type Item = T;
+
+    fn collect(_: &str, items: Vec<Self::Item>) -> Result<Self, Error> {
+        Ok(items)
+    }
+}
+
+impl<T> Deref for MultipartForm<T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<T> DerefMut for MultipartForm<T> {
+    fn deref_mut(&mut self) -> &mut T {
+        &mut self.0
+    }
+}
+
+impl<T> AsRef<T> for MultipartForm<T> {
+    fn as_ref(&self) -> &T {
+        &self.0
+    }
+}
+
+impl<T> AsMut<T> for MultipartForm<T> {
+    fn as_mut(&mut self) -> &mut T {
+        &mut self.0
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::FieldCollect;
+    use crate::{http::StatusCode, ResponseError};
+
+    fn items(n: usize) -> Vec<String> {
+        (0..n).map(|v| v.to_string()).collect()
+    }
+
+    #[test]
+    fn required_field() {
+        assert_eq!(String::collect("title", items(1)).unwrap(), "0");
+
+        let missing = String::collect("title", items(0)).unwrap_err();
+        let duplicate = String::collect("title", items(2)).unwrap_err();
+        assert_eq!(missing.status_code(), StatusCode::BAD_REQUEST);
+        assert_eq!(duplicate.status_code(), StatusCode::BAD_REQUEST);
+    }
+
+    #[test]
+    fn optional_field() {
+        assert_eq!(Option::<String>::collect("title", items(0)).unwrap(), None);
+        assert!(Option::<String>::collect("title", items(2)).is_err());
+    }
+
+    #[test]
+    fn multiple_fields() {
+        assert_eq!(Vec::<String>::collect("title", items(3)).unwrap().len(), 3);
+        assert!(Vec::<String>::collect("title", items(0))
+            .unwrap()
+            .is_empty());
+    }
+}
@@ -0,0 +1,712 @@
+//! Extract `multipart/form-data` bodies, see [`Multipart`] and [`MultipartForm`].
+mod form;
+
+pub use form::{FieldCollect, File, FromField, MultipartCollect, MultipartForm};
+
+use crate::error::{Error, ErrorCause};
+use crate::extract::FromRequest;
+
+use core::pin::Pin;
+use futures::{Stream, StreamExt};
+use http::header::{HeaderMap, HeaderName, HeaderValue, CONTENT_DISPOSITION, CONTENT_TYPE};
+use http::StatusCode;
+use mime::Mime;
+use percent_encoding::percent_decode_str;
+use worker::{Request, RouteContext};
+
+type ByteStream = Pin<Box<dyn Stream<Item = worker::Result<Vec<u8>>>>>;
+
+// headers of a single part are not expected to be larger than this
+const MAX_HEADERS_SIZE: usize = 8 * 1024;
+
+/// Limits applied while reading a [`Multipart`] body.
+///
+/// Exceeding any of them returns a `413 Payload Too Large` error.
+#[derive(Debug, Clone, Copy)]
+pub struct MultipartLimits {
+    body: usize,
+    parts: usize,
+}
+
+impl MultipartLimits {
+    /// Creates the default limits, `10 MiB` body and `100` parts.
+    pub const fn new() -> Self {
+        Self {
+            body: 10 * 1024 * 1024,
+            parts: 100,
+        }
+    }
+
+    /// Sets the maximum number of bytes read from the body.
+    pub const fn body(mut self, body: usize) -> Self {
+        self.body = body;
+        self
+    }
+
+    /// Sets the maximum number of parts.
+    pub const fn parts(mut self, parts: usize) -> Self {
+        self.parts = parts;
+        self
+    }
+}
+
+impl Default for MultipartLimits {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum State {
+    // nothing has been read yet
+    Preamble,
+    // a boundary has just been read, either a part or the end follows
+    Boundary,
+    // a part's body is being read
+    Body,
+    Done,
+}
+
+/// Extract a `multipart/form-data` body, part by part.
+///
+/// The body is streamed, a part's content is only read when asked for,
+/// the remaining content of a part is skipped when moving on to the next one.
+///
+/// ```
+/// use worker::{console_log, Response, Result, RouteContext};
+/// use worker_route::{post, multipart::Multipart};
+///
+/// #[post("/upload")]
+/// async fn upload(mut multipart: Multipart, _: RouteContext<()>) -> Result<Response> {
+///     while let Some(mut field) = multipart.next_field().await? {
+///         console_log!("name: {}, file: {:?}", field.name(), field.file_name());
+///         while let Some(chunk) = field.chunk().await? {
+///             // rest code
+///         }
+///     }
+///     Response::empty()
+/// }
+/// ```
+///
+/// # Notes
+/// The extractor uses the default [`MultipartLimits`],
+/// use [`Multipart::from_body`] and [`Multipart::limits`] for custom ones.
+///
+/// Unlike [`Json`](crate::Json), the extractor streams the request's own body rather than a clone,
+/// so it isn't buffered, and the handler can't read the body of its `Request` as well.
+/// It must be the handler's only body extractor: a [`Json`](crate::Json), [`Form`](crate::Form)
+/// or another `Multipart` extracted after it fails with a `500 Internal Server Error` of [`ErrorCause::Body`](crate::ErrorCause::Body).
+pub struct Multipart {
+    stream: ByteStream,
+    buf: Vec<u8>,
+    // "--" + boundary
+    boundary: Box<[u8]>,
+    limits: MultipartLimits,
+    read: usize,
+    parts: usize,
+    state: State,
+}
+
+fn error(message: impl Into<String>, status_code: StatusCode) -> Error {
+    Error::new(message.into(), status_code, ErrorCause::Multipart)
+}
+
+fn find(buf: &[u8], needle: &[u8]) -> Option<usize> {
+    buf.windows(needle.len()).position(|v| v == needle)
+}
+
+// `<[u8]>::trim_ascii` needs Rust 1.80
+fn trim(mut bytes: &[u8]) -> &[u8] {
+    while let [first, rest @ ..] = bytes {
+        if !first.is_ascii_whitespace() {
+            break;
+        }
+        bytes = rest;
+    }
+    while let [rest @ .., last] = bytes {
+        if !last.is_ascii_whitespace() {
+            break;
+        }
+        bytes = rest;
+    }
+
+    bytes
+}
+
+fn boundary(content_type: Option<&str>) -> Option<String> {
+    let mime = content_type?.parse::<Mime>().ok()?;
+    if mime.type_() != mime::MULTIPART || mime.subtype() != mime::FORM_DATA {
+        return None;
+    }
+
+    mime.get_param(mime::BOUNDARY).map(|v| v.to_string())
+}
+
+impl Multipart {
+    /// Creates a new [`Multipart`] from the request's `Content-Type` and a stream of its body.
+    ///
+    /// # Errors
+    /// A `415 Unsupported Media Type` error is returned if the `Content-Type` is not
+    /// `multipart/form-data` or has no boundary.
+    pub fn new(
+        content_type: Option<&str>,
+        stream: impl Stream<Item = worker::Result<Vec<u8>>> + 'static,
+    ) -> Result<Self, Error> {
+        let Some(boundary) = boundary(content_type) else {
+            return Err(error(
+                format!("expected `{CONTENT_TYPE}: multipart/form-data; boundary=...`"),
+                StatusCode::UNSUPPORTED_MEDIA_TYPE,
+            ));
+        };
+
+        Ok(Self {
+            stream: Box::pin(stream),
+            buf: Vec::new(),
+            boundary: format!("--{boundary}").into_bytes().into(),
+            limits: MultipartLimits::default(),
+            read: 0,
+            parts: 0,
+            state: State::Preamble,
+        })
+    }
+
+    /// Creates a new [`Multipart`] streaming the request's body.
+    ///
+    /// ```
+    /// use worker::{Request, Response, Result, RouteContext};
+    /// use worker_route::{post, multipart::{Multipart, MultipartLimits}};
+    ///
+    /// #[post("/upload")]
+    /// async fn upload(mut req: Request, _: RouteContext<()>) -> Result<Response> {
+    ///     let limits = MultipartLimits::new().body(1024 * 1024).parts(2);
+    ///     let mut multipart = Multipart::from_body(&mut req)?.limits(limits);
+    ///     while let Some(field) = multipart.next_field().await? {
+    ///         let bytes = field.bytes().await?;
+    ///         // rest code
+    ///     }
+    ///     Response::empty()
+    /// }
+    /// ```
+    ///
+    /// # Errors
+    /// See [`Multipart::new`], errors are also returned if the body can't be streamed.
+    pub fn from_body(req: &mut Request) -> Result<Self, Error> {
+        let content_type = req.headers().get(CONTENT_TYPE.as_str()).unwrap_or(None);
+        Self::new(content_type.as_deref(), req.stream()?)
+    }
+
+    // streams the request's own body through another handle of the same JS request,
+    // a `Request::clone` would tee it and the runtime would buffer the branch which is never read
+    pub(crate) fn from_request_body(req: &Request) -> Result<Self, Error> {
+        if req.inner().body_used() {
+            return Err(Error::body_used());
+        }
+
+        // not the inherent `web_sys::Request::clone`, which is `Request.clone()`
+        let handle = Clone::clone(req.inner());
+        Self::from_body(&mut Request::from(handle))
+    }
+
+    /// Sets the limits applied while reading the body.
+    pub fn limits(mut self, limits: MultipartLimits) -> Self {
+        self.limits = limits;
+        self
+    }
+
+    // pulls the next chunk into the buffer, returns `false` once the stream is exhausted
+    #[allow(clippy::future_not_send)]
+    async fn fill(&mut self) -> Result<bool, Error> {
+        match self.stream.next().await {
+            Some(chunk) => {
+                let chunk = chunk?;
+                self.read += chunk.len();
+                if self.read > self.limits.body {
+                    return Err(error(
+                        format!("body exceeds the limit of {} bytes", self.limits.body),
+                        StatusCode::PAYLOAD_TOO_LARGE,
+                    ));
+                }
+                self.buf.extend_from_slice(&chunk);
+                Ok(true)
+            }
+            None => Ok(false),
+        }
+    }
+
+    #[allow(clippy::future_not_send)]
+    async fn fill_or_err(&mut self) -> Result<(), Error> {
+        if self.fill().await? {
+            Ok(())
+        } else {
+            Err(error(
+                "unexpected end of multipart body",
+                StatusCode::BAD_REQUEST,
+            ))
+        }
+    }
+
+    #[allow(clippy::future_not_send)]
+    async fn read_preamble(&mut self) -> Result<(), Error> {
+        loop {
+            if let Some(pos) = find(&self.buf, &self.boundary) {
+                self.buf.drain(..pos + self.boundary.len());
+                self.state = State::Boundary;
+                return Ok(());
+            }
+            // keep a tail that may be the start of the boundary
+            let keep = self.boundary.len().min(self.buf.len());
+            self.buf.drain(..self.buf.len() - keep);
+            self.fill_or_err().await?;
+        }
+    }
+
+    #[allow(clippy::future_not_send)]
+    async fn read_headers(&mut self) -> Result<HeaderMap, Error> {
+        let end = loop {
+            if let Some(pos) = find(&self.buf, b"\r\n\r\n") {
+                break pos;
+            }
+            if self.buf.len() > MAX_HEADERS_SIZE {
+                return Err(error(
+                    format!("part headers exceed the limit of {MAX_HEADERS_SIZE} bytes"),
+                    StatusCode::PAYLOAD_TOO_LARGE,
+                ));
+            }
+            self.fill_or_err().await?;
+        };
+
+        let raw = self.buf.drain(..end + 4).collect::<Vec<_>>();
+        let mut headers = HeaderMap::new();
+        for line in raw[..end].split(|v| *v == b'\n') {
+            let line = line.strip_suffix(b"\r").unwrap_or(line);
+            if line.is_empty() {
+                continue;
+            }
+            let Some(colon) = line.iter().position(|v| *v == b':') else {
+                return Err(error("invalid part header", StatusCode::BAD_REQUEST));
+            };
+            let name = HeaderName::from_bytes(&line[..colon]);
+            let value = HeaderValue::from_bytes(trim(&line[colon + 1..]));
+            match (name, value) {
+                (Ok(name), Ok(value)) => {
+                    headers.append(name, value);
+                }
+                _ => return Err(error("invalid part header", StatusCode::BAD_REQUEST)),
+            }
+        }
+
+        Ok(headers)
+    }
+
+    /// Returns the next part of the body, `None` once every part has been read.
+    ///
+    /// # Errors
+    /// - `400 Bad Request` if the body is not a valid `multipart/form-data`.
+    /// - `413 Payload Too Large` if any of the [`MultipartLimits`] is exceeded.
+    #[allow(clippy::future_not_send)]
+    pub async fn next_field(&mut self) -> Result<Option<Field<'_>>, Error> {
+        // skip what's left of the previous part
+        while self.state == State::Body {
+            self.read_chunk().await?;
+        }
+
+        if self.state == State::Preamble {
+            self.read_preamble().await?;
+        }
+
+        if self.state == State::Done {
+            return Ok(None);
+        }
+
+        while self.buf.len() < 2 {
+            self.fill_or_err().await?;
+        }
+        if self.buf.starts_with(b"--") {
+            self.state = State::Done;
+            return Ok(None);
+        }
+        if !self.buf.starts_with(b"\r\n") {
+            return Err(error("invalid multipart boundary", StatusCode::BAD_REQUEST));
+        }
+        self.buf.drain(..2);
+
+        self.parts += 1;
+        if self.parts > self.limits.parts {
+            return Err(error(
+                format!("body exceeds the limit of {} parts", self.limits.parts),
+                StatusCode::PAYLOAD_TOO_LARGE,
+            ));
+        }
+
+        let headers = self.read_headers().await?;
+        let disposition = headers
+            .get(CONTENT_DISPOSITION)
+            .and_then(|v| v.to_str().ok())
+            .unwrap_or_default();
+        let (name, file_name) = parse_disposition(disposition);
+        let Some(name) = name else {
+            return Err(error(
+                format!("missing `{CONTENT_DISPOSITION}` name of a part"),
+                StatusCode::BAD_REQUEST,
+            ));
+        };
+
+        self.state = State::Body;
+        Ok(Some(Field {
+            name,
+            file_name,
+            headers,
+            multipart: self,
+        }))
+    }
+
+    #[allow(clippy::future_not_send)]
+    async fn read_chunk(&mut self) -> Result<Option<Vec<u8>>, Error> {
+        if self.state != State::Body {
+            return Ok(None);
+        }
+
+        // the part's content ends with "\r\n--boundary"
+        let delimiter = [b"\r\n".as_slice(), &self.boundary].concat();
+        loop {
+            if let Some(pos) = find(&self.buf, &delimiter) {
+                let chunk = self.buf.drain(..pos).collect::<Vec<_>>();
+                self.buf.drain(..delimiter.len());
+                self.state = State::Boundary;
+                return Ok((!chunk.is_empty()).then_some(chunk));
+            }
+            // the tail may be the start of the delimiter
+            if self.buf.len() > delimiter.len() {
+                let end = self.buf.len() - delimiter.len();
+                return Ok(Some(self.buf.drain(..end).collect()));
+            }
+            self.fill_or_err().await?;
+        }
+    }
+}
+
+// a quoted-string without its quotes, and what follows its closing quote
+fn quoted(value: &str) -> (String, &str) {
+    let mut unquoted = String::new();
+    let mut chars = value.char_indices().peekable();
+    while let Some((i, c)) = chars.next() {
+        match c {
+            '"' => return (unquoted, &value[i + 1..]),
+            // browsers don't escape a `\` of a file name, only `\"` and `\\` are unescaped
+            '\\' if matches!(chars.peek(), Some((_, '"' | '\\'))) => {
+                unquoted.extend(chars.next().map(|v| v.1));
+            }
+            c => unquoted.push(c),
+        }
+    }
+
+    (unquoted, "")
+}
+
+// the `key=value` parameters after the disposition type, keys are lowercased
+// and a `;` within a quoted value doesn't end it
+fn params(header: &str) -> Vec<(String, String)> {
+    let mut params = Vec::new();
+    let mut rest = header.split_once(';').map_or("", |v| v.1);
+    while let Some((key, value)) = rest.split_once('=') {
+        // a parameter without a value, eg: `; foo; name=".."`
+        let key = key.rsplit(';').next().unwrap_or(key).trim();
+        let value = value.trim_start();
+        let (value, next) = value.strip_prefix('"').map_or_else(
+            || {
+                let end = value.find(';').unwrap_or(value.len());
+                (value[..end].trim().to_owned(), &value[end..])
+            },
+            quoted,
+        );
+        params.push((key.to_ascii_lowercase(), value));
+        rest = next.split_once(';').map_or("", |v| v.1);
+    }
+
+    params
+}
+
+// an RFC 5987 extended value, eg: `UTF-8''na%C3%AFve.txt`
+fn ext_value(value: &str) -> Option<String> {
+    let mut parts = value.splitn(3, '\'');
+    let (charset, _language, encoded) = (parts.next()?, parts.next()?, parts.next()?);
+    let bytes = percent_decode_str(encoded).collect::<Vec<_>>();
+    if charset.eq_ignore_ascii_case("utf-8") {
+        String::from_utf8(bytes).ok()
+    } else if charset.eq_ignore_ascii_case("iso-8859-1") {
+        Some(bytes.into_iter().map(char::from).collect())
+    } else {
+        None
+    }
+}
+
+// form-data; name="file"; filename="foo.txt"; filename*=UTF-8''foo.txt
+// `filename*` is preferred over `filename`
+fn parse_disposition(disposition: &str) -> (Option<String>, Option<String>) {
+    let mut name = None;
+    let mut file_name = None;
+    let mut ext_file_name = None;
+    for (key, value) in params(disposition) {
+        match key.as_str() {
+            "name" => name = Some(value),
+            "filename" => file_name = Some(value),
+            "filename*" => ext_file_name = ext_value(&value),
+            _ => {}
+        }
+    }
+
+    (name, ext_file_name.or(file_name))
+}
+
+impl<D> FromRequest<D> for Multipart {
+    type Error = Error;
+
+    #[allow(clippy::future_not_send)]
+    async fn from_request(req: &Request, _: &RouteContext<D>) -> Result<Self, Self::Error> {
+        Self::from_request_body(req)
+    }
+
+    fn status_codes() -> Vec<StatusCode> {
+        vec![
+            StatusCode::BAD_REQUEST,
+            StatusCode::PAYLOAD_TOO_LARGE,
+            StatusCode::UNSUPPORTED_MEDIA_TYPE,
+        ]
+    }
+}
+
+/// A single part of a [`Multipart`] body.
+pub struct Field<'a> {
+    name: String,
+    file_name: Option<String>,
+    headers: HeaderMap,
+    multipart: &'a mut Multipart,
+}
+
+impl Field<'_> {
+    /// The name of the part, from its `Content-Disposition`.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// The file name of the part, from its `Content-Disposition`.
+    pub fn file_name(&self) -> Option<&str> {
+        self.file_name.as_deref()
+    }
+
+    /// The `Content-Type` of the part.
+    pub fn content_type(&self) -> Option<&str> {
+        self.headers.get(CONTENT_TYPE).and_then(|v| v.to_str().ok())
+    }
+
+    /// All the headers of the part.
+    pub fn headers(&self) -> &HeaderMap {
+        &self.headers
+    }
+
+    /// Returns the next chunk of the part's content, `None` once it's been fully read.
+    ///
+    /// # Errors
+    /// See [`Multipart::next_field`].
+    #[allow(clippy::future_not_send)]
+    pub async fn chunk(&mut self) -> Result<Option<Vec<u8>>, Error> {
+        self.multipart.read_chunk().await
+    }
+
+    /// Reads the whole content of the part.
+    ///
+    /// # Errors
+    /// See [`Multipart::next_field`].
+    #[allow(clippy::future_not_send)]
+    pub async fn bytes(mut self) -> Result<Vec<u8>, Error> {
+        let mut bytes = Vec::new();
+        while let Some(chunk) = self.chunk().await? {
+            bytes.extend_from_slice(&chunk);
+        }
+        Ok(bytes)
+    }
+
+    /// Reads the whole content of the part as text.
+    ///
+    /// # Errors
+    /// See [`Multipart::next_field`], a `400 Bad Request` is also returned if the content is not valid UTF-8.
+    #[allow(clippy::future_not_send)]
+    pub async fn text(self) -> Result<String, Error> {
+        let name = self.name.clone();
+        String::from_utf8(self.bytes().await?).map_err(|_| {
+            error(
+                format!("field `{name}` is not valid UTF-8"),
+                StatusCode::BAD_REQUEST,
+            )
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{parse_disposition, trim, Multipart, MultipartLimits};
+    use crate::{http::StatusCode, Error, ErrorCause, ResponseError};
+
+    use futures::executor::block_on;
+    use futures::stream;
+
+    const CONTENT_TYPE: Option<&str> = Some("multipart/form-data; boundary=X-BOUNDARY");
+    const BODY: &str = "preamble\r\n--X-BOUNDARY\r\n\
+        Content-Disposition: form-data; name=\"title\"\r\n\r\n\
+        Hello\r\n\
+        --X-BOUNDARY\r\n\
+        Content-Disposition: form-data; name=\"file\"; filename=\"foo.txt\"\r\n\
+        Content-Type: text/plain\r\n\r\n\
+        line 1\r\nline 2\r\n\
+        --X-BOUNDARY--\r\n";
+
+    // splits the body into chunks of `size` bytes
+    fn multipart(size: usize) -> Multipart {
+        let chunks = BODY
+            .as_bytes()
+            .chunks(size)
+            .map(|v| Ok(v.to_vec()))
+            .collect::<Vec<_>>();
+        Multipart::new(CONTENT_TYPE, stream::iter(chunks)).unwrap()
+    }
+
+    #[test]
+    fn fields_ok() {
+        for size in [1, 3, 7, BODY.len()] {
+            let mut multipart = multipart(size);
+            block_on(async {
+                let field = multipart.next_field().await.unwrap().unwrap();
+                assert_eq!(field.name(), "title");
+                assert_eq!(field.file_name(), None);
+                assert_eq!(field.text().await.unwrap(), "Hello");
+
+                let field = multipart.next_field().await.unwrap().unwrap();
+                assert_eq!(field.name(), "file");
+                assert_eq!(field.file_name(), Some("foo.txt"));
+                assert_eq!(field.content_type(), Some("text/plain"));
+                assert_eq!(field.text().await.unwrap(), "line 1\r\nline 2");
+
+                assert!(multipart.next_field().await.unwrap().is_none());
+            });
+        }
+    }
+
+    #[test]
+    fn skip_unread_field() {
+        let mut multipart = multipart(5);
+        block_on(async {
+            multipart.next_field().await.unwrap();
+            let field = multipart.next_field().await.unwrap().unwrap();
+            assert_eq!(field.name(), "file");
+        });
+    }
+
+    #[test]
+    fn trim_ok() {
+        assert_eq!(trim(b" \t text/plain \r"), b"text/plain");
+        assert_eq!(trim(b"a b"), b"a b");
+        assert_eq!(trim(b"  "), b"");
+        assert_eq!(trim(b""), b"");
+    }
+
+    #[test]
+    fn body_used_err() {
+        let err = Error::body_used();
+        assert_eq!(err.status_code(), StatusCode::INTERNAL_SERVER_ERROR);
+        assert!(matches!(err.cause(), ErrorCause::Body));
+    }
+
+    #[test]
+    fn content_type_err() {
+        let json = Multipart::new(Some("application/json"), stream::empty());
+        let no_boundary = Multipart::new(Some("multipart/form-data"), stream::empty());
+
+        assert_eq!(
+            json.err().unwrap().status_code(),
+            StatusCode::UNSUPPORTED_MEDIA_TYPE
+        );
+        assert_eq!(
+            no_boundary.err().unwrap().status_code(),
+            StatusCode::UNSUPPORTED_MEDIA_TYPE
+        );
+    }
+
+    #[test]
+    fn limits_err() {
+        let mut body = multipart(4).limits(MultipartLimits::new().body(16));
+        let mut parts = multipart(4).limits(MultipartLimits::new().parts(1));
+        block_on(async {
+            let err = body.next_field().await.err().unwrap();
+            assert_eq!(err.status_code(), StatusCode::PAYLOAD_TOO_LARGE);
+
+            parts.next_field().await.unwrap();
+            let err = parts.next_field().await.err().unwrap();
+            assert_eq!(err.status_code(), StatusCode::PAYLOAD_TOO_LARGE);
+        });
+    }
+
+    #[test]
+    fn truncated_err() {
+        let chunks = vec![Ok(BODY.as_bytes()[..60].to_vec())];
+        let mut multipart = Multipart::new(CONTENT_TYPE, stream::iter(chunks)).unwrap();
+        block_on(async {
+            let err = multipart.next_field().await.err().unwrap();
+            assert_eq!(err.status_code(), StatusCode::BAD_REQUEST);
+        });
+    }
+
+    #[test]
+    fn disposition() {
+        let parsed = |v: &str| parse_disposition(v);
+        let some = |name: &str, file: &str| (Some(name.to_owned()), Some(file.to_owned()));
+
+        assert_eq!(
+            parsed(r#"form-data; name="file"; filename="a;b.txt""#),
+            some("file", "a;b.txt")
+        );
+        assert_eq!(
+            parsed(r#"form-data; filename="say \"hi\".txt"; name=file"#),
+            some("file", r#"say "hi".txt"#)
+        );
+        // a windows path, sent as is
+        assert_eq!(
+            parsed(r#"form-data; name="file"; filename="C:\docs\a.txt""#),
+            some("file", r"C:\docs\a.txt")
+        );
+        assert_eq!(
+            parsed(
+                r#"form-data; Name="file"; filename="naive.txt"; filename*=UTF-8''na%C3%AFve%3Bv2.txt"#
+            ),
+            some("file", "naïve;v2.txt")
+        );
+        assert_eq!(
+            parsed("form-data; name=file; filename*=iso-8859-1'en'caf%E9.txt"),
+            some("file", "café.txt")
+        );
+        // an unknown charset falls back to `filename`
+        assert_eq!(
+            parsed(r#"form-data; name=file; filename*=koi8-r''%C1.txt; filename="a.txt""#),
+            some("file", "a.txt")
+        );
+        assert_eq!(
+            parsed("form-data; inline; name=title"),
+            (Some("title".to_owned()), None)
+        );
+        assert_eq!(parsed("form-data"), (None, None));
+    }
+
+    #[test]
+    fn quoted_semicolon() {
+        let body = "--X-BOUNDARY\r\n\
+            Content-Disposition: form-data; name=\"file\"; filename=\"a;b.txt\"\r\n\r\n\
+            data\r\n\
+            --X-BOUNDARY--\r\n";
+        let chunks = vec![Ok(body.as_bytes().to_vec())];
+        let mut multipart = Multipart::new(CONTENT_TYPE, stream::iter(chunks)).unwrap();
+        block_on(async {
+            let field = multipart.next_field().await.unwrap().unwrap();
+            assert_eq!(field.file_name(), Some("a;b.txt"));
+            assert_eq!(field.text().await.unwrap(), "data");
+        });
+    }
+}
@@ -0,0 +1,695 @@
+//! Generate an [OpenAPI 3.1](https://spec.openapis.org/oas/v3.1.0) document from the route macros.
+//!
+//! Every handler configured into a [`RouteTable`](crate::RouteTable) is documented with:
+//! - its path parameters, parsed from the `:name` and `*name` segments of its path,
+//!   typed with [`Path<T>`](crate::Path) if the handler extracts it.
+//! - its query parameters, from the fields of [`Query<T>`](crate::Query).
+//! - its request body, from [`Json<T>`](crate::Json) or [`Form<T>`](crate::Form).
+//! - its success response, from the `T` of a `Result<T, E>`, `204 No Content` without a body for `()`.
+//! - its error responses, from [`FromRequest::status_codes`](crate::FromRequest::status_codes)
+//!   of its extractors and [`ResponseError::status_codes`] of `E`.
+//!
+//! The types of the query, the body and the response are documented if they implement [`Schema`],
+//! which can be derived, they are left undocumented otherwise.
+//!
+//! # Example
+//!
+//! ```
+//! use serde::{Deserialize, Serialize};
+//! use worker::{Result, Router};
+//! use worker_route::openapi::{ApiDoc, OpenApi, OpenApiRoute, Schema};
+//! use worker_route::{get, Configure, Json, Query, RouteTable, Service};
+//!
+//! #[derive(Deserialize, Schema)]
+//! struct Search {
+//!     q: String,
+//!     page: Option<u32>,
+//! }
+//!
+//! /// A registered user.
+//! #[derive(Serialize, Schema)]
+//! struct User {
+//!     id: u64,
+//!     name: String,
+//! }
+//!
+//! #[get("/users")]
+//! async fn users(search: Query<Search>) -> Result<Json<Vec<User>>> {
+//!     Ok(Json(Vec::new()))
+//! }
+//!
+//! // generic over `Configure` to be configured into both a `Router` and a `RouteTable`
+//! fn routes<C: Configure<()>>(router: C) -> C {
+//!     router.configure(users)
+//! }
+//!
+//! struct Docs;
+//!
+//! impl ApiDoc for Docs {
+//!     fn openapi() -> OpenApi {
+//!         RouteTable::<()>::new().service(routes).openapi("Users", "1.0.0")
+//!     }
+//! }
+//!
+//! fn init_routes(router: Router<'static, ()>) -> Router<'static, ()> {
+//!     // GET /openapi.json
+//!     routes(router).configure(OpenApiRoute::<Docs>::new("/openapi.json"))
+//! }
+//! ```
+
+use crate::extract::FromRequest;
+use crate::http::ResponseError;
+use crate::route::{AddHandler, RouteFactory};
+use crate::table::RouteInfo;
+
+use core::marker::PhantomData;
+use http::StatusCode;
+use serde::{Serialize, Serializer};
+use serde_json::{json, Map, Value};
+use std::borrow::Cow;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::sync::Arc;
+use worker::{Method, Request, Response, Result, RouteContext};
+
+/// A derive macro that implements [`Schema`] for a struct with named fields,
+/// a newtype struct or an enum of unit variants.
+///
+/// Doc comments are used as descriptions,
+/// `#[serde(rename_all = "...")]`, `#[serde(rename = "...")]`, `#[serde(default)]`
+/// and `#[serde(skip)]` are followed.
+pub use worker_route_macro::Schema;
+
+#[doc(hidden)]
+pub use serde_json as __json;
+
+#[doc(hidden)]
+/// Used for code generation, not for public usage.
+pub mod __private {
+    use super::{Operation, Schema};
+    use core::marker::PhantomData;
+
+    // a type without `Schema` is left undocumented instead of failing to compile,
+    // enabling the feature mustn't break a handler whose types don't implement it
+    pub struct Probe<T: ?Sized>(PhantomData<T>);
+
+    impl<T: ?Sized> Probe<T> {
+        #[allow(clippy::new_without_default)]
+        pub const fn new() -> Self {
+            Self(PhantomData)
+        }
+    }
+
+    // preferred by method resolution, as it's implemented for `Probe<T>` rather than `&Probe<T>`
+    pub trait Documented {
+        fn path(&self, operation: &mut Operation);
+        fn query(&self, operation: &mut Operation);
+        fn body(&self, operation: &mut Operation, content_type: &str);
+        fn response(&self, operation: &mut Operation, content_type: &str);
+    }
+
+    impl<T: Schema + ?Sized> Documented for Probe<T> {
+        fn path(&self, operation: &mut Operation) {
+            operation.path::<T>();
+        }
+
+        fn query(&self, operation: &mut Operation) {
+            operation.query::<T>();
+        }
+
+        fn body(&self, operation: &mut Operation, content_type: &str) {
+            operation.body::<T>(content_type);
+        }
+
+        fn response(&self, operation: &mut Operation, content_type: &str) {
+            operation.response::<T>(content_type);
+        }
+    }
+
+    pub trait Undocumented {
+        fn path(&self, _: &mut Operation) {}
+        fn query(&self, _: &mut Operation) {}
+        fn body(&self, _: &mut Operation, _: &str) {}
+        fn response(&self, _: &mut Operation, _: &str) {}
+    }
+
+    impl<T: ?Sized> Undocumented for &Probe<T> {}
+}
+
+/// The [JSON Schema](https://json-schema.org) of a type.
+///
+/// ```
+/// use serde_json::{json, Value};
+/// use worker_route::openapi::{Components, Schema};
+///
+/// struct Email(String);
+///
+/// impl Schema for Email {
+///     fn schema(_: &mut Components) -> Value {
+///         json!({ "type": "string", "format": "email" })
+///     }
+/// }
+/// ```
+pub trait Schema {
+    /// The name of the schema under `#/components/schemas`, `None` to inline it.
+    ///
+    /// Defaults to `None`.
+    fn name() -> Option<&'static str> {
+        None
+    }
+
+    /// Returns the schema, the schema of another type is added with [`Components::reference`].
+    fn schema(components: &mut Components) -> Value;
+
+    /// Returns `false` if a field of this type may be omitted, eg: `Option<T>`.
+    ///
+    /// Defaults to `true`.
+    fn required() -> bool {
+        true
+    }
+}
+
+/// The named schemas of a document, under `#/components/schemas`.
+#[derive(Debug, Clone, Default)]
+pub struct Components(Map<String, Value>);
+
+impl Components {
+    /// Returns a reference to the schema of `T` if it is named, the schema itself otherwise.
+    pub fn reference<T: Schema + ?Sized>(&mut self) -> Value {
+        let Some(name) = T::name() else {
+            return T::schema(self);
+        };
+        if !self.0.contains_key(name) {
+            // inserted first, a recursive type refers to itself
+            self.0.insert(name.to_owned(), Value::Null);
+            let schema = T::schema(self);
+            self.0.insert(name.to_owned(), schema);
+        }
+
+        json!({ "$ref": format!("#/components/schemas/{name}") })
+    }
+
+    fn merge(&mut self, other: &Self) {
+        for (name, schema) in &other.0 {
+            self.0.insert(name.clone(), schema.clone());
+        }
+    }
+}
+
+#[doc(hidden)]
+/// Used for code generation, not for public usage.
+pub fn __describe(mut schema: Value, description: &str) -> Value {
+    if let Value::Object(schema) = &mut schema {
+        schema.insert("description".to_owned(), description.into());
+    }
+    schema
+}
+
+macro_rules! impl_schema {
+    ($($ty:ty),* => $schema:tt) => {
+        $(
+            impl Schema for $ty {
+                fn schema(_: &mut Components) -> Value {
+                    json!($schema)
+                }
+            }
+        )*
+    };
+}
+
+impl_schema!(bool => { "type": "boolean" });
+impl_schema!(String, str, char, Cow<'_, str> => { "type": "string" });
+impl_schema!(i8, i16, i32, u8, u16 => { "type": "integer", "format": "int32" });
+impl_schema!(u32, i64, u64, isize, usize => { "type": "integer", "format": "int64" });
+impl_schema!(f32 => { "type": "number", "format": "float" });
+impl_schema!(f64 => { "type": "number", "format": "double" });
+impl_schema!(() => { "type": "null" });
+impl_schema!(Value => {});
+
+impl<T: Schema> Schema for Option<T> {
+    fn schema(components: &mut Components) -> Value {
+        json!({ "anyOf": [components.reference::<T>(), { "type": "null" }] })
+    }
+
+    fn required() -> bool {
+        false
+    }
+}
+
+macro_rules! impl_schema_deref {
+    ($($ty:ty),*) => {
+        $(
+            impl<T: Schema + ?Sized> Schema for $ty {
+                fn name() -> Option<&'static str> {
+                    T::name()
+                }
+
+                fn schema(components: &mut Components) -> Value {
+                    T::schema(components)
+                }
+
+                fn required() -> bool {
+                    T::required()
+                }
+            }
+        )*
+    };
+}
+
+impl_schema_deref!(&T, Box<T>, Arc<T>);
+
+macro_rules! impl_schema_array {
+    ($($ty:ty),* => $unique:literal) => {
+        $(
+            impl<T: Schema> Schema for $ty {
+                fn schema(components: &mut Components) -> Value {
+                    let mut schema = json!({ "type": "array", "items": components.reference::<T>() });
+                    if $unique {
+                        schema["uniqueItems"] = true.into();
+                    }
+                    schema
+                }
+            }
+        )*
+    };
+}
+
+impl_schema_array!(Vec<T>, [T] => false);
+impl_schema_array!(HashSet<T>, BTreeSet<T> => true);
+
+macro_rules! impl_schema_map {
+    ($($ty:ident),*) => {
+        $(
+            #[allow(clippy::implicit_hasher)]
+            impl<K, V: Schema> Schema for $ty<K, V> {
+                fn schema(components: &mut Components) -> Value {
+                    json!({ "type": "object", "additionalProperties": components.reference::<V>() })
+                }
+            }
+        )*
+    };
+}
+
+impl_schema_map!(HashMap, BTreeMap);
+
+macro_rules! impl_schema_tuple {
+    ($len:literal, $($ty:ident),*) => {
+        impl<$($ty: Schema),*> Schema for ($($ty,)*) {
+            fn schema(components: &mut Components) -> Value {
+                json!({
+                    "type": "array",
+                    "prefixItems": [$(components.reference::<$ty>()),*],
+                    "items": false,
+                    "minItems": $len,
+                    "maxItems": $len
+                })
+            }
+        }
+    };
+}
+
+impl_schema_tuple!(1, A);
+impl_schema_tuple!(2, A, B);
+impl_schema_tuple!(3, A, B, C);
+impl_schema_tuple!(4, A, B, C, D);
+
+/// An operation of the document, one per handler.
+#[derive(Debug, Clone)]
+pub struct Operation {
+    route: RouteInfo,
+    parameters: Vec<Value>,
+    request_body: Option<Value>,
+    responses: Map<String, Value>,
+    components: Components,
+}
+
+// `/users/:id/*path` -> `/users/{id}/{path}`
+fn openapi_path(path: &str) -> String {
+    path.split('/')
+        .map(|v| {
+            v.strip_prefix(':')
+                .or_else(|| v.strip_prefix('*'))
+                .map_or_else(|| v.to_owned(), |name| format!("{{{name}}}"))
+        })
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+impl Operation {
+    #[doc(hidden)]
+    /// Used for code generation, not for public usage.
+    pub fn new(route: RouteInfo) -> Self {
+        let parameters = route
+            .path
+            .split('/')
+            .filter_map(|v| v.strip_prefix(':').or_else(|| v.strip_prefix('*')))
+            .map(|name| json!({ "name": name, "in": "path", "required": true, "schema": { "type": "string" } }))
+            .collect();
+        let mut responses = Map::new();
+        responses.insert("200".to_owned(), json!({ "description": "OK" }));
+
+        Self {
+            route,
+            parameters,
+            request_body: None,
+            responses,
+            components: Components::default(),
+        }
+    }
+
+    /// Returns the documented route.
+    pub fn route(&self) -> &RouteInfo {
+        &self.route
+    }
+
+    #[doc(hidden)]
+    /// Used for code generation, not for public usage.
+    pub fn path<T: Schema + ?Sized>(&mut self) {
+        let schema = T::schema(&mut self.components);
+        let mut params = self.parameters.iter_mut().filter(|v| v["in"] == "path");
+        if let Some(properties) = schema.get("properties") {
+            for param in params {
+                if let Some(schema) = param["name"].as_str().and_then(|v| properties.get(v)) {
+                    param["schema"] = schema.clone();
+                }
+            }
+        } else if let Some(Value::Array(items)) = schema.get("prefixItems") {
+            for (param, schema) in params.zip(items) {
+                param["schema"] = schema.clone();
+            }
+        } else if let (Some(param), None) = (params.next(), params.next()) {
+            param["schema"] = schema;
+        }
+    }
+
+    #[doc(hidden)]
+    /// Used for code generation, not for public usage.
+    pub fn query<T: Schema + ?Sized>(&mut self) {
+        let schema = T::schema(&mut self.components);
+        let Some(Value::Object(properties)) = schema.get("properties") else {
+            return;
+        };
+        let required = schema["required"].as_array();
+        for (name, schema) in properties {
+            let required = required.is_some_and(|v| v.iter().any(|v| v == name));
+            self.parameters.push(json!({
+                "name": name,
+                "in": "query",
+                "required": required,
+                "schema": schema
+            }));
+        }
+    }
+
+    #[doc(hidden)]
+    /// Used for code generation, not for public usage.
+    pub fn body<T: Schema + ?Sized>(&mut self, content_type: &str) {
+        let schema = self.components.reference::<T>();
+        self.request_body = Some(json!({
+            "required": T::required(),
+            "content": { content_type: { "schema": schema } }
+        }));
+    }
+
+    #[doc(hidden)]
+    /// Used for code generation, not for public usage.
+    pub fn response<T: Schema + ?Sized>(&mut self, content_type: &str) {
+        let schema = self.components.reference::<T>();
+        self.responses.insert(
+            "200".to_owned(),
+            json!({ "description": "OK", "content": { content_type: { "schema": schema } } }),
+        );
+    }
+
+    #[doc(hidden)]
+    /// Used for code generation, not for public usage.
+    ///
+    /// The handler answers `()` with `204 No Content`, without a body.
+    pub fn no_content(&mut self) {
+        self.responses.remove("200");
+        self.responses
+            .insert("204".to_owned(), json!({ "description": "No Content" }));
+    }
+
+    #[doc(hidden)]
+    /// Used for code generation, not for public usage.
+    pub fn extractor<T: FromRequest<D>, D>(&mut self) {
+        self.status_codes(T::status_codes());
+    }
+
+    #[doc(hidden)]
+    /// Used for code generation, not for public usage.
+    pub fn errors<E: ResponseError>(&mut self) {
+        self.status_codes(E::status_codes());
+    }
+
+    fn status_codes(&mut self, status_codes: Vec<StatusCode>) {
+        for status in status_codes {
+            self.responses.insert(
+                status.as_str().to_owned(),
+                json!({ "description": status.canonical_reason().unwrap_or("Error") }),
+            );
+        }
+    }
+
+    fn to_json(&self, method: &Method) -> Value {
+        let operation_id = if self.route.methods.len() == 1 {
+            self.route.name.to_owned()
+        } else {
+            format!("{}_{}", self.route.name, method.as_ref().to_lowercase())
+        };
+        let mut operation = json!({
+            "operationId": operation_id,
+            "parameters": self.parameters,
+            "responses": self.responses
+        });
+        if let Some(body) = &self.request_body {
+            operation["requestBody"] = body.clone();
+        }
+
+        operation
+    }
+}
+
+/// An [OpenAPI 3.1](https://spec.openapis.org/oas/v3.1.0) document, returned by [`RouteTable::openapi`](crate::RouteTable::openapi).
+///
+/// Serialized into JSON with [`to_json`](OpenApi::to_json) or `serde`.
+#[derive(Debug, Clone)]
+pub struct OpenApi {
+    title: String,
+    version: String,
+    description: Option<String>,
+    operations: Vec<Operation>,
+}
+
+impl OpenApi {
+    /// Creates a document of the given operations.
+    pub fn new(title: &str, version: &str, operations: Vec<Operation>) -> Self {
+        Self {
+            title: title.to_owned(),
+            version: version.to_owned(),
+            description: None,
+            operations,
+        }
+    }
+
+    /// Sets the description of the API.
+    pub fn description(mut self, description: &str) -> Self {
+        self.description = Some(description.to_owned());
+        self
+    }
+
+    /// Returns the operations of the document.
+    pub fn operations(&self) -> &[Operation] {
+        &self.operations
+    }
+
+    /// Returns the document as JSON.
+    pub fn to_json(&self) -> Value {
+        let mut paths = Map::new();
+        let mut components = Components::default();
+        for operation in &self.operations {
+            let path = paths
+                .entry(openapi_path(operation.route.path))
+                .or_insert_with(|| json!({}));
+            // a path item has no `connect` field, nor one for a custom method,
+            // which are listed apart in `custom_methods` and left undocumented
+            for method in operation
+                .route
+                .methods
+                .iter()
+                .filter(|v| **v != Method::Connect)
+            {
+                path[method.as_ref().to_lowercase()] = operation.to_json(method);
+            }
+            components.merge(&operation.components);
+        }
+
+        let mut info = json!({ "title": self.title, "version": self.version });
+        if let Some(description) = &self.description {
+            info["description"] = description.as_str().into();
+        }
+
+        json!({
+            "openapi": "3.1.0",
+            "info": info,
+            "paths": paths,
+            "components": { "schemas": components.0 }
+        })
+    }
+}
+
+impl Serialize for OpenApi {
+    fn serialize<S: Serializer>(&self, serializer: S) -> core::result::Result<S::Ok, S::Error> {
+        self.to_json().serialize(serializer)
+    }
+}
+
+/// The document served by [`OpenApiRoute`].
+pub trait ApiDoc {
+    /// Returns the document, built on every request.
+    fn openapi() -> OpenApi;
+}
+
+/// A route serving the JSON document of `T`, configured like a handler.
+///
+/// See the [module](self) for an example.
+#[derive(Debug, Clone, Copy)]
+pub struct OpenApiRoute<T> {
+    path: &'static str,
+    _doc: PhantomData<T>,
+}
+
+impl<T: ApiDoc> OpenApiRoute<T> {
+    /// Serves the document on `GET path`.
+    pub fn new(path: &'static str) -> Self {
+        Self {
+            path,
+            _doc: PhantomData,
+        }
+    }
+}
+
+// the signature of a handler
+#[allow(clippy::needless_pass_by_value)]
+fn serve<D, T: ApiDoc>(_: Request, _: RouteContext<D>) -> Result<Response> {
+    Response::from_json(&T::openapi().to_json())
+}
+
+impl<D: 'static, T: ApiDoc> RouteFactory<D> for OpenApiRoute<T> {
+    fn register<R: AddHandler<D>>(self, router: R) -> R {
+        router.register(self.path, Method::Get, serve::<D, T>)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{openapi_path, Components, OpenApi, Operation, Schema};
+    use crate::table::{CorsMode, RouteInfo};
+    use crate::{Error, Path};
+    use serde_json::{json, Value};
+    use worker::Method;
+
+    struct Search;
+
+    impl Schema for Search {
+        fn schema(components: &mut Components) -> Value {
+            json!({
+                "type": "object",
+                "properties": { "q": components.reference::<String>(), "page": components.reference::<Option<u32>>() },
+                "required": ["q"]
+            })
+        }
+    }
+
+    struct User;
+
+    impl Schema for User {
+        fn name() -> Option<&'static str> {
+            Some("User")
+        }
+
+        fn schema(components: &mut Components) -> Value {
+            json!({ "type": "object", "properties": { "id": components.reference::<u64>() } })
+        }
+    }
+
+    #[test]
+    fn path() {
+        assert_eq!(openapi_path("/users/:id/*path"), "/users/{id}/{path}");
+        assert_eq!(openapi_path("/"), "/");
+    }
+
+    #[test]
+    fn document() {
+        const ROUTE: RouteInfo = RouteInfo::__new(
+            "user_posts",
+            "/users/:id/posts",
+            &[Method::Get],
+            &[],
+            CorsMode::None,
+            false,
+            &[],
+            None,
+            &[],
+        );
+        let mut operation = Operation::new(ROUTE);
+        operation.path::<u64>();
+        operation.query::<Search>();
+        operation.response::<Vec<User>>("application/json");
+        operation.extractor::<Path<u64>, ()>();
+        operation.errors::<Error>();
+
+        let json = OpenApi::new("Users", "1.0.0", vec![operation]).to_json();
+        let get = &json["paths"]["/users/{id}/posts"]["get"];
+        assert_eq!(json["openapi"], "3.1.0");
+        assert_eq!(get["operationId"], "user_posts");
+        assert_eq!(get["parameters"][0]["schema"]["type"], "integer");
+        // the query's fields are sorted by name
+        assert_eq!(get["parameters"][1]["name"], "page");
+        assert_eq!(get["parameters"][1]["required"], false);
+        assert_eq!(get["parameters"][2]["name"], "q");
+        assert_eq!(get["parameters"][2]["required"], true);
+        assert_eq!(
+            get["responses"]["200"]["content"]["application/json"]["schema"]["items"]["$ref"],
+            "#/components/schemas/User"
+        );
+        assert_eq!(get["responses"]["404"]["description"], "Not Found");
+        assert_eq!(get["responses"]["400"]["description"], "Bad Request");
+        assert_eq!(
+            get["responses"]["500"]["description"],
+            "Internal Server Error"
+        );
+        assert!(get["responses"].get("401").is_none());
+        assert_eq!(
+            json["components"]["schemas"]["User"]["properties"]["id"]["format"],
+            "int64"
+        );
+    }
+
+    #[test]
+    fn no_content() {
+        const ROUTE: RouteInfo = RouteInfo::__new(
+            "purge_user",
+            "/users/:id",
+            &[Method::Delete, Method::Connect],
+            &["PURGE"],
+            CorsMode::None,
+            false,
+            &[],
+            None,
+            &[],
+        );
+        let mut operation = Operation::new(ROUTE);
+        operation.no_content();
+
+        let json = OpenApi::new("Users", "1.0.0", vec![operation]).to_json();
+        let path = &json["paths"]["/users/{id}"];
+        assert_eq!(
+            path["delete"]["responses"]["204"]["description"],
+            "No Content"
+        );
+        assert!(path["delete"]["responses"].get("200").is_none());
+        assert!(path.get("connect").is_none());
+        assert!(path.get("purge").is_none());
+    }
+}
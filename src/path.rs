@@ -0,0 +1,572 @@
+use crate::error::{Error, ErrorCause};
+use crate::extract::FromRequest;
+use crate::route::Params;
+
+use core::fmt::Display;
+use http::StatusCode;
+use percent_encoding::percent_decode_str;
+use serde::de::value::{Error as DeError, MapDeserializer, SeqDeserializer, StringDeserializer};
+use serde::de::{self, DeserializeOwned, Deserializer, IntoDeserializer, Visitor};
+use serde::forward_to_deserialize_any;
+use std::marker::PhantomData;
+use std::ops::{Deref, DerefMut};
+use worker::{Request, RouteContext};
+
+/// Extract typed information from the route's path parameters.
+///
+/// `Path<T>` only reads the parameters declared in the route's pattern (eg: `:name`),
+/// the URL query string is read with [`Query`](crate::Query).
+///
+/// `T` can be a struct, its fields are looked up by name, a tuple or a single primitive,
+/// both are filled in the order the parameters are declared in the pattern.
+/// Every segment is percent-decoded before being deserialized.
+///
+/// ```
+/// use serde::{Deserialize, Serialize};
+/// use worker::{Response, Result, RouteContext};
+/// use worker_route::{get, Path, Query};
+///
+/// #[derive(Debug, Serialize, Deserialize)]
+/// struct Person {
+///     name: String,
+///     age: usize,
+/// }
+///
+/// #[get("/person/:name/:age")]
+/// async fn person(req: Path<Person>, _: RouteContext<()>) -> Result<Response> {
+///     let Person { name, age } = req.into_inner();
+///     // rest code
+///     Response::empty()
+/// }
+///
+/// #[get("/post/:author/:id")]
+/// async fn post(req: Path<(String, u32)>, _: RouteContext<()>) -> Result<Response> {
+///     let (author, id) = req.into_inner();
+///     // rest code
+///     Response::empty()
+/// }
+///
+/// #[get("/user/:id")]
+/// async fn user(id: Path<u64>, _: RouteContext<()>) -> Result<Response> {
+///     // rest code
+///     Response::empty()
+/// }
+///
+/// #[derive(Debug, Deserialize)]
+/// struct Page {
+///     page: Option<u32>,
+/// }
+///
+/// // the params are extracted with `Path`, `Page` doesn't need an `id` field
+/// #[get("/user/:id/posts")]
+/// async fn posts(id: Path<u64>, page: Query<Page>) -> Result<Response> {
+///     // rest code
+///     Response::empty()
+/// }
+/// ```
+///
+/// # Notes
+/// A `404 Not Found` error is returned if a parameter is missing or can't be deserialized.
+/// If `T` derives [`PathParams`], the route macros check at compile time
+/// that every parameter is a field of `T`, see [`route`](macro@crate::route).
+#[derive(Debug, Clone)]
+pub struct Path<T>(T);
+
+/// The names serde deserializes the fields of a struct from,
+/// the route macros check that the parameters of a route extracting it with [`Path`] are among them.
+///
+/// Implemented with `#[derive(PathParams)]`, see [`PathParams`](macro@crate::PathParams).
+pub trait PathParams {
+    /// The renamed fields and their aliases, eg: `["userId", "postId"]`.
+    const FIELDS: &'static [&'static str];
+}
+
+// `PathParamsOf::<T>::FIELDS` is `Some` if `T` implements `PathParams`,
+// the inherent const is preferred to the one of the trait if its bound is met
+#[doc(hidden)]
+pub struct PathParamsOf<T: ?Sized>(PhantomData<T>);
+
+#[doc(hidden)]
+pub trait NoPathParams {
+    const FIELDS: Option<&'static [&'static str]> = None;
+}
+
+impl<T: ?Sized> NoPathParams for PathParamsOf<T> {}
+
+impl<T: PathParams> PathParamsOf<T> {
+    pub const FIELDS: Option<&'static [&'static str]> = Some(T::FIELDS);
+}
+
+// whether a param is a field, a type without fields has every param
+#[doc(hidden)]
+pub const fn has_param(fields: Option<&[&str]>, name: &str) -> bool {
+    let Some(fields) = fields else {
+        return true;
+    };
+    let name = name.as_bytes();
+    let mut i = 0;
+    while i < fields.len() {
+        let field = fields[i].as_bytes();
+        if field.len() == name.len() {
+            let mut j = 0;
+            while j < name.len() && field[j] == name[j] {
+                j += 1;
+            }
+            if j == name.len() {
+                return true;
+            }
+        }
+        i += 1;
+    }
+
+    false
+}
+
+impl<T> Path<T> {
+    #[allow(clippy::missing_const_for_fn)]
+    /// Acess the owned `T`
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+/// Returns the names of the parameters declared in a route's pattern, in order.
+///
+/// eg: `/post/:author/*rest` -> `["author", "rest"]`
+fn param_names(pattern: &str) -> Vec<&str> {
+    pattern
+        .split('/')
+        .filter_map(|v| v.strip_prefix(':').or_else(|| v.strip_prefix('*')))
+        .collect()
+}
+
+impl<T: DeserializeOwned> Path<T> {
+    fn new<D: Params>(names: Option<&[&str]>, ctx: &D) -> Result<Self, Error> {
+        match T::deserialize(PathDeserializer { ctx, names }) {
+            Ok(path) => Ok(Self(path)),
+            Err(err) => Err(Error::new(
+                err.to_string(),
+                StatusCode::NOT_FOUND,
+                ErrorCause::Path,
+            )),
+        }
+    }
+
+    /// Deserialize the given `T` from the parameters of the route's `pattern`.
+    ///
+    /// ```
+    /// use worker::{console_log, Request, Response, Result, RouteContext};
+    /// use worker_route::{get, Path};
+    ///
+    /// #[get("/post/:author/:id")]
+    /// async fn post(req: Request, ctx: RouteContext<()>) -> Result<Response> {
+    ///     let post = Path::<(String, u32)>::from_path("/post/:author/:id", &ctx);
+    ///     let (author, id) = post.unwrap().into_inner();
+    ///     console_log!("author: {author}, id: {id}");
+    ///     Response::empty()
+    /// }
+    /// ```
+    ///
+    /// # Errors
+    /// Errors are returned if a parameter is missing, is not valid UTF-8 once decoded
+    /// or can't be deserialized to `T`.
+    pub fn from_path<D: Params>(pattern: &str, ctx: &D) -> Result<Self, Error> {
+        Self::new(Some(&param_names(pattern)), ctx)
+    }
+}
+
+impl<D, T: DeserializeOwned> FromRequest<D> for Path<T> {
+    type Error = Error;
+
+    // without the pattern, only structs can be extracted as their fields are looked up by name
+    #[allow(clippy::future_not_send)]
+    async fn from_request(_: &Request, ctx: &RouteContext<D>) -> Result<Self, Self::Error> {
+        Self::new(None, ctx)
+    }
+
+    #[allow(clippy::future_not_send)]
+    async fn from_route(
+        _: &Request,
+        ctx: &RouteContext<D>,
+        pattern: &'static str,
+    ) -> Result<Self, Self::Error> {
+        Self::from_path(pattern, ctx)
+    }
+
+    fn status_codes() -> Vec<StatusCode> {
+        vec![StatusCode::NOT_FOUND]
+    }
+}
+
+fn decode(name: &str, value: &str) -> Result<String, DeError> {
+    percent_decode_str(value)
+        .decode_utf8()
+        .map(std::borrow::Cow::into_owned)
+        .map_err(|_| de::Error::custom(format!("path parameter `{name}` is not valid UTF-8")))
+}
+
+struct PathDeserializer<'a, D> {
+    ctx: &'a D,
+    names: Option<&'a [&'a str]>,
+}
+
+impl<D: Params> PathDeserializer<'_, D> {
+    // values in the order of the pattern, used for tuples and primitives
+    fn positional(&self) -> Result<Vec<(String, Value)>, DeError> {
+        let names = self.names.ok_or_else(|| {
+            de::Error::custom("the route's pattern is required to extract tuples or primitives")
+        })?;
+
+        names
+            .iter()
+            .map(|name| match self.ctx.param_(name) {
+                Some(value) => Ok(((*name).to_owned(), Value(decode(name, value)?))),
+                None => Err(de::Error::custom(format!(
+                    "missing path parameter `{name}`"
+                ))),
+            })
+            .collect()
+    }
+
+    fn single(&self) -> Result<Value, DeError> {
+        let mut values = self.positional()?;
+        if values.len() != 1 {
+            return Err(de::Error::custom(format!(
+                "expected 1 path parameter, found {}",
+                values.len()
+            )));
+        }
+
+        Ok(values.remove(0).1)
+    }
+
+    fn seq<'de, V: Visitor<'de>>(&self, visitor: V) -> Result<V::Value, DeError> {
+        let values = self.positional()?.into_iter().map(|v| v.1);
+        let mut seq = SeqDeserializer::new(values);
+        let value = visitor.visit_seq(&mut seq)?;
+        seq.end()?;
+        Ok(value)
+    }
+}
+
+macro_rules! forward_single {
+    ($($fn:ident)*) => {
+        $(
+            fn $fn<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+                self.single()?.$fn(visitor)
+            }
+        )*
+    };
+}
+
+impl<'de, D: Params> Deserializer<'de> for PathDeserializer<'_, D> {
+    type Error = DeError;
+
+    forward_single! {
+        deserialize_any deserialize_bool deserialize_i8 deserialize_i16 deserialize_i32
+        deserialize_i64 deserialize_u8 deserialize_u16 deserialize_u32 deserialize_u64
+        deserialize_f32 deserialize_f64 deserialize_char deserialize_str deserialize_string
+        deserialize_bytes deserialize_byte_buf deserialize_option deserialize_identifier
+    }
+
+    fn deserialize_unit<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        visitor.visit_unit()
+    }
+
+    fn deserialize_unit_struct<V: Visitor<'de>>(
+        self,
+        _: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        visitor.visit_unit()
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_seq<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        self.seq(visitor)
+    }
+
+    fn deserialize_tuple<V: Visitor<'de>>(
+        self,
+        _: usize,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        self.seq(visitor)
+    }
+
+    fn deserialize_tuple_struct<V: Visitor<'de>>(
+        self,
+        _: &'static str,
+        _: usize,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        self.seq(visitor)
+    }
+
+    fn deserialize_map<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        let mut map = MapDeserializer::new(self.positional()?.into_iter());
+        let value = visitor.visit_map(&mut map)?;
+        map.end()?;
+        Ok(value)
+    }
+
+    fn deserialize_struct<V: Visitor<'de>>(
+        self,
+        _: &'static str,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        // missing fields are left to serde, `Option` fields are allowed to be missing
+        let pairs = fields
+            .iter()
+            .filter_map(|name| {
+                let value = self.ctx.param_(name)?;
+                Some(decode(name, value).map(|value| (*name, Value(value))))
+            })
+            .collect::<Result<Vec<_>, DeError>>()?;
+        let mut map = MapDeserializer::new(pairs.into_iter());
+        let value = visitor.visit_map(&mut map)?;
+        map.end()?;
+        Ok(value)
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        name: &'static str,
+        variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        self.single()?.deserialize_enum(name, variants, visitor)
+    }
+
+    fn deserialize_ignored_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        visitor.visit_unit()
+    }
+}
+
+/// A single decoded path parameter.
+struct Value(String);
+
+impl IntoDeserializer<'_, DeError> for Value {
+    type Deserializer = Self;
+
+    fn into_deserializer(self) -> Self::Deserializer {
+        self
+    }
+}
+
+macro_rules! parse_value {
+    ($($fn:ident => $visit:ident: $ty:ty)*) => {
+        $(
+            fn $fn<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+                match self.0.parse::<$ty>() {
+                    Ok(value) => visitor.$visit(value),
+                    Err(_) => Err(de::Error::custom(format!(
+                        "can not parse `{}` to a `{}`",
+                        self.0,
+                        stringify!($ty)
+                    ))),
+                }
+            }
+        )*
+    };
+}
+
+impl<'de> Deserializer<'de> for Value {
+    type Error = DeError;
+
+    parse_value! {
+        deserialize_bool => visit_bool: bool
+        deserialize_i8 => visit_i8: i8
+        deserialize_i16 => visit_i16: i16
+        deserialize_i32 => visit_i32: i32
+        deserialize_i64 => visit_i64: i64
+        deserialize_u8 => visit_u8: u8
+        deserialize_u16 => visit_u16: u16
+        deserialize_u32 => visit_u32: u32
+        deserialize_u64 => visit_u64: u64
+        deserialize_f32 => visit_f32: f32
+        deserialize_f64 => visit_f64: f64
+    }
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        visitor.visit_string(self.0)
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        visitor.visit_some(self)
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        _: &'static str,
+        _: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        let value: StringDeserializer<DeError> = self.0.into_deserializer();
+        visitor.visit_enum(value)
+    }
+
+    forward_to_deserialize_any! {
+        char str string bytes byte_buf unit unit_struct seq tuple
+        tuple_struct map struct identifier ignored_any
+    }
+}
+
+impl<T: Display> Display for Path<T> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl<T> Deref for Path<T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<T> DerefMut for Path<T> {
+    fn deref_mut(&mut self) -> &mut T {
+        &mut self.0
+    }
+}
+
+impl<T> AsRef<T> for Path<T> {
+    fn as_ref(&self) -> &T {
+        &self.0
+    }
+}
+
+impl<T> AsMut<T> for Path<T> {
+    fn as_mut(&mut self) -> &mut T {
+        &mut self.0
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{has_param, param_names, NoPathParams as _, PathParams, PathParamsOf};
+    use crate::{http::StatusCode, route::Params, Path, ResponseError};
+
+    use serde::Deserialize;
+    use std::collections::HashMap;
+
+    struct CustomParam(HashMap<String, String>);
+
+    impl Params for CustomParam {
+        fn param_(&self, key: &str) -> Option<&String> {
+            self.0.get(key)
+        }
+    }
+
+    fn params(pairs: &[(&str, &str)]) -> CustomParam {
+        CustomParam(
+            pairs
+                .iter()
+                .map(|(k, v)| ((*k).to_owned(), (*v).to_owned()))
+                .collect(),
+        )
+    }
+
+    #[allow(unused)]
+    #[derive(Deserialize, Debug)]
+    struct Person {
+        name: String,
+        age: usize,
+    }
+
+    impl PathParams for Person {
+        const FIELDS: &'static [&'static str] = &["name", "age"];
+    }
+
+    #[test]
+    fn names() {
+        assert_eq!(
+            param_names("/post/:author/:id/*rest"),
+            ["author", "id", "rest"]
+        );
+        assert!(param_names("/post").is_empty());
+    }
+
+    #[test]
+    fn struct_ok() {
+        let ctx = params(&[("name", "Foo%20Bar"), ("age", "20")]);
+        let person = Path::<Person>::from_path("/person/:name/:age", &ctx).unwrap();
+
+        assert_eq!(person.name, "Foo Bar");
+        assert_eq!(person.age, 20);
+    }
+
+    #[test]
+    fn struct_without_pattern_ok() {
+        let ctx = params(&[("age", "20"), ("name", "Foo")]);
+
+        assert!(Path::<Person>::new(None, &ctx).is_ok());
+    }
+
+    #[test]
+    fn tuple_ok() {
+        let ctx = params(&[("id", "10"), ("author", "caf%C3%A9")]);
+        let post = Path::<(String, u32)>::from_path("/post/:author/:id", &ctx).unwrap();
+
+        assert_eq!(post.0, ("café".to_owned(), 10));
+    }
+
+    #[test]
+    fn primitive_ok() {
+        let ctx = params(&[("id", "42")]);
+        let id = Path::<u64>::from_path("/user/:id", &ctx).unwrap();
+
+        assert_eq!(id.into_inner(), 42);
+    }
+
+    #[test]
+    fn primitive_err() {
+        let ctx = params(&[("id", "abc")]);
+        let err = Path::<u64>::from_path("/user/:id", &ctx).unwrap_err();
+        let missing = Path::<u64>::from_path("/user/:user_id", &ctx).unwrap_err();
+        let without_pattern = Path::<u64>::new(None, &ctx).unwrap_err();
+
+        assert_eq!(err.status_code(), StatusCode::NOT_FOUND);
+        assert_eq!(missing.status_code(), StatusCode::NOT_FOUND);
+        assert_eq!(without_pattern.status_code(), StatusCode::NOT_FOUND);
+    }
+
+    #[test]
+    fn tuple_len_err() {
+        let ctx = params(&[("author", "Foo"), ("id", "10")]);
+
+        assert!(Path::<(String, u32, u32)>::from_path("/post/:author/:id", &ctx).is_err());
+        assert!(Path::<u32>::from_path("/post/:author/:id", &ctx).is_err());
+    }
+
+    #[test]
+    fn fields() {
+        const PERSON: Option<&[&str]> = <PathParamsOf<Person>>::FIELDS;
+        const NUMBER: Option<&[&str]> = <PathParamsOf<u64>>::FIELDS;
+        assert_eq!(PERSON, Some(["name", "age"].as_slice()));
+        assert_eq!(NUMBER, None);
+
+        assert!(has_param(PERSON, "age"));
+        assert!(!has_param(PERSON, "ag"));
+        assert!(!has_param(PERSON, "ages"));
+        assert!(has_param(NUMBER, "id"));
+    }
+}
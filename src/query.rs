@@ -1,5 +1,6 @@
 use crate::error::Error;
 use crate::error::ErrorCause;
+use crate::extract::FromRequest;
 use crate::route::Params;
 use crate::utils::struct_fields;
 use crate::utils::StructFields;
@@ -10,7 +11,7 @@ use http::StatusCode;
 use serde::de::DeserializeOwned;
 use std::borrow::Cow;
 use std::ops::Deref;
-use worker::Url;
+use worker::{Request, RouteContext, Url};
 
 /// Extract typed information with the supplied struct and deserialize it with [`worker::Url`](worker::Url).
 ///
@@ -47,13 +48,12 @@ use worker::Url;
 /// ```
 ///
 /// # Notes
-/// Request can be an ommited from the parameter too.
-/// When ommitting either of them, the sequence must always be in the correct order.
+/// `Query<T>` only reads the URL query string, the route's path parameters are extracted
+/// with [`Path`](crate::Path), eg: `/users/42?id=7` is `Path<u64>` of `42` and a query `id` of `7`.
+/// [`from_query_path`](Query::from_query_path) merges both into `T`.
 ///
-/// The correct orders are:
-/// - (`Request`, `RouteContext<D: Params>`)
-/// - (`Query<T>`, `RouteContext<D: Params>`)
-/// - (`Query<T>`, `Request`, `RouteContext<D: Params>`)
+/// `Query<T>` is extracted with [`FromRequest`](crate::FromRequest),
+/// therefore it can be declared in any order alongside `Request`, `RouteContext<D>` and other extractors.
 ///
 /// ```
 /// use serde::{Deserialize, Serialize};
@@ -72,7 +72,7 @@ use worker::Url;
 /// }
 ///
 /// #[get("/foo-with-request")]
-/// async fn with_request(req: Query<Foo>, _: Request, _: RouteContext<()>) -> Result<Response> {
+/// async fn with_request(_: Request, _: RouteContext<()>, req: Query<Foo>) -> Result<Response> {
 ///     // rest code
 ///     Response::empty()
 /// }
@@ -291,11 +291,13 @@ impl<T: DeserializeOwned> Query<T> {
         fields.iter().filter_map(|v| paths(v, ctx)).collect()
     }
 
-    fn new<D: Params>(url: &Url, ctx: &D, strict: bool) -> Result<Self, Error> {
-        let fields = struct_fields::<T>()?;
-        // get fields from path first and merge them later on
-        // "/my_path/:name/:age"
-        let mut paths = Self::collect_paths(fields, ctx);
+    // `paths` are the `field=value` pairs of the path parameters merged with the query string
+    fn new(
+        url: &Url,
+        fields: StructFields,
+        mut paths: Vec<String>,
+        strict: bool,
+    ) -> Result<Self, Error> {
         // if the given route is "/my_path/{some_params}" then paths.len() should be empty
         // or if the given route is "/my_path/:name/{some_optional_params}"
         // then we try getting them from the url instead
@@ -319,7 +321,23 @@ impl<T: DeserializeOwned> Query<T> {
         Ok(Self(serde_qs::from_str::<T>(&queries)?))
     }
 
-    /// Deserialize the given `T` from the URL query string.
+    /// Deserialize the given `T` from the URL query string, the extraction of `Query<T>`.
+    ///
+    /// `strict` rejects an unexpected or duplicate query parameter,
+    /// and the parameters which aren't in the order of the fields of `T`.
+    ///
+    /// # Errors
+    /// Errors are returned if the given `T` is not a regular struct (eg: tuple, unit)
+    /// or can't be deserialized from the query string.
+    pub fn from_query(url: &Url, strict: bool) -> Result<Self, Error> {
+        Self::new(url, struct_fields::<T>()?, Vec::new(), strict)
+    }
+
+    /// Deserialize the given `T` from the route's path parameters merged with the URL query string.
+    ///
+    /// A field of `T` named after a path parameter, eg: `name` of `/persons/:name/:age`,
+    /// is read from the path. A query parameter with the name of a path parameter,
+    /// eg: `/persons/Foo/20?name=Bar`, is rejected, the query string can't override the path.
     ///
     /// ```
     /// use serde::{Deserialize, Serialize};
@@ -347,13 +365,14 @@ impl<T: DeserializeOwned> Query<T> {
     /// Errors are returned if the given `T` is not a regular struct (eg: tuple, unit).
     ///
     pub fn from_query_path<D: Params>(url: &Url, ctx: &D, strict: bool) -> Result<Self, Error> {
-        Self::new(url, ctx, strict)
+        let fields = struct_fields::<T>()?;
+        Self::new(url, fields, Self::collect_paths(fields, ctx), strict)
     }
 
     #[doc(hidden)]
-    pub fn _internal_query<D: Params>(url: worker::Result<Url>, ctx: &D) -> Result<Self, Error> {
+    pub fn _internal_query(url: worker::Result<Url>) -> Result<Self, Error> {
         match url {
-            Ok(url) => Query::<T>::from_query_path(&url, ctx, true),
+            Ok(url) => Query::<T>::from_query(&url, true),
             Err(e) => Err(crate::error::Error::new(
                 e.to_string(),
                 StatusCode::INTERNAL_SERVER_ERROR,
@@ -363,6 +382,19 @@ impl<T: DeserializeOwned> Query<T> {
     }
 }
 
+impl<D, T: DeserializeOwned> FromRequest<D> for Query<T> {
+    type Error = Error;
+
+    #[allow(clippy::future_not_send)]
+    async fn from_request(req: &Request, _: &RouteContext<D>) -> Result<Self, Self::Error> {
+        Self::_internal_query(req.url())
+    }
+
+    fn status_codes() -> Vec<StatusCode> {
+        vec![StatusCode::BAD_REQUEST, StatusCode::INTERNAL_SERVER_ERROR]
+    }
+}
+
 impl<T: Display> Display for Query<T> {
     fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         self.0.fmt(f)
@@ -397,7 +429,7 @@ impl<T> std::ops::DerefMut for Query<T> {
 
 #[cfg(test)]
 mod test {
-    use crate::{route::Params, Query};
+    use crate::{http::StatusCode, route::Params, Query, ResponseError};
 
     use serde::Deserialize;
     use std::collections::HashMap;
@@ -558,6 +590,37 @@ mod test {
         assert_eq!(fields.last_name, last_name);
     }
 
+    #[test]
+    fn path_query_collision() {
+        // `page` is a path parameter, the query string can't override it
+        let url = Url::parse("http://127.0.0.1:8787/profile/10?page=1&sort_by=email").unwrap();
+        let mut params = CustomParam::new();
+        params.insert("page".into(), "10".into());
+
+        let strict = Query::<Profile>::from_query_path(&url, &params, true).unwrap_err();
+        assert_eq!(
+            strict.description(),
+            "duplicate query parameters found: `page`"
+        );
+        let lenient = Query::<Profile>::from_query_path(&url, &params, false).unwrap_err();
+        assert_eq!(lenient.status_code(), StatusCode::BAD_REQUEST);
+    }
+
+    #[test]
+    fn query_only() {
+        // `/profile/:page`, the path parameter isn't read
+        let url = Url::parse("http://127.0.0.1:8787/profile/10?page=1&sort_by=email").unwrap();
+        let query = Query::<Profile>::from_query(&url, true)
+            .unwrap()
+            .into_inner();
+        assert_eq!(query.page, 1);
+        assert_eq!(query.sort_by.as_deref(), Some("email"));
+
+        let url = Url::parse("http://127.0.0.1:8787/profile/10?sort_by=email").unwrap();
+        let err = Query::<Profile>::from_query(&url, false).unwrap_err();
+        assert_eq!(err.status_code(), StatusCode::BAD_REQUEST);
+    }
+
     #[derive(Deserialize, Debug)]
     struct Tuple(ManyFields);
 
@@ -0,0 +1,922 @@
+//! Limit the rate of requests with a [`Middleware`], see [`RateLimit`].
+use crate::binding::{missing, Binding};
+use crate::error::{Error, ErrorCause};
+use crate::http::{HttpRequest, HttpResponse, ResponseError};
+use crate::middleware::Middleware;
+
+use core::fmt::Debug;
+use core::future::Future;
+use core::marker::PhantomData;
+use core::str::FromStr;
+use http::StatusCode;
+use serde::{Deserialize, Serialize};
+use std::cell::RefCell;
+use std::collections::HashMap;
+use worker::{Date, Env, Request, Response, RouteContext};
+
+const RATELIMIT_LIMIT: &str = "ratelimit-limit";
+const RATELIMIT_REMAINING: &str = "ratelimit-remaining";
+const RATELIMIT_RESET: &str = "ratelimit-reset";
+const RETRY_AFTER: &str = "retry-after";
+
+// the header set by Cloudflare to the client's IP
+const CF_CONNECTING_IP: &str = "cf-connecting-ip";
+
+// KV doesn't accept an expiration shorter than a minute
+const KV_MIN_TTL: u64 = 60;
+
+fn invalid_quota(quota: &str) -> Error {
+    Error::new(
+        format!(
+            r#"invalid quota `{quota}`, expected `<limit>/<period>`, eg: "100/min" or "10/30s""#
+        ),
+        StatusCode::INTERNAL_SERVER_ERROR,
+        ErrorCause::RateLimit,
+    )
+}
+
+/// The number of requests allowed in a period.
+///
+/// Parsed from `<limit>/<period>`, the period is a unit optionally preceded by a count,
+/// eg: `"100/min"`, `"10/30s"` or `"5000/day"`.
+/// The units are `s`, `sec`, `second`, `m`, `min`, `minute`, `h`, `hour` and `d`, `day`.
+///
+/// `rate_limit = ".."` is parsed at compile time, an invalid quota doesn't compile:
+/// ```compile_fail
+/// use worker::Result;
+/// use worker_route::get;
+///
+/// #[get("/search", rate_limit = "100/week")]
+/// async fn search() -> Result<String> {
+///     Ok("search".to_owned())
+/// }
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Quota {
+    limit: u32,
+    period: u64,
+}
+
+impl Quota {
+    /// Allows `limit` requests every `period` seconds, the period is at least a second
+    /// and at most as long as `limit` requests can be counted in milliseconds.
+    pub const fn new(limit: u32, period: u64) -> Self {
+        let max = Self::max_period(limit);
+        let period = if period == 0 {
+            1
+        } else if period > max {
+            max
+        } else {
+            period
+        };
+        Self { limit, period }
+    }
+
+    /// Allows `limit` requests every second.
+    pub const fn per_second(limit: u32) -> Self {
+        Self::new(limit, 1)
+    }
+
+    /// Allows `limit` requests every minute.
+    pub const fn per_minute(limit: u32) -> Self {
+        Self::new(limit, 60)
+    }
+
+    /// Allows `limit` requests every hour.
+    pub const fn per_hour(limit: u32) -> Self {
+        Self::new(limit, 60 * 60)
+    }
+
+    /// Allows `limit` requests every day.
+    pub const fn per_day(limit: u32) -> Self {
+        Self::new(limit, 24 * 60 * 60)
+    }
+
+    /// The number of requests allowed in a period.
+    pub const fn limit(&self) -> u32 {
+        self.limit
+    }
+
+    /// The period, in seconds.
+    pub const fn period(&self) -> u64 {
+        self.period
+    }
+
+    fn period_ms(self) -> u64 {
+        self.period * 1000
+    }
+
+    // the longest period, in seconds, whose `limit` or 2 periods fit in a `u64` of milliseconds
+    const fn max_period(limit: u32) -> u64 {
+        let limit = if limit < 2 { 2 } else { limit as u64 };
+        u64::MAX / 1000 / limit
+    }
+}
+
+impl FromStr for Quota {
+    type Err = Error;
+
+    fn from_str(quota: &str) -> Result<Self, Self::Err> {
+        Self::parse(quota).ok_or_else(|| invalid_quota(quota))
+    }
+}
+
+// the units of a period and their length in seconds
+const UNITS: [(&str, u64); 10] = [
+    ("s", 1),
+    ("sec", 1),
+    ("second", 1),
+    ("m", 60),
+    ("min", 60),
+    ("minute", 60),
+    ("h", 60 * 60),
+    ("hour", 60 * 60),
+    ("d", 24 * 60 * 60),
+    ("day", 24 * 60 * 60),
+];
+
+impl Quota {
+    // a `const fn`, so `rate_limit = ".."` is parsed by the same code at compile time
+    const fn parse(quota: &str) -> Option<Self> {
+        let bytes = quota.as_bytes();
+        let mut slash = 0;
+        while slash < bytes.len() && bytes[slash] != b'/' {
+            slash += 1;
+        }
+        if slash == bytes.len() {
+            return None;
+        }
+        let (start, end) = trim(bytes, 0, slash);
+        let limit = match number(bytes, start, end) {
+            #[allow(clippy::cast_possible_truncation)]
+            Some(limit) if limit <= u32::MAX as u64 => limit as u32,
+            _ => return None,
+        };
+        let (start, end) = trim(bytes, slash + 1, bytes.len());
+        let mut split = start;
+        while split < end && bytes[split].is_ascii_digit() {
+            split += 1;
+        }
+        let count = if split == start {
+            1
+        } else {
+            match number(bytes, start, split) {
+                Some(0) | None => return None,
+                Some(count) => count,
+            }
+        };
+        let mut i = 0;
+        while i < UNITS.len() {
+            if eq(bytes, split, end, UNITS[i].0.as_bytes()) {
+                return match count.checked_mul(UNITS[i].1) {
+                    Some(period) if period <= Self::max_period(limit) => {
+                        Some(Self::new(limit, period))
+                    }
+                    _ => None,
+                };
+            }
+            i += 1;
+        }
+
+        None
+    }
+
+    #[doc(hidden)]
+    /// The quota of `rate_limit = ".."`, evaluated in a `const` so an invalid quota doesn't compile.
+    pub const fn __from_attr(quota: &str) -> Self {
+        match Self::parse(quota) {
+            Some(quota) => quota,
+            None => {
+                panic!(r#"expected rate_limit = "<limit>/<period>", eg: "100/min" or "10/30s""#)
+            }
+        }
+    }
+}
+
+// the range of `bytes[start..end]` without its leading and trailing whitespaces
+const fn trim(bytes: &[u8], mut start: usize, mut end: usize) -> (usize, usize) {
+    while start < end && bytes[start].is_ascii_whitespace() {
+        start += 1;
+    }
+    while start < end && bytes[end - 1].is_ascii_whitespace() {
+        end -= 1;
+    }
+    (start, end)
+}
+
+// the number written in `bytes[start..end]`, `None` if it isn't only digits or overflows
+const fn number(bytes: &[u8], mut start: usize, end: usize) -> Option<u64> {
+    if start == end {
+        return None;
+    }
+    let mut number: u64 = 0;
+    while start < end {
+        if !bytes[start].is_ascii_digit() {
+            return None;
+        }
+        number = match number.checked_mul(10) {
+            Some(v) => match v.checked_add((bytes[start] - b'0') as u64) {
+                Some(v) => v,
+                None => return None,
+            },
+            None => return None,
+        };
+        start += 1;
+    }
+    Some(number)
+}
+
+// `bytes[start..end] == other`
+const fn eq(bytes: &[u8], start: usize, end: usize, other: &[u8]) -> bool {
+    if end - start != other.len() {
+        return false;
+    }
+    let mut i = 0;
+    while i < other.len() {
+        if bytes[start + i] != other[i] {
+            return false;
+        }
+        i += 1;
+    }
+    true
+}
+
+/// The algorithm counting the requests of a key.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Algorithm {
+    /// A bucket of `limit` tokens refilled over the period, a request takes a token.
+    ///
+    /// Allows bursts of up to `limit` requests.
+    #[default]
+    TokenBucket,
+    /// Counts the requests of the current window, and weights the previous window's
+    /// by how much of it overlaps the last period.
+    SlidingWindow,
+}
+
+/// What a request is limited by, requests without a key share a single limit.
+#[derive(Debug, Clone, Default)]
+pub enum Key {
+    /// The client's IP, read from `CF-Connecting-IP`.
+    #[default]
+    Ip,
+    /// The value of a header, eg: `x-api-key`.
+    Header(String),
+    /// A key extracted from the request.
+    Custom(fn(&Request) -> Option<String>),
+}
+
+impl Key {
+    fn get(&self, req: &Request) -> Option<String> {
+        let header = match self {
+            Self::Ip => CF_CONNECTING_IP,
+            Self::Header(name) => name,
+            Self::Custom(extract) => return extract(req),
+        };
+        req.headers().get(header).ok().flatten()
+    }
+}
+
+/// The state of a key, saved by a [`Store`] between requests.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct State(Counter);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "algorithm", rename_all = "snake_case")]
+enum Counter {
+    // a token is `period_ms` units, `limit` units are refilled every millisecond
+    TokenBucket {
+        level: u64,
+        updated: u64,
+    },
+    // the start of the current window and the requests of the current and previous windows
+    SlidingWindow {
+        start: u64,
+        current: u64,
+        previous: u64,
+    },
+}
+
+impl State {
+    /// The time, in milliseconds since the epoch, after which the state is the same as no state.
+    pub fn expires(&self, quota: Quota) -> u64 {
+        match self.0 {
+            Counter::TokenBucket { level, updated } => {
+                let capacity = u64::from(quota.limit) * quota.period_ms();
+                let refill =
+                    (capacity - level.min(capacity)).div_ceil(u64::from(quota.limit.max(1)));
+                updated.saturating_add(refill)
+            }
+            Counter::SlidingWindow { start, .. } => start.saturating_add(2 * quota.period_ms()),
+        }
+    }
+}
+
+/// The outcome of a request.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Decision {
+    allowed: bool,
+    limit: u32,
+    remaining: u32,
+    reset: u64,
+    retry_after: u64,
+}
+
+// seconds, rounded up
+fn secs(ms: u64) -> u64 {
+    ms.div_ceil(1000)
+}
+
+impl Decision {
+    /// Returns `true` if the request is under the limit.
+    pub const fn is_allowed(&self) -> bool {
+        self.allowed
+    }
+
+    /// The number of requests allowed in a period.
+    pub const fn limit(&self) -> u32 {
+        self.limit
+    }
+
+    /// The number of requests left.
+    pub const fn remaining(&self) -> u32 {
+        self.remaining
+    }
+
+    /// The seconds until the whole quota is available again.
+    pub const fn reset(&self) -> u64 {
+        self.reset
+    }
+
+    /// The seconds until a request is allowed again, `0` if the request is allowed.
+    pub const fn retry_after(&self) -> u64 {
+        self.retry_after
+    }
+
+    fn headers(&self) -> Vec<(&'static str, String)> {
+        let mut headers = vec![
+            (RATELIMIT_LIMIT, self.limit.to_string()),
+            (RATELIMIT_REMAINING, self.remaining.to_string()),
+            (RATELIMIT_RESET, self.reset.to_string()),
+        ];
+        if !self.allowed {
+            headers.push((RETRY_AFTER, self.retry_after.to_string()));
+        }
+
+        headers
+    }
+}
+
+/// A request counted against the state of its key.
+///
+/// Sent in the query of the request to the Durable Object of a [`DurableObjectStore`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Hit {
+    key: String,
+    limit: u32,
+    period: u64,
+    algorithm: Algorithm,
+    now: u64,
+}
+
+impl Hit {
+    /// A request of `key` at `now`, in milliseconds since the epoch.
+    pub fn new(key: impl Into<String>, quota: Quota, algorithm: Algorithm, now: u64) -> Self {
+        Self {
+            key: key.into(),
+            limit: quota.limit,
+            period: quota.period,
+            algorithm,
+            now,
+        }
+    }
+
+    /// Deserializes the hit from the request's query, see [`DurableObjectStore`].
+    ///
+    /// # Errors
+    ///
+    /// Fails if the query isn't a hit.
+    pub fn from_request(req: &Request) -> Result<Self, Error> {
+        let url = req.url()?;
+        Ok(serde_qs::from_str(url.query().unwrap_or_default())?)
+    }
+
+    /// The key of the request, prefixed by the [`RateLimit`]'s prefix.
+    pub fn key(&self) -> &str {
+        &self.key
+    }
+
+    /// The quota of the key.
+    pub const fn quota(&self) -> Quota {
+        Quota::new(self.limit, self.period)
+    }
+
+    /// Counts the request against the state of its key, `None` if the key has no state yet,
+    /// and returns the new state with the decision.
+    ///
+    /// A state of another algorithm is discarded.
+    pub fn apply(&self, state: Option<State>) -> (State, Decision) {
+        let state = state.map(|v| v.0);
+        match self.algorithm {
+            Algorithm::TokenBucket => self.token_bucket(state),
+            Algorithm::SlidingWindow => self.sliding_window(state),
+        }
+    }
+
+    fn token_bucket(&self, state: Option<Counter>) -> (State, Decision) {
+        let quota = self.quota();
+        let limit = u64::from(quota.limit);
+        let token = quota.period_ms();
+        let capacity = limit * token;
+        let level = match state {
+            Some(Counter::TokenBucket { level, updated }) => level
+                .saturating_add(self.now.saturating_sub(updated).saturating_mul(limit))
+                .min(capacity),
+            _ => capacity,
+        };
+
+        let allowed = level >= token;
+        let level = if allowed { level - token } else { level };
+        let refill = |units: u64| secs(units.div_ceil(limit.max(1)));
+        let decision = Decision {
+            allowed,
+            limit: quota.limit,
+            remaining: u32::try_from(level / token).unwrap_or(quota.limit),
+            reset: refill(capacity - level),
+            retry_after: if allowed { 0 } else { refill(token - level) },
+        };
+        let state = Counter::TokenBucket {
+            level,
+            updated: self.now,
+        };
+
+        (State(state), decision)
+    }
+
+    fn sliding_window(&self, state: Option<Counter>) -> (State, Decision) {
+        let quota = self.quota();
+        let limit = u64::from(quota.limit);
+        let window = quota.period_ms();
+        let start = self.now - self.now % window;
+        let (mut current, previous) = match state {
+            Some(Counter::SlidingWindow {
+                start: last,
+                current,
+                previous,
+            }) => match start.saturating_sub(last) {
+                0 => (current, previous),
+                elapsed if elapsed == window => (0, current),
+                _ => (0, 0),
+            },
+            _ => (0, 0),
+        };
+
+        let elapsed = self.now - start;
+        // the requests of the previous window still in the last period
+        let weighted = (previous * (window - elapsed)).div_ceil(window);
+        let allowed = weighted + current < limit;
+        if allowed {
+            current += 1;
+        }
+
+        // the time into a window after which `count` requests of the previous one weight `free` at most
+        let weight = |count: u64, free: u64| match count {
+            0 => 0,
+            count => window.saturating_sub(free * window / count),
+        };
+        let retry_after = match (allowed, current < limit) {
+            (true, _) => 0,
+            (false, true) => weight(previous, limit - current - 1).max(elapsed + 1) - elapsed,
+            (false, false) => window - elapsed + weight(current, limit.saturating_sub(1)).max(1),
+        };
+        let reset = match (current, weighted) {
+            (0, 0) => 0,
+            (0, _) => window - elapsed,
+            _ => 2 * window - elapsed,
+        };
+        let decision = Decision {
+            allowed,
+            limit: quota.limit,
+            remaining: u32::try_from(limit.saturating_sub(weighted + current)).unwrap_or(0),
+            reset: secs(reset),
+            retry_after: secs(retry_after),
+        };
+        let state = Counter::SlidingWindow {
+            start,
+            current,
+            previous,
+        };
+
+        (State(state), decision)
+    }
+}
+
+/// Where the state of every key is saved.
+///
+/// Implemented by [`MemoryStore`], [`KvStore`] and [`DurableObjectStore`].
+pub trait Store {
+    /// Applies the hit to the state of its key, see [`Hit::apply`], and returns the decision.
+    fn hit(&self, env: &Env, hit: &Hit) -> impl Future<Output = Result<Decision, Error>>;
+}
+
+thread_local! {
+    // the states of `MemoryStore` and when they expire
+    static MEMORY: RefCell<HashMap<String, (State, u64)>> = RefCell::new(HashMap::new());
+}
+
+// the expired states are removed once there are this many
+const MEMORY_CAPACITY: usize = 1024;
+
+/// Saves the states in the memory of the isolate.
+///
+/// Every isolate has its own states, and they are lost when it's evicted.
+/// Useful for tests or as a best effort limit.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct MemoryStore;
+
+impl Store for MemoryStore {
+    #[allow(clippy::future_not_send)]
+    async fn hit(&self, _: &Env, hit: &Hit) -> Result<Decision, Error> {
+        Ok(MEMORY.with(|v| {
+            let mut memory = v.borrow_mut();
+            if memory.len() >= MEMORY_CAPACITY {
+                memory.retain(|_, v| v.1 > hit.now);
+            }
+            let state = memory.get(hit.key()).map(|v| v.0);
+            let (state, decision) = hit.apply(state);
+            memory.insert(hit.key.clone(), (state, state.expires(hit.quota())));
+            decision
+        }))
+    }
+}
+
+/// Saves the states in a KV namespace, see [`Binding`].
+///
+/// KV is eventually consistent and doesn't lock the key between reading and writing it,
+/// concurrent requests, especially from different locations, may exceed the limit.
+pub struct KvStore<B: Binding>(PhantomData<B>);
+
+impl<B: Binding> KvStore<B> {
+    /// Creates the store of the `B` binding.
+    pub const fn new() -> Self {
+        Self(PhantomData)
+    }
+}
+
+impl<B: Binding> Default for KvStore<B> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<B: Binding> Debug for KvStore<B> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_tuple("KvStore").field(&B::NAME).finish()
+    }
+}
+
+impl<B: Binding> Store for KvStore<B> {
+    #[allow(clippy::future_not_send)]
+    async fn hit(&self, env: &Env, hit: &Hit) -> Result<Decision, Error> {
+        let kv = env.kv(B::NAME).map_err(|err| missing::<B>(&err))?;
+        let state = kv
+            .get(hit.key())
+            .json::<State>()
+            .await
+            .map_err(worker::Error::from)?;
+        let (state, decision) = hit.apply(state);
+        let ttl = secs(state.expires(hit.quota()).saturating_sub(hit.now)).max(KV_MIN_TTL);
+        kv.put(hit.key(), state)
+            .map_err(worker::Error::from)?
+            .expiration_ttl(ttl)
+            .execute()
+            .await
+            .map_err(worker::Error::from)?;
+
+        Ok(decision)
+    }
+}
+
+/// Saves the states in Durable Objects, one for each key, see [`Binding`].
+///
+/// The requests to an object are handled one at a time, the limit is exact.
+/// The hit is sent in the query of a request to the object,
+/// which answers with the JSON of the [`Decision`].
+///
+/// # Example
+///
+/// The `fetch` of the Durable Object, its state is kept in its storage.
+///
+/// ```
+/// use worker::{Request, Response, Result};
+/// use worker_route::rate_limit::{Hit, State};
+///
+/// struct RateLimiter {
+///     state: Option<State>,
+/// }
+///
+/// impl RateLimiter {
+///     async fn fetch(&mut self, req: Request) -> Result<Response> {
+///         let hit = Hit::from_request(&req).map_err(|err| worker::Error::RustError(err.to_string()))?;
+///         let (state, decision) = hit.apply(self.state.take());
+///         self.state = Some(state);
+///         Response::from_json(&decision)
+///     }
+/// }
+/// ```
+pub struct DurableObjectStore<B: Binding>(PhantomData<B>);
+
+impl<B: Binding> DurableObjectStore<B> {
+    /// Creates the store of the `B` binding.
+    pub const fn new() -> Self {
+        Self(PhantomData)
+    }
+}
+
+impl<B: Binding> Default for DurableObjectStore<B> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<B: Binding> Debug for DurableObjectStore<B> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_tuple("DurableObjectStore").field(&B::NAME).finish()
+    }
+}
+
+impl<B: Binding> Store for DurableObjectStore<B> {
+    #[allow(clippy::future_not_send)]
+    async fn hit(&self, env: &Env, hit: &Hit) -> Result<Decision, Error> {
+        let namespace = env
+            .durable_object(B::NAME)
+            .map_err(|err| missing::<B>(&err))?;
+        let stub = namespace.id_from_name(hit.key())?.get_stub()?;
+        let query = serde_qs::to_string(hit)?;
+        let mut res = stub
+            .fetch_with_str(&format!("https://rate-limit/?{query}"))
+            .await?;
+
+        Ok(res.json::<Decision>().await?)
+    }
+}
+
+/// A rate limit, applied as a [`Middleware`] to a route with `wrap = [..]` or `rate_limit = "100/min"`,
+/// or to every route of an app with [`App::wrap`](crate::App::wrap).
+///
+/// - The requests are limited by a [`Key`], the client's IP by default.
+/// - The requests are counted with an [`Algorithm`], a token bucket by default.
+/// - The states are saved in a [`Store`], the isolate's memory by default.
+///
+/// A request over the limit is answered with `429 Too Many Requests`, `Retry-After` and the
+/// `RateLimit-Limit`, `RateLimit-Remaining` and `RateLimit-Reset` headers.
+/// An error of the store is returned as is, the request isn't let through.
+///
+/// # Examples
+/// ```
+/// use worker::{Result, Router};
+/// use worker_route::rate_limit::{Algorithm, Key, KvStore, Quota};
+/// use worker_route::{get, Binding, Configure, RateLimit};
+///
+/// struct RateLimits;
+///
+/// impl Binding for RateLimits {
+///     const NAME: &'static str = "RATE_LIMITS";
+/// }
+///
+/// fn by_api_key() -> RateLimit<KvStore<RateLimits>> {
+///     RateLimit::new(Quota::per_hour(5000))
+///         .with_algorithm(Algorithm::SlidingWindow)
+///         .with_key(Key::Header("x-api-key".to_owned()))
+///         .with_store(KvStore::new())
+/// }
+///
+/// // 100 requests per minute for each IP, in the isolate's memory
+/// #[get("/search", rate_limit = "100/min")]
+/// async fn search() -> Result<String> {
+///     Ok("search".to_owned())
+/// }
+///
+/// #[get("/users", wrap = [by_api_key().with_prefix("users")])]
+/// async fn users() -> Result<String> {
+///     Ok("users".to_owned())
+/// }
+///
+/// fn init_routes(router: Router<'static, ()>) -> Router<'static, ()> {
+///     router.configure(search).configure(users)
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct RateLimit<S = MemoryStore> {
+    quota: Quota,
+    algorithm: Algorithm,
+    key: Key,
+    prefix: String,
+    store: S,
+}
+
+impl RateLimit {
+    /// Creates a token bucket limit of each IP, saved in the isolate's memory.
+    pub fn new(quota: Quota) -> Self {
+        Self {
+            quota,
+            algorithm: Algorithm::default(),
+            key: Key::default(),
+            prefix: "rate_limit".to_owned(),
+            store: MemoryStore,
+        }
+    }
+}
+
+impl<S: Store> RateLimit<S> {
+    /// Sets the algorithm counting the requests.
+    pub fn with_algorithm(mut self, algorithm: Algorithm) -> Self {
+        self.algorithm = algorithm;
+        self
+    }
+
+    /// Sets what the requests are limited by.
+    pub fn with_key(mut self, key: Key) -> Self {
+        self.key = key;
+        self
+    }
+
+    /// Prefixes the keys in the store, limits of different prefixes are counted apart.
+    ///
+    /// `rate_limit = ".."` prefixes them with the handler's path, eg: `my_crate::users::users`.
+    pub fn with_prefix(mut self, prefix: impl Into<String>) -> Self {
+        self.prefix = prefix.into();
+        self
+    }
+
+    /// Sets where the states are saved.
+    pub fn with_store<T: Store>(self, store: T) -> RateLimit<T> {
+        RateLimit {
+            quota: self.quota,
+            algorithm: self.algorithm,
+            key: self.key,
+            prefix: self.prefix,
+            store,
+        }
+    }
+
+    /// The quota of every key.
+    pub const fn quota(&self) -> Quota {
+        self.quota
+    }
+
+    fn hit(&self, req: &Request, now: u64) -> Hit {
+        let key = self.key.get(req).unwrap_or_default();
+        Hit::new(
+            format!("{}:{key}", self.prefix),
+            self.quota,
+            self.algorithm,
+            now,
+        )
+    }
+}
+
+fn too_many_requests(req: &Request, decision: &Decision) -> Result<Response, Error> {
+    let err = Error::new(
+        format!(
+            "too many requests, retry after {} seconds",
+            decision.retry_after
+        ),
+        StatusCode::TOO_MANY_REQUESTS,
+        ErrorCause::RateLimit,
+    );
+    let mut res = err.error_response(HttpRequest::from(req)).into_res();
+    for (name, value) in decision.headers() {
+        res.headers_mut().set(name, &value)?;
+    }
+
+    Ok(res)
+}
+
+impl<D, S: Store> Middleware<D> for RateLimit<S> {
+    /// Counts the request, `429 Too Many Requests` if it's over the limit.
+    #[allow(clippy::future_not_send)]
+    async fn before(
+        &self,
+        req: &mut Request,
+        ctx: &RouteContext<D>,
+    ) -> Result<Option<HttpResponse>, Error> {
+        let hit = self.hit(req, Date::now().as_millis());
+        let decision = self.store.hit(&ctx.env, &hit).await?;
+        if decision.allowed {
+            return Ok(None);
+        }
+
+        too_many_requests(req, &decision).map(|res| Some(HttpResponse::from_response(res)))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Algorithm, Decision, Hit, Quota, State};
+
+    fn hits(algorithm: Algorithm, quota: Quota, times: &[u64]) -> Vec<Decision> {
+        let mut state: Option<State> = None;
+        times
+            .iter()
+            .map(|now| {
+                let (next, decision) = Hit::new("k", quota, algorithm, *now).apply(state);
+                state = Some(next);
+                decision
+            })
+            .collect()
+    }
+
+    #[test]
+    fn quota() {
+        assert_eq!("100/min".parse::<Quota>().unwrap(), Quota::per_minute(100));
+        assert_eq!("10/30s".parse::<Quota>().unwrap(), Quota::new(10, 30));
+        assert_eq!("5000 / day".parse::<Quota>().unwrap(), Quota::per_day(5000));
+        assert_eq!("1/2h".parse::<Quota>().unwrap().period(), 7200);
+
+        assert_eq!(Quota::__from_attr("60/ 2m").period(), 120);
+        assert_eq!(Quota::new(1, u64::MAX).period(), u64::MAX / 2000);
+        assert_eq!(Quota::new(u32::MAX, 0).period(), 1);
+
+        for invalid in [
+            "100",
+            "100/",
+            "x/min",
+            "100/0s",
+            "100/week",
+            "-1/min",
+            "4294967296/min",
+            "1/99999999999999999999s",
+            "1/999999999999999999d",
+            "1/20000000000000000s",
+            "4000000000/1000000d",
+        ] {
+            assert!(invalid.parse::<Quota>().is_err(), "{invalid}");
+        }
+    }
+
+    #[test]
+    fn token_bucket() {
+        let quota = Quota::per_minute(2);
+        let decisions = hits(Algorithm::TokenBucket, quota, &[0, 1, 2, 30_000, 30_001]);
+        let allowed = decisions
+            .iter()
+            .map(Decision::is_allowed)
+            .collect::<Vec<_>>();
+
+        // a token every 30 seconds
+        assert_eq!(allowed, [true, true, false, true, false]);
+        assert_eq!(decisions[0].remaining(), 1);
+        assert_eq!(decisions[1].remaining(), 0);
+        assert_eq!(decisions[1].reset(), 60);
+        assert_eq!(decisions[2].retry_after(), 30);
+        assert_eq!(decisions[4].retry_after(), 30);
+    }
+
+    #[test]
+    fn sliding_window() {
+        let quota = Quota::per_minute(2);
+        let decisions = hits(
+            Algorithm::SlidingWindow,
+            quota,
+            &[0, 1, 2, 60_000, 90_000, 120_000],
+        );
+        let allowed = decisions
+            .iter()
+            .map(Decision::is_allowed)
+            .collect::<Vec<_>>();
+
+        // the 2 requests of the first window weight 2, then 1 halfway through the second
+        assert_eq!(allowed, [true, true, false, false, true, true]);
+        assert_eq!(decisions[1].remaining(), 0);
+        assert_eq!(decisions[2].retry_after(), 90);
+        assert_eq!(decisions[3].retry_after(), 30);
+        assert_eq!(decisions[4].remaining(), 0);
+    }
+
+    #[test]
+    fn headers() {
+        let decisions = hits(Algorithm::TokenBucket, Quota::per_second(1), &[0, 0]);
+        let headers = decisions[1].headers();
+
+        assert_eq!(decisions[0].headers().len(), 3);
+        assert!(headers.iter().any(|v| v.0 == "retry-after" && v.1 == "1"));
+        assert!(headers
+            .iter()
+            .any(|v| v.0 == "ratelimit-remaining" && v.1 == "0"));
+    }
+
+    #[test]
+    fn query() {
+        let hit = Hit::new(
+            "users:1.1.1.1",
+            Quota::per_hour(10),
+            Algorithm::SlidingWindow,
+            5,
+        );
+        let query = serde_qs::to_string(&hit).unwrap();
+
+        assert_eq!(serde_qs::from_str::<Hit>(&query).unwrap(), hit);
+    }
+}
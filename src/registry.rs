@@ -0,0 +1,280 @@
+use crate::middleware::DynMiddleware;
+
+use core::any::Any;
+use futures::future::LocalBoxFuture;
+use matchit::Node;
+use std::cell::RefCell;
+use std::rc::Rc;
+use worker::{Method, Request, Response, Result, RouteContext};
+
+// the methods a router registers by themselves, in the order they're listed in `Allow`
+const METHODS: [Method; 7] = [
+    Method::Head,
+    Method::Get,
+    Method::Post,
+    Method::Put,
+    Method::Patch,
+    Method::Delete,
+    Method::Options,
+];
+
+/// A handler run by the catch-all of a router, see [`route::dispatch`](crate::route::dispatch).
+pub type Handler<D> =
+    Rc<dyn Fn(Request, RouteContext<D>) -> LocalBoxFuture<'static, Result<Response>>>;
+
+/// The handlers configured into the routers whose data is `D`.
+///
+/// A worker configures its router again for every request, recording the same handlers again changes nothing.
+pub struct Routes<D> {
+    // the first registration of a router, which adds the catch-all
+    first: Option<String>,
+    // every pattern and its methods, uppercase, in the order of registration
+    methods: Vec<(String, Vec<String>)>,
+    // the handlers of `CONNECT`, `TRACE` and custom methods, with their pattern and methods
+    handlers: Vec<(String, Vec<String>, Handler<D>)>,
+    fallback: Option<Handler<D>>,
+    // the global middlewares of the app whose router runs
+    middleware: Rc<[Box<dyn DynMiddleware<D>>]>,
+}
+
+impl<D> Default for Routes<D> {
+    fn default() -> Self {
+        Self {
+            first: None,
+            methods: Vec::new(),
+            handlers: Vec::new(),
+            fallback: None,
+            middleware: Rc::new([]),
+        }
+    }
+}
+
+impl<D> Routes<D> {
+    /// Records the methods of a pattern, with their handler if the router can't register it by itself.
+    ///
+    /// Returns `true` for the first registration of the router, which adds the catch-all.
+    pub fn record(&mut self, pattern: &str, methods: &[&str], handler: Option<Handler<D>>) -> bool {
+        let methods: Vec<String> = methods.iter().map(|v| v.to_ascii_uppercase()).collect();
+        let first = self.first(format!("{} {pattern}", methods.join(",")));
+
+        match self.methods.iter_mut().find(|v| v.0 == pattern) {
+            Some((_, recorded)) => {
+                for method in &methods {
+                    if !recorded.contains(method) {
+                        recorded.push(method.clone());
+                    }
+                }
+            }
+            None => self.methods.push((pattern.to_owned(), methods.clone())),
+        }
+        if let Some(handler) = handler {
+            match self
+                .handlers
+                .iter_mut()
+                .find(|v| v.0 == pattern && v.1 == methods)
+            {
+                Some(v) => v.2 = handler,
+                None => self.handlers.push((pattern.to_owned(), methods, handler)),
+            }
+        }
+
+        first
+    }
+
+    /// Records the fallback, returns `true` if it's the first registration of the router.
+    pub fn record_fallback(&mut self, handler: Handler<D>) -> bool {
+        self.fallback = Some(handler);
+        self.first("fallback".to_owned())
+    }
+
+    /// The handler of the paths without handlers.
+    pub fn fallback(&self) -> Option<Handler<D>> {
+        self.fallback.clone()
+    }
+
+    /// Sets the global middlewares, those of the app whose router runs.
+    pub fn wrap(&mut self, middleware: Rc<[Box<dyn DynMiddleware<D>>]>) {
+        self.middleware = middleware;
+    }
+
+    // a router is configured the same way on every request, its first registration is always the same
+    fn first(&mut self, key: String) -> bool {
+        if let Some(first) = &self.first {
+            return *first == key;
+        }
+
+        self.first = Some(key);
+        true
+    }
+
+    /// Returns the handler of `method` matching `path`, among those the router can't register by themselves.
+    ///
+    /// The method is read as sent, eg: `PURGE`.
+    pub fn handler(&self, path: &str, method: &str) -> Option<Handler<D>> {
+        let mut node = Node::new();
+        for (i, handler) in self.handlers.iter().enumerate() {
+            if handler.1.iter().any(|v| v.eq_ignore_ascii_case(method)) {
+                // the same pattern can't be recorded twice for a method, it's matched the same as the router's
+                let _ = node.insert(handler.0.as_str(), i);
+            }
+        }
+        let i = *node.at(path).ok()?.value;
+        Some(Rc::clone(&self.handlers[i].2))
+    }
+
+    /// Returns the methods whose handlers match `path`.
+    ///
+    /// The router matches each method on its own, among the patterns of the method,
+    /// eg: with `GET /users/:id` and `DELETE /users/me`, `/users/me` allows both but `/users/42` only `GET`.
+    pub fn allowed(&self, path: &str) -> Vec<&str> {
+        let mut methods: Vec<&str> = METHODS.iter().map(AsRef::as_ref).collect();
+        // `CONNECT`, `TRACE` and custom methods can't be registered by themselves, they're listed last
+        for method in self.methods.iter().flat_map(|v| &v.1) {
+            if !methods.contains(&method.as_str()) {
+                methods.push(method);
+            }
+        }
+
+        methods
+            .into_iter()
+            .filter(|method| {
+                let mut node = Node::new();
+                for (pattern, _) in self
+                    .methods
+                    .iter()
+                    .filter(|v| v.1.iter().any(|v| v == method))
+                {
+                    let _ = node.insert(pattern.as_str(), ());
+                }
+                node.at(path).is_ok()
+            })
+            .collect()
+    }
+}
+
+thread_local! {
+    // the routes of each data type, a worker runs its requests on a single thread
+    static ROUTES: RefCell<Vec<Box<dyn Any>>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Runs `f` with the routes of the routers whose data is `D`.
+pub fn with<D: 'static, T>(f: impl FnOnce(&mut Routes<D>) -> T) -> T {
+    ROUTES.with(|routes| {
+        let mut routes = routes.borrow_mut();
+        if let Some(routes) = routes.iter_mut().find_map(|v| v.downcast_mut()) {
+            return f(routes);
+        }
+
+        let mut recorded = Routes::default();
+        let res = f(&mut recorded);
+        routes.push(Box::new(recorded));
+        res
+    })
+}
+
+/// The global middlewares of the routers whose data is `D`.
+pub fn middleware<D: 'static>() -> Rc<[Box<dyn DynMiddleware<D>>]> {
+    with(|routes: &mut Routes<D>| Rc::clone(&routes.middleware))
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Handler, Routes};
+    use std::rc::Rc;
+    use worker::Response;
+
+    fn handler() -> Handler<()> {
+        Rc::new(|_, _| Box::pin(async { Response::empty() }))
+    }
+
+    #[test]
+    fn first() {
+        let mut routes = Routes::<()>::default();
+        assert!(routes.record("/users", &["GET"], None));
+        assert!(!routes.record("/users", &["POST"], None));
+        assert!(!routes.record_fallback(handler()));
+        // the router is configured again, for the next request
+        assert!(routes.record("/users", &["get"], None));
+        assert!(!routes.record("/users", &["POST"], None));
+    }
+
+    #[test]
+    fn allowed() {
+        let mut routes = Routes::<()>::default();
+        routes.record("/users/:id", &["GET"], None);
+        routes.record("/users/me", &["DELETE"], None);
+        routes.record("/users/:id", &["post", "GET"], None);
+        routes.record("/users/:id", &["PURGE"], Some(handler()));
+
+        assert_eq!(
+            routes.allowed("/users/me"),
+            vec!["GET", "POST", "DELETE", "PURGE"]
+        );
+        assert_eq!(routes.allowed("/users/42"), vec!["GET", "POST", "PURGE"]);
+        assert!(routes.allowed("/users").is_empty());
+        assert!(routes.allowed("/users/42/posts").is_empty());
+    }
+
+    #[test]
+    fn wildcards() {
+        let mut routes = Routes::<()>::default();
+        routes.record("/files/*path", &["GET"], None);
+        routes.record("/files/readme", &["PUT"], None);
+
+        // overlapping patterns are matched in their own table, as the router does
+        assert_eq!(routes.allowed("/files/readme"), vec!["GET", "PUT"]);
+        assert_eq!(routes.allowed("/files/a/b"), vec!["GET"]);
+        assert_eq!(routes.allowed("/files/"), vec!["GET"]);
+    }
+
+    #[test]
+    fn handlers() {
+        let mut routes = Routes::<()>::default();
+        routes.record("/cache/:key", &["GET"], None);
+        routes.record("/cache/:key", &["PURGE"], Some(handler()));
+        routes.record("/cache/all", &["purge", "COPY"], Some(handler()));
+        routes.record("/trace", &["TRACE"], Some(handler()));
+
+        assert!(routes.handler("/cache/a", "purge").is_some());
+        assert!(routes.handler("/cache/all", "COPY").is_some());
+        assert!(routes.handler("/cache/a", "COPY").is_none());
+        // the router runs the handlers of the methods it registers by itself
+        assert!(routes.handler("/cache/a", "GET").is_none());
+        assert!(routes.handler("/trace", "TRACE").is_some());
+        assert!(routes.handler("/users", "PURGE").is_none());
+    }
+
+    #[test]
+    fn recorded_again() {
+        let mut routes = Routes::<()>::default();
+        for _ in 0..2 {
+            routes.record("/cache/:key", &["PURGE"], Some(handler()));
+            routes.record("/cache/:key", &["GET"], None);
+            routes.record_fallback(handler());
+        }
+
+        assert_eq!(routes.handlers.len(), 1);
+        assert_eq!(
+            routes.methods,
+            vec![(
+                "/cache/:key".to_owned(),
+                vec!["PURGE".to_owned(), "GET".to_owned()]
+            )]
+        );
+        assert_eq!(routes.allowed("/cache/a"), vec!["GET", "PURGE"]);
+    }
+
+    #[test]
+    fn data_types() {
+        super::with(|routes: &mut Routes<()>| routes.record("/users", &["GET"], None));
+        super::with(|routes: &mut Routes<u8>| routes.record("/posts", &["GET"], None));
+
+        assert_eq!(
+            super::with(|routes: &mut Routes<()>| routes.allowed("/users").len()),
+            1
+        );
+        assert!(super::with(|routes: &mut Routes<u8>| routes
+            .allowed("/users")
+            .is_empty()));
+    }
+}
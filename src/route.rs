@@ -1,5 +1,13 @@
+use crate::error::{Error, ErrorCause};
+use crate::http::{HttpRequest, ResponseError};
+use crate::middleware;
+use crate::registry::{self, Routes};
+
 use core::future::Future;
-use worker::{console_debug, Method, Request, Response, Result, RouteContext, Router};
+use http::header::ALLOW;
+use http::StatusCode;
+use std::rc::Rc;
+use worker::{Method, Request, Response, Result, RouteContext, Router};
 
 // This trait is exactly the same as the one that RouteContext uses to get params
 // This is used mainly for testing-suite
@@ -18,17 +26,38 @@ impl<D> Params for RouteContext<D> {
 /// Used for code generation, not for public usage.
 pub trait RouteFactory<D> {
     /// Used for code generation, not for public usage.
-    fn register(self, router: Router<'_, D>) -> Router<'_, D>;
+    fn register<R: AddHandler<D>>(self, router: R) -> R;
+
+    /// Used for code generation, not for public usage.
+    fn describe(&self, _routes: &mut Vec<crate::table::RouteInfo>) {}
+
+    /// Used for code generation, not for public usage.
+    #[cfg(feature = "openapi")]
+    fn operations(&self, _operations: &mut Vec<crate::openapi::Operation>) {}
 }
 
-/// Implemented for [`worker::Router`](https://docs.rs/worker/latest/worker/struct.Router.html) to configure the route's pattern.
+/// Implemented for [`worker::Router`](https://docs.rs/worker/latest/worker/struct.Router.html) and [`App`](crate::App) to configure the route's pattern.
+///
+/// The methods of each pattern are recorded as the handlers are configured, the first handler of the router
+/// adds a catch-all, which answers the requests the router doesn't run a handler for:
+/// - the handler of `CONNECT`, `TRACE` or a custom method, eg: `PURGE`,
+/// - an `OPTIONS` request without a handler with `204 No Content` and an `Allow` header,
+/// - any other method with a `405 Method Not Allowed` error and an `Allow` header listing the allowed methods,
+/// - a path without handlers with the [`fallback`](crate::fallback).
+///
+/// The router answers the other standard methods of a path on its own, with a `405 Method Not Allowed`
+/// without an `Allow` header, and `CONNECT` only reaches its handler if no handler of another method matches the path.
+///
+/// The handlers are recorded per data type of the router: a worker configuring the same handlers
+/// into its router on every request, as usual, gets the same answers. Routers with different handlers
+/// should have different data types.
 ///
 /// # Example
 ///
 /// ```
 /// use serde::{Deserialize, Serialize};
 /// use worker::{event, Env, Request, Response, ResponseBody, Result, RouteContext, Router};
-/// use worker_route::{get, Configure, Query, Service};
+/// use worker_route::{get, Configure, Path, Service};
 ///
 /// #[derive(Debug, Deserialize, Serialize)]
 /// struct Person {
@@ -37,7 +66,7 @@ pub trait RouteFactory<D> {
 /// }
 ///
 /// #[get("/person/:name/:age")]
-/// async fn person(req: Query<Person>, _: RouteContext<()>) -> Result<Response> {
+/// async fn person(req: Path<Person>, _: RouteContext<()>) -> Result<Response> {
 ///     Response::from_json(&req.into_inner())
 /// }
 ///
@@ -53,14 +82,32 @@ pub trait Configure<D> {
     fn configure<F: RouteFactory<D>>(self, f: F) -> Self;
 }
 
-impl<D> Configure<D> for Router<'_, D> {
+impl<D: 'static> Configure<D> for Router<'_, D> {
     fn configure<F: RouteFactory<D>>(self, f: F) -> Self {
         f.register(self)
     }
 }
 
+pub fn not_allowed_response(req: &Request, method: &str, allow: &str) -> Result<Response> {
+    let err = Error::new(
+        format!("method `{method}` is not allowed"),
+        StatusCode::METHOD_NOT_ALLOWED,
+        ErrorCause::Route,
+    );
+    let mut res = err.error_response(HttpRequest::from(req)).into_res();
+    res.headers_mut().set(ALLOW.as_str(), allow)?;
+    Ok(res)
+}
+
+// the answer to an `OPTIONS` request without a handler, of a path with others
+pub fn options_response(allow: &str) -> Result<Response> {
+    let mut res = Response::empty()?.with_status(StatusCode::NO_CONTENT.as_u16());
+    res.headers_mut().set(ALLOW.as_str(), allow)?;
+    Ok(res)
+}
+
 /// Implemented for [`worker::Router`](https://docs.rs/worker/latest/worker/struct.Router.html) to run external route configuration.
-/// 
+///
 /// This trait is useful for splitting the configuration to a different module.
 ///
 /// # Example
@@ -68,7 +115,7 @@ impl<D> Configure<D> for Router<'_, D> {
 /// ```
 /// use serde::{Deserialize, Serialize};
 /// use worker::{event, Env, Request, Response, ResponseBody, Result, RouteContext, Router};
-/// use worker_route::{get, Service, Configure, Query};
+/// use worker_route::{get, Service, Configure, Path, Query};
 ///
 /// #[derive(Debug, Serialize, Deserialize)]
 /// struct Bar {
@@ -97,7 +144,7 @@ impl<D> Configure<D> for Router<'_, D> {
 /// }
 ///
 /// #[get("/person/:name/:age")]
-/// async fn person(req: Query<Person>, _: RouteContext<()>) -> Result<Response> {
+/// async fn person(req: Path<Person>, _: RouteContext<()>) -> Result<Response> {
 ///     Response::from_json(&req.into_inner())
 /// }
 ///
@@ -129,11 +176,14 @@ impl<D> Service for Router<'_, D> {
     }
 }
 
-type Handler<D, U> = fn(Request, RouteContext<D>) -> U;
+pub type Handler<D, U> = fn(Request, RouteContext<D>) -> U;
 
 #[doc(hidden)]
 /// Used for code generation, not for public usage.
-pub trait AddHandler<'a, D> {
+///
+/// Implemented for a router, which records the methods of each pattern as it adds their handler,
+/// and for an [`App`](crate::App), which adds them to its router.
+pub trait AddHandler<D> {
     /// Used for code generation, not for public usage.
     fn register(
         self,
@@ -142,22 +192,50 @@ pub trait AddHandler<'a, D> {
         sync_handler: Handler<D, Result<Response>>,
     ) -> Self;
     /// Used for code generation, not for public usage.
-    fn register_async<U: Future<Output = Result<Response>> + 'a>(
+    fn register_async<U: Future<Output = Result<Response>> + 'static>(
         self,
         pattern: &str,
         method: Method,
         async_handler: Handler<D, U>,
     ) -> Self;
+    /// Used for code generation, not for public usage.
+    ///
+    /// Registers the handler of `CONNECT`, `TRACE` or custom methods, which a router can't register by themselves,
+    /// the catch-all of the router runs it for the request's method as sent, see [`dispatch`].
+    fn register_any(
+        self,
+        pattern: &str,
+        methods: &[&str],
+        sync_handler: Handler<D, Result<Response>>,
+    ) -> Self;
+    /// Used for code generation, not for public usage.
+    fn register_any_async<U: Future<Output = Result<Response>> + 'static>(
+        self,
+        pattern: &str,
+        methods: &[&str],
+        async_handler: Handler<D, U>,
+    ) -> Self;
+    /// Used for code generation, not for public usage.
+    fn fallback(self, sync_handler: Handler<D, Result<Response>>) -> Self;
+    /// Used for code generation, not for public usage.
+    fn fallback_async<U: Future<Output = Result<Response>> + 'static>(
+        self,
+        async_handler: Handler<D, U>,
+    ) -> Self;
 }
 
-impl<'a, D: 'a> AddHandler<'a, D> for Router<'a, D> {
+// matches every path, `/` included, the param is the whole path.
+// The only pattern the router runs for any method: a catch-all can't be registered next to another pattern
+const CATCH_ALL: &str = "/*path";
+
+impl<D: 'static> AddHandler<D> for Router<'_, D> {
     fn register(
         self,
         pattern: &str,
         method: Method,
         sync_handler: Handler<D, Result<Response>>,
     ) -> Self {
-        match method {
+        let router = match method {
             Method::Head => self.head(pattern, sync_handler),
             Method::Get => self.get(pattern, sync_handler),
             Method::Post => self.post(pattern, sync_handler),
@@ -165,22 +243,23 @@ impl<'a, D: 'a> AddHandler<'a, D> for Router<'a, D> {
             Method::Patch => self.patch(pattern, sync_handler),
             Method::Delete => self.delete(pattern, sync_handler),
             Method::Options => self.options(pattern, sync_handler),
-            _ => {
-                // the method variant is passed from the macro module
-                // it should not panic by right.
-                console_debug!("{:?} is not supported.", method);
-                panic!()
+            // a router can't register them by themselves, the catch-all only runs them for their method
+            Method::Connect | Method::Trace => {
+                return self.register_any(pattern, &[method.as_ref()], sync_handler)
             }
-        }
+        };
+        record(router, |routes| {
+            routes.record(pattern, &[method.as_ref()], None)
+        })
     }
 
-    fn register_async<U: Future<Output = Result<Response>> + 'a>(
+    fn register_async<U: Future<Output = Result<Response>> + 'static>(
         self,
         pattern: &str,
         method: Method,
         async_handler: Handler<D, U>,
     ) -> Self {
-        match method {
+        let router = match method {
             Method::Head => self.head_async(pattern, async_handler),
             Method::Get => self.get_async(pattern, async_handler),
             Method::Post => self.post_async(pattern, async_handler),
@@ -188,12 +267,218 @@ impl<'a, D: 'a> AddHandler<'a, D> for Router<'a, D> {
             Method::Patch => self.patch_async(pattern, async_handler),
             Method::Delete => self.delete_async(pattern, async_handler),
             Method::Options => self.options_async(pattern, async_handler),
-            _ => {
-                // the method variant is passed from the macro module
-                // it should not panic by right.
-                console_debug!("{:?} is not supported.", method);
-                panic!()
+            Method::Connect | Method::Trace => {
+                return self.register_any_async(pattern, &[method.as_ref()], async_handler)
             }
-        }
+        };
+        record(router, |routes| {
+            routes.record(pattern, &[method.as_ref()], None)
+        })
+    }
+
+    fn register_any(
+        self,
+        pattern: &str,
+        methods: &[&str],
+        sync_handler: Handler<D, Result<Response>>,
+    ) -> Self {
+        record(self, |routes| {
+            routes.record(pattern, methods, Some(boxed(sync_handler)))
+        })
+    }
+
+    fn register_any_async<U: Future<Output = Result<Response>> + 'static>(
+        self,
+        pattern: &str,
+        methods: &[&str],
+        async_handler: Handler<D, U>,
+    ) -> Self {
+        record(self, |routes| {
+            routes.record(pattern, methods, Some(boxed_async(async_handler)))
+        })
+    }
+
+    fn fallback(self, sync_handler: Handler<D, Result<Response>>) -> Self {
+        record(self, |routes| routes.record_fallback(boxed(sync_handler)))
+    }
+
+    fn fallback_async<U: Future<Output = Result<Response>> + 'static>(
+        self,
+        async_handler: Handler<D, U>,
+    ) -> Self {
+        record(self, |routes| {
+            routes.record_fallback(boxed_async(async_handler))
+        })
+    }
+}
+
+// records a registration, the first of the router adds the catch-all
+fn record<D: 'static>(
+    router: Router<'_, D>,
+    f: impl FnOnce(&mut Routes<D>) -> bool,
+) -> Router<'_, D> {
+    if registry::with(f) {
+        return router.or_else_any_method_async(CATCH_ALL, dispatch::<D>);
+    }
+
+    router
+}
+
+fn boxed<D: 'static>(sync_handler: Handler<D, Result<Response>>) -> registry::Handler<D> {
+    Rc::new(move |req, ctx| Box::pin(async move { sync_handler(req, ctx) }))
+}
+
+fn boxed_async<D: 'static, U: Future<Output = Result<Response>> + 'static>(
+    async_handler: Handler<D, U>,
+) -> registry::Handler<D> {
+    Rc::new(move |req, ctx| Box::pin(async_handler(req, ctx)))
+}
+
+#[doc(hidden)]
+/// Used for code generation, not for public usage.
+///
+/// Returns `true` if the request's method isn't one of `worker::Method`, which reads it as `GET`, eg: `PURGE`,
+/// nor one of the handler's `methods`.
+pub fn is_custom(req: &Request, methods: &[&str]) -> bool {
+    let method = req.inner().method();
+    !req.method().as_ref().eq_ignore_ascii_case(&method)
+        && !methods.iter().any(|v| v.eq_ignore_ascii_case(&method))
+}
+
+#[doc(hidden)]
+/// Used for code generation, not for public usage.
+///
+/// The catch-all of a router, it runs the handler of the request's method, read as sent.
+/// The router runs the `GET` handler of a path for a custom method, which hands the request over, see [`is_custom`].
+#[allow(clippy::future_not_send)]
+pub async fn dispatch<D: 'static>(req: Request, ctx: RouteContext<D>) -> Result<Response> {
+    let path = req.path();
+    let method = req.inner().method();
+    let (handler, allow, fallback) = registry::with(|routes: &mut Routes<D>| {
+        (
+            routes.handler(&path, &method),
+            routes.allowed(&path).join(", "),
+            routes.fallback(),
+        )
+    });
+    if let Some(handler) = handler {
+        return handler(req, ctx).await;
+    }
+
+    if !allow.is_empty() {
+        // the global middlewares run around the answer, eg: a `CorsPolicy` answers a preflight
+        return middleware::wrap(req, ctx, &[], |req, _| async move {
+            if method.eq_ignore_ascii_case("OPTIONS") {
+                options_response(&allow)
+            } else {
+                not_allowed_response(&req, &method, &allow)
+            }
+        })
+        .await;
+    }
+
+    match fallback {
+        Some(fallback) => fallback(req, ctx).await,
+        None => Response::error("Not Found", 404),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::AddHandler;
+    use crate::registry::{self, Routes};
+    use worker::{Method, Request, Response, Result, RouteContext, Router};
+
+    #[allow(clippy::needless_pass_by_value)]
+    fn handler<D>(_: Request, _: RouteContext<D>) -> Result<Response> {
+        Response::empty()
+    }
+
+    #[test]
+    fn recorded_methods() {
+        let router = || {
+            Router::with_data(0_u16)
+                .register("/users/:id", Method::Get, handler)
+                .register("/users/:id", Method::Delete, handler)
+                .register("/users/me", Method::Put, handler)
+        };
+        router();
+        router();
+
+        registry::with(|routes: &mut Routes<u16>| {
+            assert_eq!(routes.allowed("/users/42"), vec!["GET", "DELETE"]);
+            assert_eq!(routes.allowed("/users/me"), vec!["GET", "PUT", "DELETE"]);
+            assert!(routes.allowed("/posts").is_empty());
+            // the router runs the handlers of the standard methods
+            assert!(routes.handler("/users/42", "GET").is_none());
+        });
+    }
+
+    #[test]
+    fn fallback_and_custom_methods() {
+        let router = || {
+            Router::with_data(0_i16)
+                .register("/cache/:key", Method::Get, handler)
+                .register_any("/cache/:key", &["PURGE"], handler)
+                .register_any("/cache/all", &["PURGE", "COPY"], handler)
+                .fallback(handler)
+        };
+        // the fallback is run by the router's catch-all, which is added once, by the first handler
+        router();
+        router();
+
+        registry::with(|routes: &mut Routes<i16>| {
+            assert!(routes.fallback().is_some());
+            assert!(routes.handler("/cache/a", "PURGE").is_some());
+            assert!(routes.handler("/cache/all", "COPY").is_some());
+            assert!(routes.handler("/users", "PURGE").is_none());
+            assert_eq!(routes.allowed("/cache/all"), vec!["GET", "PURGE", "COPY"]);
+        });
+    }
+
+    #[test]
+    fn fallback_first() {
+        let router = || {
+            Router::with_data(0_i32).fallback(handler).register_any(
+                "/cache/:key",
+                &["PURGE"],
+                handler,
+            )
+        };
+        router();
+        router();
+
+        registry::with(|routes: &mut Routes<i32>| {
+            assert!(routes.fallback().is_some());
+            assert!(routes.handler("/cache/a", "PURGE").is_some());
+        });
+    }
+
+    #[test]
+    fn connect_and_trace() {
+        Router::with_data(0_i8)
+            .register("/trace", Method::Trace, handler)
+            .register_async("/tunnel", Method::Connect, |_, _| async {
+                Response::empty()
+            });
+
+        // they're run by the catch-all for their method only, the others get a 405
+        registry::with(|routes: &mut Routes<i8>| {
+            assert!(routes.handler("/trace", "TRACE").is_some());
+            assert!(routes.handler("/trace", "GET").is_none());
+            assert!(routes.handler("/tunnel", "CONNECT").is_some());
+            assert!(routes.handler("/tunnel", "TRACE").is_none());
+            assert_eq!(routes.allowed("/trace"), vec!["TRACE"]);
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "/*path")]
+    fn catch_all() {
+        let router =
+            || Router::with_data(0_usize).register("/users", Method::Get, |_, _| Response::empty());
+        router();
+        // the router configured for the next request has its catch-all too
+        router().or_else_any_method("/posts", |_, _| Response::empty());
     }
 }
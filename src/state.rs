@@ -0,0 +1,213 @@
+use crate::extract::FromRequest;
+
+use core::convert::Infallible;
+use core::fmt::Display;
+use std::ops::{Deref, DerefMut};
+use worker::{Request, RouteContext};
+
+/// Used to project a part of the router's data `T` into `Self`, see [`State`].
+///
+/// Implemented for every `T: Clone`, the whole data is then extracted.
+///
+/// ```
+/// use worker_route::FromRef;
+///
+/// #[derive(Clone)]
+/// struct Config {
+///     api_url: String,
+/// }
+///
+/// struct AppData {
+///     config: Config,
+///     version: u32,
+/// }
+///
+/// impl FromRef<AppData> for Config {
+///     fn from_ref(data: &AppData) -> Self {
+///         data.config.clone()
+///     }
+/// }
+/// ```
+pub trait FromRef<T> {
+    /// Creates `Self` from a reference of the router's data.
+    fn from_ref(input: &T) -> Self;
+}
+
+impl<T: Clone> FromRef<T> for T {
+    fn from_ref(input: &T) -> Self {
+        input.clone()
+    }
+}
+
+/// Extract the router's data, or a part of it, given with
+/// [`worker::Router::with_data`](https://docs.rs/worker/latest/worker/struct.Router.html#method.with_data).
+///
+/// `S` must implement [`FromRef<D>`](FromRef) where `D` is the router's data.
+/// Handlers taking `State<S>` don't need to take a `RouteContext<D>`,
+/// they can be configured on any router whose data can be projected into `S`.
+///
+/// ```
+/// use std::rc::Rc;
+/// use worker::{Response, Result, Router};
+/// use worker_route::{get, Configure, FromRef, State};
+///
+/// #[derive(Clone)]
+/// struct Config {
+///     api_url: String,
+/// }
+///
+/// #[derive(Clone)]
+/// struct AppData {
+///     config: Config,
+///     counter: Rc<u32>,
+/// }
+///
+/// impl FromRef<AppData> for Config {
+///     fn from_ref(data: &AppData) -> Self {
+///         data.config.clone()
+///     }
+/// }
+///
+/// #[get("/config")]
+/// async fn config(config: State<Config>) -> Result<String> {
+///     Ok(config.into_inner().api_url)
+/// }
+///
+/// #[get("/counter")]
+/// async fn counter(data: State<AppData>) -> Result<String> {
+///     Ok(data.counter.to_string())
+/// }
+///
+/// fn init_routes(router: Router<'static, AppData>) -> Router<'static, AppData> {
+///     router.configure(config).configure(counter)
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct State<S>(S);
+
+impl<S> State<S> {
+    #[allow(clippy::missing_const_for_fn)]
+    /// Acess the owned `S`
+    pub fn into_inner(self) -> S {
+        self.0
+    }
+
+    // the state projected from the router's data, as extracted by a handler
+    fn from_data<D>(data: &D) -> Self
+    where
+        S: FromRef<D>,
+    {
+        Self(S::from_ref(data))
+    }
+}
+
+impl<D, S: FromRef<D>> FromRequest<D> for State<S> {
+    type Error = Infallible;
+
+    #[allow(clippy::future_not_send)]
+    async fn from_request(_: &Request, ctx: &RouteContext<D>) -> Result<Self, Self::Error> {
+        Ok(Self::from_data(&ctx.data))
+    }
+}
+
+impl<S: Display> Display for State<S> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl<S> Deref for State<S> {
+    type Target = S;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<S> DerefMut for State<S> {
+    fn deref_mut(&mut self) -> &mut S {
+        &mut self.0
+    }
+}
+
+impl<S> AsRef<S> for State<S> {
+    fn as_ref(&self) -> &S {
+        &self.0
+    }
+}
+
+impl<S> AsMut<S> for State<S> {
+    fn as_mut(&mut self) -> &mut S {
+        &mut self.0
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{FromRef, State};
+    use std::rc::Rc;
+
+    #[derive(Debug, Clone, PartialEq)]
+    struct Config {
+        api_url: String,
+    }
+
+    #[derive(Clone)]
+    struct AppData {
+        config: Config,
+        counter: Rc<u32>,
+    }
+
+    impl FromRef<AppData> for Config {
+        fn from_ref(data: &AppData) -> Self {
+            data.config.clone()
+        }
+    }
+
+    // not `Clone`, only ever projected
+    struct ApiUrl(String);
+
+    impl FromRef<AppData> for ApiUrl {
+        fn from_ref(data: &AppData) -> Self {
+            Self(data.config.api_url.clone())
+        }
+    }
+
+    fn data() -> AppData {
+        AppData {
+            config: Config {
+                api_url: "https://api.example.com".to_owned(),
+            },
+            counter: Rc::new(1),
+        }
+    }
+
+    #[test]
+    fn from_ref() {
+        let data = data();
+        assert_eq!(Config::from_ref(&data), data.config);
+        assert_eq!(ApiUrl::from_ref(&data).0, "https://api.example.com");
+
+        // the whole data, shared rather than copied
+        let whole = AppData::from_ref(&data);
+        assert!(Rc::ptr_eq(&whole.counter, &data.counter));
+    }
+
+    #[test]
+    fn state() {
+        let data = data();
+        let mut config = State::<Config>::from_data(&data);
+        assert_eq!(config.api_url, "https://api.example.com");
+
+        config.api_url.push_str("/v2");
+        assert_eq!(config.into_inner().api_url, "https://api.example.com/v2");
+        // the router's data is untouched
+        assert_eq!(data.config.api_url, "https://api.example.com");
+
+        assert_eq!(
+            State::<ApiUrl>::from_data(&data).0 .0,
+            "https://api.example.com"
+        );
+        assert_eq!(*State::<AppData>::from_data(&data).counter, 1);
+    }
+}
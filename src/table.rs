@@ -0,0 +1,411 @@
+use crate::route::{Configure, RouteFactory, Service};
+
+use core::fmt::Display;
+use core::marker::PhantomData;
+use worker::{console_debug, Method};
+
+/// How a route applies CORS, set with the `cors` or `lazy_cors` attribute.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CorsMode {
+    /// The route doesn't apply CORS.
+    None,
+    /// `cors = T`, the name of the [`Wrap`](crate::Wrap) type.
+    Cors(&'static str),
+    /// `lazy_cors = T`, the name of the lazily initialized `Cors`.
+    Lazy(&'static str),
+}
+
+/// The metadata of a route, exposed as `ROUTE` on every handler generated by the route macros.
+///
+/// ```
+/// use worker::{Method, Result};
+/// use worker_route::{get, Query};
+/// # #[derive(serde::Deserialize)]
+/// # struct Search { q: String }
+///
+/// #[get("/search")]
+/// async fn search(query: Query<Search>) -> Result<String> {
+///     Ok(query.into_inner().q)
+/// }
+///
+/// assert_eq!(search::ROUTE.name, "search");
+/// assert_eq!(search::ROUTE.path, "/search");
+/// assert_eq!(search::ROUTE.methods, &[Method::Get]);
+/// assert_eq!(search::ROUTE.extractors, &["Query<Search>"]);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct RouteInfo {
+    /// The name of the handler.
+    pub name: &'static str,
+    /// The pattern of the route, eg: `/users/:id`.
+    pub path: &'static str,
+    /// The methods of the route, empty for a [`fallback`](crate::fallback).
+    pub methods: &'static [Method],
+    /// The custom methods of the route, eg: `PURGE` or `PROPFIND`.
+    pub custom_methods: &'static [&'static str],
+    /// How the route applies CORS.
+    pub cors: CorsMode,
+    /// `true` if an `OPTIONS` handler is registered for the route's CORS, with the `preflight` attribute.
+    pub preflight: bool,
+    /// The middlewares of the `wrap` attribute, as written, eg: `["Auth", "Timing"]`.
+    ///
+    /// The middlewares added with [`App::wrap`](crate::App::wrap) aren't listed.
+    pub middleware: &'static [&'static str],
+    /// The quota of the `rate_limit` attribute, eg: `100/min`.
+    pub rate_limit: Option<&'static str>,
+    /// The types extracted with [`FromRequest`](crate::FromRequest), in the declared order.
+    pub extractors: &'static [&'static str],
+}
+
+impl RouteInfo {
+    #[doc(hidden)]
+    /// Used for code generation, not for public usage.
+    #[allow(clippy::too_many_arguments)]
+    pub const fn __new(
+        name: &'static str,
+        path: &'static str,
+        methods: &'static [Method],
+        custom_methods: &'static [&'static str],
+        cors: CorsMode,
+        preflight: bool,
+        middleware: &'static [&'static str],
+        rate_limit: Option<&'static str>,
+        extractors: &'static [&'static str],
+    ) -> Self {
+        Self {
+            name,
+            path,
+            methods,
+            custom_methods,
+            cors,
+            preflight,
+            middleware,
+            rate_limit,
+            extractors,
+        }
+    }
+
+    /// Returns `true` if the route is a [`fallback`](crate::fallback).
+    pub fn is_fallback(&self) -> bool {
+        self.methods.is_empty() && self.custom_methods.is_empty()
+    }
+
+    /// Returns `true` if the route has `CONNECT`, `TRACE` or a custom method,
+    /// which the router can't register by themselves, its catch-all runs the handler, see [`Configure`](crate::Configure).
+    pub fn is_catch_all(&self) -> bool {
+        !self.custom_methods.is_empty()
+            || self
+                .methods
+                .iter()
+                .any(|v| matches!(v, Method::Connect | Method::Trace))
+    }
+}
+
+impl Display for RouteInfo {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let methods = if self.is_fallback() {
+            "*".to_owned()
+        } else {
+            self.methods
+                .iter()
+                .map(AsRef::as_ref)
+                .chain(self.custom_methods.iter().copied())
+                .collect::<Vec<&str>>()
+                .join(",")
+        };
+        write!(f, "{methods:<12} {:<32} {}", self.path, self.name)?;
+        match self.cors {
+            CorsMode::None => {}
+            CorsMode::Cors(cors) | CorsMode::Lazy(cors) => write!(f, " (cors: {cors})")?,
+        }
+        self.rate_limit
+            .map_or(Ok(()), |quota| write!(f, " (rate limit: {quota})"))
+    }
+}
+
+/// Every route configured with [`Configure`], for printing, testing or finding duplicates.
+///
+/// `D` is the router's data, as the routes can be configured for a given `D` only.
+///
+/// ```
+/// use worker::{Method, Result};
+/// use worker_route::{get, post, Configure, RouteTable};
+///
+/// #[get("/users")]
+/// async fn users() -> Result<String> {
+///     Ok("users".to_owned())
+/// }
+///
+/// #[post("/users")]
+/// async fn create_user() -> Result<String> {
+///     Ok("created".to_owned())
+/// }
+///
+/// let table: RouteTable<()> = RouteTable::new().configure(users).configure(create_user);
+///
+/// assert_eq!(table.len(), 2);
+/// assert_eq!(table.find(&Method::Post, "/users").unwrap().name, "create_user");
+/// assert!(table.duplicates().is_empty());
+/// // prints every route, one per line
+/// println!("{table}");
+/// ```
+#[derive(Debug, Clone)]
+pub struct RouteTable<D> {
+    routes: Vec<RouteInfo>,
+    #[cfg(feature = "openapi")]
+    operations: Vec<crate::openapi::Operation>,
+    _data: PhantomData<D>,
+}
+
+impl<D> RouteTable<D> {
+    /// Creates an empty table.
+    pub fn new() -> Self {
+        Self {
+            routes: Vec::new(),
+            #[cfg(feature = "openapi")]
+            operations: Vec::new(),
+            _data: PhantomData,
+        }
+    }
+
+    /// Returns the number of routes.
+    pub fn len(&self) -> usize {
+        self.routes.len()
+    }
+
+    /// Returns `true` if no route is configured.
+    pub fn is_empty(&self) -> bool {
+        self.routes.is_empty()
+    }
+
+    /// Returns an iterator over the routes, in the order they were configured.
+    pub fn iter(&self) -> core::slice::Iter<'_, RouteInfo> {
+        self.routes.iter()
+    }
+
+    /// Returns the route registered for the given method and pattern.
+    pub fn find(&self, method: &Method, path: &str) -> Option<&RouteInfo> {
+        self.routes
+            .iter()
+            .find(|v| v.path == path && v.methods.contains(method))
+    }
+
+    /// Returns every method and pattern registered more than once,
+    /// the router panics when such routes are configured.
+    ///
+    /// The `OPTIONS` handler of a route with a [`preflight`](RouteInfo::preflight) is accounted for,
+    /// the custom methods of a [catch-all](RouteInfo::is_catch_all) route aren't.
+    pub fn duplicates(&self) -> Vec<(Method, &'static str)> {
+        let mut seen = Vec::new();
+        let mut duplicates = Vec::new();
+        for route in &self.routes {
+            let preflight = (route.preflight && !route.methods.contains(&Method::Options))
+                .then_some(&Method::Options);
+            for method in route.methods.iter().chain(preflight) {
+                let pair = (method.clone(), route.path);
+                if seen.contains(&pair) {
+                    if !duplicates.contains(&pair) {
+                        duplicates.push(pair);
+                    }
+                } else {
+                    seen.push(pair);
+                }
+            }
+        }
+
+        duplicates
+    }
+
+    /// Returns the [`OpenApi`](crate::openapi::OpenApi) document of every route but the [`fallback`](crate::fallback).
+    ///
+    /// See [`openapi`](crate::openapi) for an example.
+    #[cfg_attr(docsrs, doc(cfg(feature = "openapi")))]
+    #[cfg(feature = "openapi")]
+    pub fn openapi(&self, title: &str, version: &str) -> crate::openapi::OpenApi {
+        crate::openapi::OpenApi::new(title, version, self.operations.clone())
+    }
+}
+
+impl<D> RouteTable<D> {
+    #[doc(hidden)]
+    /// Used for code generation, not for public usage.
+    ///
+    /// A table of the same data as `router`, the data is inferred from it.
+    pub fn __of<C: Configure<D>>(_router: &C) -> Self {
+        Self::new()
+    }
+
+    #[doc(hidden)]
+    /// Used for code generation, not for public usage.
+    ///
+    /// Logs every duplicate, before the router panics on them.
+    pub fn __log_duplicates(&self) {
+        for (method, path) in self.duplicates() {
+            console_debug!("{} {path} is configured more than once.", method.as_ref());
+        }
+    }
+}
+
+/// Configures every handler of the given [`scope`](crate::scope) or [`handlers`](crate::handlers) modules,
+/// nested modules included.
+///
+/// Returns a closure to be used with [`Service`](crate::Service),
+/// the handlers of a same method and pattern are logged with `console_debug!` in debug builds.
+///
+/// ```
+/// use worker::Router;
+/// use worker_route::{handlers, routes, scope, Service};
+///
+/// #[handlers]
+/// mod app {
+///     use worker::Result;
+///     use worker_route::get;
+///
+///     #[get("/")]
+///     async fn index() -> Result<String> {
+///         Ok("index".to_owned())
+///     }
+/// }
+///
+/// #[scope("/api")]
+/// mod api {
+///     use worker::Result;
+///     use worker_route::get;
+///
+///     #[get("/users")]
+///     async fn users() -> Result<String> {
+///         Ok("users".to_owned())
+///     }
+/// }
+///
+/// fn init_routes(router: Router<'static, ()>) -> Router<'static, ()> {
+///     router.service(routes![app, api])
+/// }
+/// ```
+#[macro_export]
+macro_rules! routes {
+    ($($($module:ident)::+),+ $(,)?) => {
+        |router__| {
+            #[cfg(debug_assertions)]
+            {
+                let table__ = $crate::RouteTable::__of(&router__);
+                $(let table__ = $crate::Configure::configure(table__, $($module)::+::__scope);)+
+                table__.__log_duplicates();
+            }
+            $(let router__ = $crate::Configure::configure(router__, $($module)::+::__scope);)+
+            router__
+        }
+    };
+}
+
+impl<D> Default for RouteTable<D> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<D> Configure<D> for RouteTable<D> {
+    fn configure<F: RouteFactory<D>>(mut self, f: F) -> Self {
+        f.describe(&mut self.routes);
+        #[cfg(feature = "openapi")]
+        f.operations(&mut self.operations);
+        self
+    }
+}
+
+impl<D> Service for RouteTable<D> {
+    fn service<F: FnOnce(Self) -> Self>(self, f: F) -> Self {
+        f(self)
+    }
+}
+
+impl<'a, D> IntoIterator for &'a RouteTable<D> {
+    type Item = &'a RouteInfo;
+    type IntoIter = core::slice::Iter<'a, RouteInfo>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.routes.iter()
+    }
+}
+
+impl<D> Display for RouteTable<D> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        for route in &self.routes {
+            writeln!(f, "{route}")?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{CorsMode, RouteInfo, RouteTable};
+    use crate::route::{AddHandler, RouteFactory};
+    use worker::Method;
+
+    struct Users;
+
+    impl RouteFactory<()> for Users {
+        fn register<R: AddHandler<()>>(self, router: R) -> R {
+            router
+        }
+
+        fn describe(&self, routes: &mut Vec<RouteInfo>) {
+            routes.push(RouteInfo::__new(
+                "users",
+                "/users",
+                &[Method::Get, Method::Post],
+                &[],
+                CorsMode::Cors("ApiCors"),
+                false,
+                &["Auth"],
+                Some("100/min"),
+                &[],
+            ));
+        }
+    }
+
+    #[test]
+    fn table() {
+        let mut table = RouteTable::<()>::new();
+        Users.describe(&mut table.routes);
+
+        assert_eq!(table.find(&Method::Post, "/users").unwrap().name, "users");
+        assert!(table.find(&Method::Put, "/users").is_none());
+        assert!(table.duplicates().is_empty());
+        assert!(table.to_string().contains("GET,POST"));
+        assert!(table.to_string().contains("(rate limit: 100/min)"));
+
+        Users.describe(&mut table.routes);
+        assert_eq!(
+            table.duplicates(),
+            vec![(Method::Get, "/users"), (Method::Post, "/users")]
+        );
+    }
+
+    #[test]
+    fn catch_all() {
+        let mut table = RouteTable::<()>::new();
+        table.routes.push(RouteInfo::__new(
+            "purge",
+            "/cache",
+            &[],
+            &["PURGE"],
+            CorsMode::None,
+            false,
+            &[],
+            None,
+            &[],
+        ));
+        assert!(table.routes[0].is_catch_all());
+        assert!(!table.routes[0].is_fallback());
+        assert!(table.to_string().contains("PURGE"));
+        assert!(table.duplicates().is_empty());
+
+        // dispatched on its method, the other handlers of its path are kept
+        Users.describe(&mut table.routes);
+        table.routes[1].path = "/cache";
+        assert!(table.duplicates().is_empty());
+    }
+}
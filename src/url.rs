@@ -0,0 +1,163 @@
+use crate::error::Error;
+
+use core::fmt::Display;
+use percent_encoding::{utf8_percent_encode, AsciiSet, CONTROLS};
+use serde::Serialize;
+
+// https://url.spec.whatwg.org/#path-percent-encode-set, with `/` and `%` as a param is a single segment
+const SEGMENT: &AsciiSet = &CONTROLS
+    .add(b' ')
+    .add(b'"')
+    .add(b'#')
+    .add(b'<')
+    .add(b'>')
+    .add(b'?')
+    .add(b'`')
+    .add(b'{')
+    .add(b'}')
+    .add(b'/')
+    .add(b'%');
+
+/// A URL generated from the pattern of a handler, with the `url` function of its route macro.
+///
+/// Renaming a route or its parameters breaks the compilation instead of the links.
+///
+/// ```
+/// use serde::Serialize;
+/// use worker::Result;
+/// use worker_route::get;
+///
+/// #[get("/person/:name/:age<u8>")]
+/// async fn person() -> Result<String> {
+///     Ok("person".to_owned())
+/// }
+///
+/// #[derive(Serialize)]
+/// struct Page {
+///     page: u32,
+/// }
+///
+/// assert_eq!(person::url("bob smith", 30).to_string(), "/person/bob%20smith/30");
+/// assert_eq!(
+///     person::url("bob", 30).query(&Page { page: 2 }).unwrap().as_str(),
+///     "/person/bob/30?page=2"
+/// );
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct RouteUrl(String);
+
+impl RouteUrl {
+    #[doc(hidden)]
+    /// Used for code generation, not for public usage.
+    pub fn __new(path: &str) -> Self {
+        Self(path.to_owned())
+    }
+
+    #[doc(hidden)]
+    /// Used for code generation, not for public usage.
+    ///
+    /// Appends a static part of the pattern, as is.
+    pub fn __push(&mut self, path: &str) {
+        self.0.push_str(path);
+    }
+
+    #[doc(hidden)]
+    /// Used for code generation, not for public usage.
+    ///
+    /// Appends the value of a `:param`, a `/` is encoded as it would be another segment.
+    pub fn __param(&mut self, value: impl Display) {
+        self.0
+            .extend(utf8_percent_encode(&value.to_string(), SEGMENT));
+    }
+
+    #[doc(hidden)]
+    /// Used for code generation, not for public usage.
+    ///
+    /// Appends the value of a `*wildcard`, each of its segments is encoded.
+    pub fn __wildcard(&mut self, value: impl Display) {
+        let value = value.to_string();
+        for (i, segment) in value.trim_start_matches('/').split('/').enumerate() {
+            if i > 0 {
+                self.0.push('/');
+            }
+            self.0.extend(utf8_percent_encode(segment, SEGMENT));
+        }
+    }
+
+    /// Appends the serialized `query` to the URL's query string.
+    ///
+    /// # Errors
+    /// If `query` can't be serialized with [`serde_qs`](https://docs.rs/serde_qs).
+    pub fn query<T: Serialize>(mut self, query: &T) -> Result<Self, Error> {
+        let query = serde_qs::to_string(query)?;
+        if !query.is_empty() {
+            self.0.push(if self.0.contains('?') { '&' } else { '?' });
+            self.0.push_str(&query);
+        }
+
+        Ok(self)
+    }
+
+    /// Returns the URL as a string slice.
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// Returns the URL as a [`String`].
+    pub fn into_string(self) -> String {
+        self.0
+    }
+}
+
+impl Display for RouteUrl {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl AsRef<str> for RouteUrl {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl From<RouteUrl> for String {
+    fn from(url: RouteUrl) -> Self {
+        url.0
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::RouteUrl;
+    use serde::Serialize;
+    use std::collections::BTreeMap;
+
+    #[derive(Serialize)]
+    struct Page<'a> {
+        page: u32,
+        tag: Option<&'a str>,
+    }
+
+    #[test]
+    fn encode() {
+        let mut url = RouteUrl::__new("/files/");
+        url.__param("a b/c?");
+        url.__push("/");
+        url.__wildcard("/docs/read me.md");
+        assert_eq!(url.as_str(), "/files/a%20b%2Fc%3F/docs/read%20me.md");
+    }
+
+    #[test]
+    fn query() {
+        let url = RouteUrl::__new("/users")
+            .query(&Page {
+                page: 2,
+                tag: Some("a&b"),
+            })
+            .unwrap()
+            .query(&BTreeMap::from([("sort", "name")]))
+            .unwrap();
+        assert_eq!(url.into_string(), "/users?page=2&tag=a%26b&sort=name");
+    }
+}
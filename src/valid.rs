@@ -0,0 +1,217 @@
+use crate::error::{Error, ErrorCause};
+use crate::extract::FromRequest;
+
+use core::fmt::Display;
+use http::StatusCode;
+use serde_json::{json, Map, Value};
+use std::ops::Deref;
+use validator::{Validate, ValidationErrors, ValidationErrorsKind};
+use worker::{Request, RouteContext};
+
+/// Validate an extracted value with [`Validate`](validator::Validate).
+///
+/// `E` can be any extractor dereferencing to a `T: Validate`,
+/// eg: [`Query<T>`](crate::Query), [`Json<T>`](crate::Json), [`Form<T>`](crate::Form) or [`Path<T>`](crate::Path).
+/// Errors from extracting `E` are returned as is.
+///
+/// A `422 Unprocessable Entity` error is returned if the validation fails,
+/// with every invalid field listed under `fields`:
+///
+/// ```json
+/// {
+///     "message": "validation failed",
+///     "statusCode": 422,
+///     "success": false,
+///     "fields": {
+///         "age": [{ "code": "range", "message": null, "params": { "min": 18.0, "value": 12 } }]
+///     }
+/// }
+/// ```
+///
+/// Nested structs and lists are flattened, eg: `address.city` or `items[0].name`.
+///
+/// ```
+/// use serde::Deserialize;
+/// use validator::Validate;
+/// use worker::Result;
+/// use worker_route::{post, Json, Valid};
+///
+/// #[derive(Deserialize, Validate)]
+/// struct Person {
+///     #[validate(length(min = 1, max = 64))]
+///     name: String,
+///     #[validate(range(min = 18))]
+///     age: u32,
+/// }
+///
+/// #[post("/person")]
+/// async fn person(person: Valid<Json<Person>>) -> Result<String> {
+///     Ok(person.into_inner().into_inner().name)
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct Valid<E>(E);
+
+impl<E> Valid<E> {
+    #[allow(clippy::missing_const_for_fn)]
+    /// Acess the owned extractor `E`
+    pub fn into_inner(self) -> E {
+        self.0
+    }
+}
+
+impl<D, E> FromRequest<D> for Valid<E>
+where
+    E: FromRequest<D> + Deref,
+    E::Error: Into<Error>,
+    E::Target: Validate,
+{
+    type Error = Error;
+
+    #[allow(clippy::future_not_send)]
+    async fn from_request(req: &Request, ctx: &RouteContext<D>) -> Result<Self, Self::Error> {
+        validate(E::from_request(req, ctx).await.map_err(Into::into)?)
+    }
+
+    #[allow(clippy::future_not_send)]
+    async fn from_route(
+        req: &Request,
+        ctx: &RouteContext<D>,
+        pattern: &'static str,
+    ) -> Result<Self, Self::Error> {
+        validate(E::from_route(req, ctx, pattern).await.map_err(Into::into)?)
+    }
+
+    fn status_codes() -> Vec<StatusCode> {
+        let mut status_codes = E::status_codes();
+        if !status_codes.contains(&StatusCode::UNPROCESSABLE_ENTITY) {
+            status_codes.push(StatusCode::UNPROCESSABLE_ENTITY);
+        }
+        status_codes
+    }
+}
+
+fn validate<E: Deref>(extracted: E) -> Result<Valid<E>, Error>
+where
+    E::Target: Validate,
+{
+    match extracted.validate() {
+        Ok(()) => Ok(Valid(extracted)),
+        Err(errors) => Err(Error::new(
+            "validation failed".to_owned(),
+            StatusCode::UNPROCESSABLE_ENTITY,
+            ErrorCause::Validation(errors),
+        )),
+    }
+}
+
+// flattens nested errors into `{ "path.to[0].field": [errors] }`
+pub fn fields(errors: &ValidationErrors) -> Map<String, Value> {
+    fn collect(prefix: &str, errors: &ValidationErrors, fields: &mut Map<String, Value>) {
+        for (name, kind) in errors.errors() {
+            let path = if prefix.is_empty() {
+                (*name).to_owned()
+            } else {
+                format!("{prefix}.{name}")
+            };
+            match kind {
+                ValidationErrorsKind::Field(errors) => {
+                    let errors = errors
+                        .iter()
+                        .map(
+                            |v| json!({ "code": v.code, "message": v.message, "params": v.params }),
+                        )
+                        .collect();
+                    fields.insert(path, Value::Array(errors));
+                }
+                ValidationErrorsKind::Struct(errors) => collect(&path, errors, fields),
+                ValidationErrorsKind::List(list) => {
+                    for (index, errors) in list {
+                        collect(&format!("{path}[{index}]"), errors, fields);
+                    }
+                }
+            }
+        }
+    }
+
+    let mut fields = Map::new();
+    collect("", errors, &mut fields);
+    fields
+}
+
+impl<E: Display> Display for Valid<E> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl<E> Deref for Valid<E> {
+    type Target = E;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<E> AsRef<E> for Valid<E> {
+    fn as_ref(&self) -> &E {
+        &self.0
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::validate;
+    use crate::{http::StatusCode, ResponseError};
+    use serde::Deserialize;
+    use validator::{Validate, ValidationError};
+
+    #[derive(Debug, Deserialize, Validate)]
+    struct Address {
+        #[validate(length(min = 1))]
+        city: String,
+    }
+
+    #[derive(Debug, Deserialize, Validate)]
+    struct Person {
+        #[validate(range(min = 18))]
+        age: u32,
+        #[validate]
+        address: Address,
+        #[validate(custom = "not_admin")]
+        name: String,
+    }
+
+    fn not_admin(name: &str) -> Result<(), ValidationError> {
+        if name == "admin" {
+            return Err(ValidationError::new("reserved"));
+        }
+        Ok(())
+    }
+
+    // `Box` stands in for an extractor dereferencing to `Person`
+    #[allow(clippy::unnecessary_box_returns)]
+    fn person(body: &str) -> Box<Person> {
+        serde_json::from_str(body).unwrap()
+    }
+
+    #[test]
+    fn valid() {
+        let body = r#"{ "age": 20, "address": { "city": "Paris" }, "name": "john" }"#;
+
+        assert_eq!(validate(person(body)).unwrap().age, 20);
+    }
+
+    #[test]
+    fn invalid() {
+        let body = r#"{ "age": 12, "address": { "city": "" }, "name": "admin" }"#;
+        let err = validate(person(body)).unwrap_err();
+        let json = err.to_json();
+
+        assert_eq!(err.status_code(), StatusCode::UNPROCESSABLE_ENTITY);
+        assert_eq!(json["statusCode"], 422);
+        assert_eq!(json["fields"]["age"][0]["code"], "range");
+        assert_eq!(json["fields"]["address.city"][0]["code"], "length");
+        assert_eq!(json["fields"]["name"][0]["code"], "reserved");
+    }
+}
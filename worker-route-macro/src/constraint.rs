@@ -0,0 +1,240 @@
+use crate::route::Route;
+
+use proc_macro2::{Span, TokenStream};
+use quote::{format_ident, quote};
+
+// the types a param can be constrained to, eg: `:age<u8>`, any other constraint is a regex
+const TYPES: &[&str] = &[
+    "bool", "char", "i8", "i16", "i32", "i64", "i128", "isize", "u8", "u16", "u32", "u64", "u128",
+    "usize", "f32", "f64",
+];
+
+/// The constraint of a path parameter, eg: `u8` for `:age<u8>`.
+pub enum Constraint {
+    Type(&'static str),
+    Regex(String),
+}
+
+// reads a constraint up to its closing `>`, a `<` or `>` of the regex is either nested or escaped
+fn read(chars: &mut impl Iterator<Item = char>) -> Option<String> {
+    let mut depth = 0;
+    let mut constraint = String::new();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => {
+                constraint.push(c);
+                constraint.push(chars.next()?);
+                continue;
+            }
+            '<' => depth += 1,
+            '>' if depth == 0 => return Some(constraint),
+            '>' => depth -= 1,
+            _ => {}
+        }
+        constraint.push(c);
+    }
+
+    None
+}
+
+fn to_constraint(name: &str, constraint: String, span: Span) -> syn::Result<Constraint> {
+    if constraint.is_empty() {
+        return Err(syn::Error::new(
+            span,
+            format!("empty constraint for path parameter `{name}`"),
+        ));
+    }
+    if let Some(ty) = TYPES.iter().find(|v| **v == constraint) {
+        return Ok(Constraint::Type(ty));
+    }
+    match regex_lite::Regex::new(&format!("^(?:{constraint})$")) {
+        Ok(_) => Ok(Constraint::Regex(constraint)),
+        Err(e) => Err(syn::Error::new(
+            span,
+            format!("invalid constraint for path parameter `{name}`: {e}"),
+        )),
+    }
+}
+
+/// Strips the constraints of a path's params, returning the pattern registered to the router
+/// and the constraint of each param, eg: `/person/:name/:age<u8>` -> `/person/:name/:age`.
+pub fn parse(path: &str, span: Span) -> syn::Result<(String, Vec<(String, Constraint)>)> {
+    let mut pattern = String::with_capacity(path.len());
+    let mut constraints = Vec::new();
+    let mut chars = path.chars().peekable();
+    let mut name: Option<String> = None;
+    while let Some(c) = chars.next() {
+        match (&mut name, c) {
+            (Some(_), '/') => {
+                name = None;
+                pattern.push(c);
+            }
+            (Some(current), '<') => {
+                let Some(constraint) = read(&mut chars) else {
+                    return Err(syn::Error::new(
+                        span,
+                        format!("unclosed constraint for path parameter `{current}`"),
+                    ));
+                };
+                let constraint = to_constraint(current, constraint, span)?;
+                constraints.push((current.clone(), constraint));
+                if chars.peek().is_some_and(|v| *v != '/') {
+                    return Err(syn::Error::new(
+                        span,
+                        format!("a constraint must end the segment of path parameter `{current}`"),
+                    ));
+                }
+                name = None;
+            }
+            (Some(current), _) => {
+                current.push(c);
+                pattern.push(c);
+            }
+            (None, ':' | '*') if pattern.is_empty() || pattern.ends_with('/') => {
+                name = Some(String::new());
+                pattern.push(c);
+            }
+            (None, _) => pattern.push(c),
+        }
+    }
+
+    Ok((pattern, constraints))
+}
+
+/// Returns a `404 Not Found` from the handler if a param doesn't satisfy its constraint,
+/// before anything is extracted.
+pub fn check(route: &Route) -> Option<TokenStream> {
+    if route.constraints.is_empty() {
+        return None;
+    }
+
+    let constraints = route.constraints.iter().map(|(name, constraint)| {
+        let constraint = match constraint {
+            Constraint::Type(ty) => {
+                let mut variant = String::from(*ty);
+                variant[..1].make_ascii_uppercase();
+                let variant = format_ident!("{variant}");
+                quote!(::worker_route::__private::Constraint::#variant)
+            }
+            Constraint::Regex(regex) => {
+                quote!(::worker_route::__private::Constraint::Regex(#regex))
+            }
+        };
+        quote!((#name, #constraint))
+    });
+    let cors = route
+        .cors()
+        .map_or_else(|| quote!(None), |cors| quote!(Some(&#cors)));
+
+    Some(quote! {
+        if let Err(err__) = ::worker_route::__private::constrain(
+            &req__.path(),
+            &ctx__,
+            &[#(#constraints),*],
+        ) {
+            return ::worker_route::__private::responder::<::worker::Response, _>(
+                #cors,
+                ::worker_route::http::HttpRequest::from(&req__),
+                Err(err__),
+            );
+        }
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::{parse, to_constraint, Constraint};
+    use proc_macro2::Span;
+
+    // the pattern, and each param with its type or regex
+    fn parsed(path: &str) -> (String, Vec<(String, String)>) {
+        let (pattern, constraints) = parse(path, Span::call_site()).unwrap();
+        let constraints = constraints
+            .into_iter()
+            .map(|(name, constraint)| match constraint {
+                Constraint::Type(ty) => (name, ty.to_owned()),
+                Constraint::Regex(regex) => (name, format!("/{regex}/")),
+            })
+            .collect();
+        (pattern, constraints)
+    }
+
+    fn error(path: &str) -> String {
+        parse(path, Span::call_site())
+            .err()
+            .map(|v| v.to_string())
+            .unwrap_or_default()
+    }
+
+    #[test]
+    fn parse_path() {
+        assert_eq!(
+            parsed("/person/:name"),
+            ("/person/:name".to_owned(), vec![])
+        );
+        assert_eq!(
+            parsed("/person/:name/:age<u8>"),
+            (
+                "/person/:name/:age".to_owned(),
+                vec![("age".to_owned(), "u8".to_owned())]
+            )
+        );
+        assert_eq!(
+            parsed(r"/:id<\d{1,3}>/*rest<[a-z<>]+>"),
+            (
+                "/:id/*rest".to_owned(),
+                vec![
+                    ("id".to_owned(), r"/\d{1,3}/".to_owned()),
+                    ("rest".to_owned(), "/[a-z<>]+/".to_owned())
+                ]
+            )
+        );
+        // an escaped `>` doesn't close the constraint
+        assert_eq!(parsed(r"/:op<\>|\<>").1[0].1, r"/\>|\</");
+        // only a segment's leading `:` or `*` starts a param
+        assert_eq!(parsed("/a:b<c>"), ("/a:b<c>".to_owned(), vec![]));
+    }
+
+    #[test]
+    fn parse_errors() {
+        assert_eq!(
+            error("/:age<u8"),
+            "unclosed constraint for path parameter `age`"
+        );
+        assert_eq!(
+            error("/:age<u8>s"),
+            "a constraint must end the segment of path parameter `age`"
+        );
+        assert_eq!(
+            error("/:age<>"),
+            "empty constraint for path parameter `age`"
+        );
+        // the first unescaped `>` closes the constraint, `[0-9` isn't a regex
+        assert!(error("/:id<[0-9>").starts_with("invalid constraint for path parameter `id`"));
+        assert!(error("/:id<(>").starts_with("invalid constraint for path parameter `id`"));
+    }
+
+    #[test]
+    fn constraints() {
+        let span = Span::call_site();
+        assert!(matches!(
+            to_constraint("age", "u8".to_owned(), span),
+            Ok(Constraint::Type("u8"))
+        ));
+        assert!(matches!(
+            to_constraint("ok", "bool".to_owned(), span),
+            Ok(Constraint::Type("bool"))
+        ));
+        // not one of the types, a regex matching `String`
+        assert!(matches!(
+            to_constraint("name", "String".to_owned(), span),
+            Ok(Constraint::Regex(v)) if v == "String"
+        ));
+        assert!(matches!(
+            to_constraint("slug", "[a-z-]+".to_owned(), span),
+            Ok(Constraint::Regex(v)) if v == "[a-z-]+"
+        ));
+        assert!(to_constraint("id", String::new(), span).is_err());
+        assert!(to_constraint("id", "[".to_owned(), span).is_err());
+    }
+}
@@ -25,7 +25,7 @@ impl ErrorSpan {
             Self::DuplicateMethod =>  "duplicate method found".into(),
             Self::InvalidFormat => format!(r#"expected string literal, found {}"#, ident.unwrap()),
             Self::InvalidMethod => format!(r#"expected "get, head, delete, put, patch, post", found {}"#, ident.unwrap()),
-            Self::InvalidMethods => format!(r#"expected "all, get, head, delete, put, patch, post", found {}"#, ident.unwrap()),
+            Self::InvalidMethods => format!(r#"expected "all, get, head, delete, put, patch, post, options, connect, trace" or an uppercase custom method, found {}"#, ident.unwrap()),
             Self::Method =>  format!("method's attribute is not supported when using {} attribute, use route instead or remove method", ident.unwrap()),
     }
     }
@@ -1,10 +1,11 @@
 use crate::error::Error;
+use crate::method::{Method, REGISTERED_METHODS};
 use crate::route::{gen_router, Route};
-use crate::transform::FnWrapper;
+use crate::transform::{type_name, FnWrapper};
 
-use proc_macro2::TokenStream;
-use quote::quote;
-use syn::{parse2, parse_quote};
+use proc_macro2::{Ident, TokenStream};
+use quote::{quote, ToTokens};
+use syn::{parse2, parse_quote, LitStr, Type};
 
 #[allow(clippy::module_name_repetitions)]
 pub fn expand_fn(items: TokenStream) -> Result<syn::ItemFn, Error> {
@@ -27,6 +28,9 @@ pub fn expand(items: TokenStream, route: &Route) -> TokenStream {
         wrapper,
         route_context,
         data,
+        extractors,
+        generics,
+        where_clause,
     } = FnWrapper::new(&fn_, path, &cors);
 
     let attr = if asyncness.is_some() {
@@ -40,42 +44,175 @@ pub fn expand(items: TokenStream, route: &Route) -> TokenStream {
     };
     let attrs = &fn_.attrs;
 
-    let routes = route.methods.iter().enumerate().map(|(i, v)| {
-        gen_router(
-            asyncness,
-            &cors,
-            path,
-            &v.value(),
-            route.wrap,
-            Some((i, route.methods.len())),
-        )
-    });
+    let routes = routes(route, cors.as_ref(), &data, path);
 
+    let info = route_info(route, name, path, &extractors);
+    let checks = crate::params::check(route, &extractors);
+    let constraints = crate::constraint::check(route);
+    let custom_method = custom_method(route);
+    let url = crate::url::url(route, path);
+    // the rate limit runs before the middlewares of `wrap`
+    let middleware = crate::rate_limit::middleware(route, name)
+        .into_iter()
+        .chain(route.middleware.iter().map(ToTokens::to_token_stream));
+    #[cfg(feature = "openapi")]
+    let operations = crate::openapi::operations(route, &extractors, &data, ret);
+    #[cfg(not(feature = "openapi"))]
+    let operations: Option<TokenStream> = None;
     let expanded = parse_quote! {
         #(#attrs)*
         #[allow(non_camel_case_types, missing_docs)]
         pub struct #name;
-        impl ::worker_route::__private::RouteFactory<#data> for #name {
-            fn register(
+        #checks
+        impl #name {
+            /// The metadata of the route.
+            #[allow(dead_code)]
+            pub const ROUTE: ::worker_route::RouteInfo = #info;
+
+            #url
+        }
+        impl #generics ::worker_route::__private::RouteFactory<#data> for #name #where_clause {
+            fn register<R__: ::worker_route::__private::AddHandler<#data>>(
                 self,
-                router__: ::worker::Router<'_, #data>
-            ) -> ::worker::Router<'_, #data> {
-                use ::worker_route::__private::AddHandler;
-                pub #asyncness fn __handler(
+                router__: R__
+            ) -> R__ {
+                // the middlewares are awaited, the handler is async even if the given fn is not
+                #[allow(unused_variables)]
+                pub async fn __handler #generics (
                     req__: ::worker::Request,
                     ctx__: #route_context
-                ) -> ::worker::Result<::worker::Response> {
+                ) -> ::worker::Result<::worker::Response> #where_clause {
                     #attr
                     #[allow(missing_docs)]
                     #vis #asyncness fn #name(#args) #ret {
                         #(#stmts)*
                     }
-                    #wrapper
+                    #custom_method
+                    #constraints
+                    ::worker_route::__private::wrap(
+                        req__,
+                        ctx__,
+                        &[#(::worker_route::__private::middleware::<#data, _>(&#middleware)),*],
+                        |req__, ctx__| async move { #wrapper },
+                    )
+                    .await
                 }
                 #(#routes)*
             }
+
+            fn describe(&self, routes: &mut ::std::vec::Vec<::worker_route::RouteInfo>) {
+                routes.push(Self::ROUTE);
+            }
+
+            #operations
         }
     };
 
     expanded
 }
+
+// registers the handler, for each of its methods
+fn routes(
+    route: &Route,
+    cors: Option<&TokenStream>,
+    data: &TokenStream,
+    path: &str,
+) -> Vec<TokenStream> {
+    if route.is_fallback {
+        return vec![quote!(router__.fallback_async(__handler))];
+    }
+
+    let (registered, custom): (Vec<_>, Vec<_>) = route
+        .methods
+        .iter()
+        .map(LitStr::value)
+        .partition(|v| REGISTERED_METHODS.contains(&v.to_uppercase().as_str()));
+    let mut routes: Vec<TokenStream> = registered
+        .iter()
+        .enumerate()
+        .map(|(i, v)| gen_router(route, cors, data, v, Some((i, registered.len()))))
+        .collect();
+    // the methods the router can't register by themselves are registered together, after the others
+    if !custom.is_empty() {
+        let methods = custom.iter().map(|v| v.to_uppercase());
+        let call = if routes.is_empty() {
+            quote!(router__.register_any_async)
+        } else {
+            quote!(.register_any_async)
+        };
+        routes.push(quote!(#call(#path, &[#(#methods),*], __handler)));
+    }
+    routes
+}
+
+// the const metadata exposed as `ROUTE` on the handler
+fn route_info(route: &Route, name: &Ident, path: &str, extractors: &[Type]) -> TokenStream {
+    let name = name.to_string();
+    let extractors = extractors.iter().map(type_name);
+    let (methods, custom_methods) = if route.is_fallback {
+        (Vec::new(), Vec::new())
+    } else {
+        let (methods, custom_methods): (Vec<_>, Vec<_>) = route
+            .methods
+            .iter()
+            .map(|v| v.value().to_uppercase())
+            .partition(|v| Method::new(v).is_some());
+        let methods = methods
+            .iter()
+            .filter_map(|v| Method::new(v))
+            .map(|v| TokenStream::from(v.to_token()))
+            .collect();
+        (methods, custom_methods)
+    };
+    let cors = match (&route.cors, &route.lazy_cors) {
+        (Some(cors), _) => {
+            let cors = cors.to_string();
+            quote!(::worker_route::CorsMode::Cors(#cors))
+        }
+        (_, Some(cors)) => {
+            let cors = cors.to_string();
+            quote!(::worker_route::CorsMode::Lazy(#cors))
+        }
+        _ => quote!(::worker_route::CorsMode::None),
+    };
+    let preflight = route.preflight;
+    let middleware = route.middleware.iter().map(type_name);
+    let rate_limit = route.rate_limit.as_ref().map_or_else(
+        || quote!(::core::option::Option::None),
+        |quota| quote!(::core::option::Option::Some(#quota)),
+    );
+
+    quote! {
+        ::worker_route::RouteInfo::__new(
+            #name,
+            #path,
+            &[#(#methods),*],
+            &[#(#custom_methods),*],
+            #cors,
+            #preflight,
+            &[#(#middleware),*],
+            #rate_limit,
+            &[#(#extractors),*],
+        )
+    }
+}
+
+// the router runs the `GET` handler of a path for a custom method, it's handed over to the router's catch-all,
+// which runs the handler of the method as sent
+fn custom_method(route: &Route) -> Option<TokenStream> {
+    if route.is_fallback {
+        return None;
+    }
+
+    // the catch-all runs the handler for its own custom methods
+    let methods = route
+        .methods
+        .iter()
+        .map(|v| v.value().to_uppercase())
+        .filter(|v| !REGISTERED_METHODS.contains(&v.as_str()));
+    Some(quote! {
+        if ::worker_route::__private::is_custom(&req__, &[#(#methods),*]) {
+            return ::worker_route::__private::dispatch(req__, ctx__).await;
+        }
+    })
+}
@@ -4,15 +4,25 @@ extern crate proc_macro;
 extern crate quote;
 extern crate syn;
 
+mod constraint;
 mod error;
 mod expand;
 mod method;
+mod multipart;
+#[cfg(feature = "openapi")]
+mod openapi;
+mod params;
+mod rate_limit;
 mod route;
+mod scope;
+mod serde_attrs;
 mod transform;
+mod url;
 mod wrapper;
 use method::Method;
 use paste::paste;
 use proc_macro::TokenStream;
+use syn::{parse_macro_input, DeriveInput};
 
 macro_rules! route_method {
     ($variant:ident, $method:ident) => {
@@ -30,7 +40,10 @@ macro_rules! route_method {
             /// - `"path"`: Worker's path.
             /// - `Option<cors>`: Wrap a struct that implements `worker_route::MwService`.
             /// - `Option<lazy_cors>`: Wrap a lazy initialized Cors.
-            /// - `Option<wrap>`: Register an options handler with the provided cors. Defaults to `None`.
+            /// - `Option<preflight>`: Register an options handler with the provided cors, `wrap` without a list is the same. Defaults to `None`.
+            /// - `Option<wrap>`: An array of `worker_route::Middleware` run around the handler, eg: `wrap = [Auth, Timing]`.
+            /// - `Option<rate_limit>`: Limit the requests of each IP with a `worker_route::RateLimit`, eg: `rate_limit = "100/min"`.
+            /// - `Option<unchecked>`: Skip the compile-time check of the path's parameters, see [`route`](macro@route).
             ///
             /// # Examples
             /// ```
@@ -54,21 +67,62 @@ macro_rules! route_method {
 ///
 /// # Usage
 /// ```text
-/// #[route("path", method = "method", cors = "cors", lazy_cors = "lazy_cors", wrap)]
+/// #[route("path", method = "method", cors = "cors", lazy_cors = "lazy_cors", preflight, wrap = [..])]
 /// ```
 ///
 /// # Attributes
 /// - `"path"`: Worker's path.
-/// - `method`: An array of methods or a method in string literal.
+/// - `method`: An array of methods or a method in string literal, a custom method is uppercase, eg: `"PURGE"`.
 /// - `Option<cors>`: Wrap a struct that implements `worker_route::MwService`.
 /// - `Option<lazy_cors>`: Wrap a lazy initialized Cors.
-/// - `Option<wrap>`: Register an options handler with the provided cors. Defaults to `None`.
+/// - `Option<preflight>`: Register an options handler with the provided cors, `wrap` without a list is the same. Defaults to `None`.
+/// - `Option<wrap>`: An array of `worker_route::Middleware` run around the handler, eg: `wrap = [Auth, Timing]`.
+/// - `Option<rate_limit>`: Limit the requests of each IP with a `worker_route::RateLimit`, eg: `rate_limit = "100/min"`.
+/// - `Option<unchecked>`: Skip the compile-time check of the path's parameters.
+///
+/// # Path parameters
+/// Every `:param` and `*wildcard` of the path must be a field of the struct extracted with
+/// `Path<T>` if it derives `worker_route::PathParams`, a misspelled or missing parameter fails
+/// to compile at the path literal. The fields are matched by the names serde deserializes.
+/// `Query<T>` isn't checked, it only reads the query string.
+///
+/// # Constraints
+/// A parameter can be constrained to a primitive type or to a regex matching its whole value,
+/// eg: `/person/:name/:age<u8>` or `/commit/:id<[0-9a-f]{8}>`, an invalid regex fails to compile.
+/// The constraints are stripped from the registered pattern, a request to `/person/bob/abc`
+/// is matched by the route and answered with `404 Not Found` before anything is extracted,
+/// another route of the same pattern can't be tried in its place.
+///
+/// # Custom methods
+/// The router registers `GET`, `HEAD`, `POST`, `PUT`, `PATCH`, `DELETE` and `OPTIONS` by themselves.
+/// A handler with `CONNECT`, `TRACE` or a custom method, eg: `PURGE` or `PROPFIND`,
+/// is run by the catch-all of the router, on the method of the request, see `worker_route::Configure`.
+/// The other handlers of its path are kept, the router runs its `GET` handler for a custom method,
+/// which hands the request over to the catch-all. `CONNECT` only reaches its handler
+/// if no handler of another method matches the path. `preflight` can't be used with it.
+///
+/// # Middleware
+/// The middlewares of `wrap` are expressions of a type implementing `worker_route::Middleware`,
+/// evaluated for every request, eg: `wrap = [Auth, Timeout::secs(5)]`.
+/// They run in the declared order, after the global middlewares of `worker_route::App::wrap`,
+/// and the handler is registered as async even if the function is not.
+///
+/// `rate_limit = "<limit>/<period>"` runs a `worker_route::RateLimit` of each IP before them,
+/// its state kept in the isolate's memory and apart from the other handlers',
+/// eg: `"100/min"` or `"10/30s"`. Other keys or stores are set with `wrap = [RateLimit::new(..)]`.
+///
+/// # URLs
+/// The handler gets a `url` function taking a value for each parameter of the path, in order,
+/// eg: `person::url(name, age)` for `/person/:name/:age<u8>`, where `age` is a `u8`.
+/// The values are percent-encoded and it returns a `worker_route::RouteUrl`,
+/// whose `query` appends a serialized struct.
+/// A parameter that isn't a Rust identifier is named as one, eg: `my_id` for `:my-id`.
 ///
 /// # Examples
 /// ```
 /// use worker::{Result, Request, RouteContext, Response};
 /// use worker_route::route;
-/// 
+///
 /// #[route("/path", method = "get", method = "post")]
 /// async fn foo(req: Request, ctx: RouteContext<()>) -> Result<Response> {
 ///     Response::empty()
@@ -79,6 +133,42 @@ pub fn route(attrs: TokenStream, items: TokenStream) -> TokenStream {
     route::with_method::<{ Method::Default as _ }>(attrs, items)
 }
 
+/// A macro that creates a fallback handler, answering every request which no other handler matches.
+///
+/// The fallback is run by the catch-all of the router, see `worker_route::Configure`,
+/// it can return any `Result<impl Responder, impl ResponseError>` like other handlers.
+/// `Path<String>` extracts the unmatched path.
+///
+/// # Usage
+/// ```text
+/// #[fallback]
+/// ```
+///
+/// # Attributes
+/// - `Option<cors>`: Wrap a struct that implements `worker_route::Wrap`.
+/// - `Option<lazy_cors>`: Wrap a lazy initialized Cors.
+/// - `Option<wrap>`: An array of `worker_route::Middleware` run around the handler.
+/// - `Option<rate_limit>`: Limit the requests of each IP with a `worker_route::RateLimit`, eg: `rate_limit = "100/min"`.
+///
+/// # Examples
+/// ```
+/// use worker::{Result, Router};
+/// use worker_route::{fallback, Configure, Error, HttpResponse, Path};
+///
+/// #[fallback]
+/// async fn not_found(path: Path<String>) -> std::result::Result<HttpResponse, Error> {
+///     Err(Error::not_found(&path))
+/// }
+///
+/// fn init_routes(router: Router<'static, ()>) -> Router<'static, ()> {
+///     router.configure(not_found)
+/// }
+/// ```
+#[proc_macro_attribute]
+pub fn fallback(attrs: TokenStream, items: TokenStream) -> TokenStream {
+    route::fallback(attrs, items)
+}
+
 route_method!(Delete, delete);
 route_method!(Get, get);
 route_method!(Head, head);
@@ -86,3 +176,245 @@ route_method!(Options, options);
 route_method!(Patch, patch);
 route_method!(Post, post);
 route_method!(Put, put);
+
+/// A macro that creates a `CONNECT` route handler.
+///
+/// `CONNECT` can't be registered by itself, the handler is run by the catch-all of the router,
+/// see [`route`](macro@route).
+///
+/// # Usage
+/// ```text
+/// #[connect("/path")]
+/// ```
+#[proc_macro_attribute]
+pub fn connect(attrs: TokenStream, items: TokenStream) -> TokenStream {
+    route::with_method::<{ Method::Connect as _ }>(attrs, items)
+}
+
+/// A macro that creates a `TRACE` route handler.
+///
+/// `TRACE` can't be registered by itself, the handler is run by the catch-all of the router,
+/// see [`route`](macro@route).
+///
+/// # Usage
+/// ```text
+/// #[trace("/path")]
+/// ```
+#[proc_macro_attribute]
+pub fn trace(attrs: TokenStream, items: TokenStream) -> TokenStream {
+    route::with_method::<{ Method::Trace as _ }>(attrs, items)
+}
+
+/// A macro that prefixes every handler of an inline module with a shared path,
+/// and applies a shared cors to the handlers without their own.
+///
+/// A `service` function registering every handler of the module, nested scopes included,
+/// is added to the module to be used with [`Service`](https://docs.rs/worker-route/latest/worker_route/trait.Service.html)
+/// or `worker_route::routes!`.
+/// Two handlers of the same method and pattern in a module fail to compile.
+///
+/// # Usage
+/// ```text
+/// #[scope("/prefix")]
+/// ```
+///
+/// # Attributes
+/// - `"prefix"`: The path prepended to every handler's path.
+/// - `Option<cors>`: Wrap a struct that implements `worker_route::Wrap`.
+/// - `Option<lazy_cors>`: Wrap a lazy initialized Cors.
+/// - `Option<preflight>`: Register an options handler with the provided cors, `wrap` without a list is the same. Defaults to `None`.
+/// - `Option<wrap>`: An array of `worker_route::Middleware` run before the middlewares of each handler.
+///
+/// The cors and the middlewares must be in scope of the module, eg: with `use super::*`.
+/// Nested scopes inherit the prefix and the cors of their parent, and run their middlewares after the parent's.
+///
+/// # Examples
+/// ```
+/// use worker::{Cors, Request, Result, Router};
+/// use worker_route::{scope, Service, Wrap};
+///
+/// pub struct ApiCors;
+///
+/// impl Wrap for ApiCors {
+///     type Output = Cors;
+///
+///     fn wrap(_: &Request) -> Self::Output {
+///         Cors::default().with_origins(["*"])
+///     }
+/// }
+///
+/// #[scope("/api/v1", cors = ApiCors, preflight)]
+/// mod api {
+///     use super::*;
+///     use worker_route::{get, post};
+///
+///     // GET /api/v1/users
+///     #[get("/users")]
+///     async fn users() -> Result<String> {
+///         Ok("users".to_owned())
+///     }
+///
+///     #[scope("/admin")]
+///     mod admin {
+///         use super::*;
+///
+///         // POST /api/v1/admin/reset
+///         #[post("/reset")]
+///         async fn reset() -> Result<String> {
+///             Ok("reset".to_owned())
+///         }
+///     }
+/// }
+///
+/// fn init_routes(router: Router<'static, ()>) -> Router<'static, ()> {
+///     router.service(api::service)
+/// }
+/// ```
+#[proc_macro_attribute]
+pub fn scope(attrs: TokenStream, items: TokenStream) -> TokenStream {
+    let scope = parse_macro_input!(attrs as scope::Scope);
+    let item = parse_macro_input!(items as syn::ItemMod);
+    scope::expand(&scope, item)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// A macro that registers every handler of an inline module and of its nested inline modules,
+/// without prefixing their paths.
+///
+/// Like [`scope`](macro@scope), a `service` function is added to the module,
+/// and it can be listed in `worker_route::routes!`.
+/// Nested scopes keep their prefix, a `#[cfg]` module isn't included.
+/// Two handlers of the same method and pattern in a module fail to compile.
+///
+/// # Usage
+/// ```text
+/// #[handlers]
+/// ```
+///
+/// # Examples
+/// ```
+/// use worker::Router;
+/// use worker_route::{handlers, routes, Service};
+///
+/// #[handlers]
+/// mod app {
+///     use worker::Result;
+///     use worker_route::get;
+///
+///     #[get("/")]
+///     async fn index() -> Result<String> {
+///         Ok("index".to_owned())
+///     }
+///
+///     mod users {
+///         use super::*;
+///
+///         #[get("/users")]
+///         async fn users() -> Result<String> {
+///             Ok("users".to_owned())
+///         }
+///     }
+/// }
+///
+/// fn init_routes(router: Router<'static, ()>) -> Router<'static, ()> {
+///     // GET / and GET /users
+///     router.service(routes![app])
+/// }
+/// ```
+#[proc_macro_attribute]
+pub fn handlers(attrs: TokenStream, items: TokenStream) -> TokenStream {
+    parse_macro_input!(attrs as syn::parse::Nothing);
+    let item = parse_macro_input!(items as syn::ItemMod);
+    scope::expand(&scope::Scope::handlers(), item)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// Implements `worker_route::openapi::Schema` for a struct with named fields,
+/// a newtype struct or an enum of unit variants.
+///
+/// Doc comments are used as descriptions,
+/// `#[serde(rename_all = "...")]`, `#[serde(rename = "...")]`, `#[serde(default)]`
+/// and `#[serde(skip)]` are followed.
+///
+/// # Examples
+/// ```
+/// use serde::Deserialize;
+/// use worker_route::openapi::Schema;
+///
+/// /// A search of the users.
+/// #[derive(Deserialize, Schema)]
+/// #[serde(rename_all = "camelCase")]
+/// struct Search {
+///     /// The searched name.
+///     q: String,
+///     per_page: Option<u32>,
+/// }
+/// ```
+#[cfg(feature = "openapi")]
+#[proc_macro_derive(Schema)]
+pub fn schema(items: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(items as DeriveInput);
+    openapi::derive(&input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// Implements `worker_route::PathParams` for a struct with named fields,
+/// the route macros then check at compile time that every parameter of the path is one of its fields.
+///
+/// The fields are named as serde deserializes them, `#[serde(rename_all = "...")]`,
+/// `#[serde(rename = "...")]`, `#[serde(alias = "...")]` and `#[serde(skip)]` are followed.
+///
+/// # Examples
+/// ```
+/// use serde::Deserialize;
+/// use worker::{Response, Result};
+/// use worker_route::{get, Path, PathParams};
+///
+/// #[derive(Deserialize, PathParams)]
+/// #[serde(rename_all = "camelCase")]
+/// struct Post {
+///     user_id: u64,
+///     post_id: u64,
+/// }
+///
+/// // `/users/:userId/posts/:id` fails to compile
+/// #[get("/users/:userId/posts/:postId")]
+/// async fn post(post: Path<Post>) -> Result<Response> {
+///     Response::empty()
+/// }
+/// ```
+#[proc_macro_derive(PathParams)]
+pub fn path_params(items: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(items as DeriveInput);
+    params::derive(&input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// A derive macro that implements `worker_route::multipart::MultipartCollect`,
+/// used to extract the struct with `worker_route::MultipartForm`.
+///
+/// # Attributes
+/// - `#[multipart(max_body = usize, max_parts = usize)]` on the struct: limits applied while reading the body.
+/// - `#[multipart(rename = "name")]` on a field: the name of the part, defaults to the field's name.
+///
+/// # Examples
+/// ```
+/// use worker_route::{multipart::File, MultipartForm};
+///
+/// #[derive(MultipartForm)]
+/// struct Upload {
+///     title: String,
+///     file: File,
+/// }
+/// ```
+#[proc_macro_derive(MultipartForm, attributes(multipart))]
+pub fn multipart_form(items: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(items as DeriveInput);
+    multipart::expand(&input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
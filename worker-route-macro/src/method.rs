@@ -14,12 +14,18 @@ pub enum Method {
     Patch = 5,
     Post = 6,
     Put = 7,
+    Connect = 8,
+    Trace = 9,
 }
 
 pub const SUPPORTED_METHODS: &[&str] = &[
-    "ALL", "GET", "DELETE", "HEAD", "PATCH", "POST", "PUT", "OPTIONS",
+    "ALL", "GET", "DELETE", "HEAD", "PATCH", "POST", "PUT", "OPTIONS", "CONNECT", "TRACE",
 ];
 
+// the methods the router registers by themselves, any other is run by the router's catch-all
+pub const REGISTERED_METHODS: &[&str] =
+    &["GET", "DELETE", "HEAD", "PATCH", "POST", "PUT", "OPTIONS"];
+
 impl From<Method> for String {
     fn from(value: Method) -> Self {
         match value {
@@ -31,6 +37,8 @@ impl From<Method> for String {
             Method::Patch => Self::from("PATCH"),
             Method::Post => Self::from("POST"),
             Method::Put => Self::from("PUT"),
+            Method::Connect => Self::from("CONNECT"),
+            Method::Trace => Self::from("TRACE"),
         }
     }
 }
@@ -45,6 +53,8 @@ impl Method {
             5 => Some(Self::Patch),
             6 => Some(Self::Post),
             7 => Some(Self::Put),
+            8 => Some(Self::Connect),
+            9 => Some(Self::Trace),
             _ => None,
         }
     }
@@ -62,20 +72,22 @@ impl Method {
         .into()
     }
 
-    pub fn new(method: &str) -> Self {
+    /// Returns `None` for a custom method, eg: `PURGE`.
+    pub fn new(method: &str) -> Option<Self> {
         match method {
-            "DELETE" => Self::Delete,
-            "GET" => Self::Get,
-            "HEAD" => Self::Head,
-            "OPTIONS" => Self::Options,
-            "PATCH" => Self::Patch,
-            "POST" => Self::Post,
-            "PUT" => Self::Put,
-            _ => todo!(),
+            "DELETE" => Some(Self::Delete),
+            "GET" => Some(Self::Get),
+            "HEAD" => Some(Self::Head),
+            "OPTIONS" => Some(Self::Options),
+            "PATCH" => Some(Self::Patch),
+            "POST" => Some(Self::Post),
+            "PUT" => Some(Self::Put),
+            "CONNECT" => Some(Self::Connect),
+            "TRACE" => Some(Self::Trace),
+            _ => None,
         }
     }
 
-    #[allow(clippy::match_wildcard_for_single_variants)]
     pub fn to_token(self) -> TokenStream {
         match self {
             Self::Delete => quote!(::worker::Method::Delete).into(),
@@ -85,11 +97,12 @@ impl Method {
             Self::Patch => quote!(::worker::Method::Patch).into(),
             Self::Post => quote!(::worker::Method::Post).into(),
             Self::Put => quote!(::worker::Method::Put).into(),
-            _ => todo!(),
+            Self::Connect => quote!(::worker::Method::Connect).into(),
+            Self::Trace => quote!(::worker::Method::Trace).into(),
+            Self::Default => quote!(compile_error!("a route requires a method")).into(),
         }
     }
 
-    #[allow(clippy::match_wildcard_for_single_variants)]
     pub fn to_compile_error(self, error: &syn::Error) -> TokenStream {
         match self {
             Self::Delete => quote_spanned! {
@@ -127,11 +140,36 @@ impl Method {
                 compile_error!(r#"unexpected token, valid token is #[put("/path")]"#);
             }
             .into(),
-            _ => todo!(),
+            Self::Connect => quote_spanned! {
+                error.span() =>
+                compile_error!(r#"unexpected token, valid token is #[connect("/path")]"#);
+            }
+            .into(),
+            Self::Trace => quote_spanned! {
+                error.span() =>
+                compile_error!(r#"unexpected token, valid token is #[trace("/path")]"#);
+            }
+            .into(),
+            Self::Default => quote_spanned! {
+                error.span() =>
+                compile_error!(r#"unexpected token, valid token is #[route("/path", method = "get")]"#);
+            }
+            .into(),
         }
     }
 }
 
+/// Returns `true` if `method` is a valid custom method, an uppercase token, eg: `PURGE` or `VERSION-CONTROL`.
+///
+/// A lowercase method which isn't supported is rejected, it's most likely misspelled.
+pub fn is_custom(method: &str) -> bool {
+    !method.is_empty()
+        && method.chars().any(|c| c.is_ascii_uppercase())
+        && method
+            .chars()
+            .all(|c| c.is_ascii_uppercase() || c.is_ascii_digit() || "!#$%&'*+-.^_`|~".contains(c))
+}
+
 impl Debug for Method {
     fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         let v = match self {
@@ -142,9 +180,42 @@ impl Debug for Method {
             Self::Post => "POST",
             Self::Put => "PUT",
             Self::Options => "OPTIONS",
+            Self::Connect => "CONNECT",
+            Self::Trace => "TRACE",
             Self::Default => "NONE",
         };
 
         f.write_str(v)
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::{is_custom, Method};
+
+    #[test]
+    fn custom() {
+        assert!(is_custom("PURGE"));
+        assert!(is_custom("VERSION-CONTROL"));
+        assert!(is_custom("M-SEARCH"));
+        assert!(is_custom("LINK2"));
+
+        // a misspelled method rather than a custom one
+        assert!(!is_custom("purge"));
+        assert!(!is_custom("Purge"));
+        assert!(!is_custom("123"));
+        assert!(!is_custom("-"));
+        assert!(!is_custom(""));
+        // not a token
+        assert!(!is_custom("GET POST"));
+        assert!(!is_custom("GET/1"));
+    }
+
+    #[test]
+    fn methods() {
+        assert_eq!(Method::new("CONNECT"), Some(Method::Connect));
+        assert_eq!(Method::new("TRACE"), Some(Method::Trace));
+        assert_eq!(Method::new("PURGE"), None);
+        assert_eq!(Method::new("get"), None);
+    }
+}
@@ -0,0 +1,130 @@
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::{Data, DeriveInput, Fields, LitInt, LitStr};
+
+struct Field {
+    ident: syn::Ident,
+    name: String,
+    ty: syn::Type,
+}
+
+// #[multipart(max_body = 1024, max_parts = 10)]
+fn limits(input: &DeriveInput) -> syn::Result<Option<TokenStream>> {
+    let mut limits = Vec::new();
+    for attr in input
+        .attrs
+        .iter()
+        .filter(|v| v.path().is_ident("multipart"))
+    {
+        attr.parse_nested_meta(|meta| {
+            let setter = if meta.path.is_ident("max_body") {
+                quote!(body)
+            } else if meta.path.is_ident("max_parts") {
+                quote!(parts)
+            } else {
+                return Err(meta.error("expected `max_body` or `max_parts`"));
+            };
+            let value: LitInt = meta.value()?.parse()?;
+            limits.push(quote!(.#setter(#value)));
+            Ok(())
+        })?;
+    }
+
+    Ok((!limits.is_empty()).then(|| {
+        quote! {
+            fn limits() -> ::worker_route::multipart::MultipartLimits {
+                ::worker_route::multipart::MultipartLimits::new()#(#limits)*
+            }
+        }
+    }))
+}
+
+// #[multipart(rename = "name")]
+fn field_name(field: &syn::Field) -> syn::Result<Option<String>> {
+    let mut name = None;
+    for attr in field
+        .attrs
+        .iter()
+        .filter(|v| v.path().is_ident("multipart"))
+    {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("rename") {
+                let value: LitStr = meta.value()?.parse()?;
+                name = Some(value.value());
+                Ok(())
+            } else {
+                Err(meta.error("expected `rename`"))
+            }
+        })?;
+    }
+
+    Ok(name)
+}
+
+fn fields(input: &DeriveInput) -> syn::Result<Vec<Field>> {
+    let Data::Struct(data) = &input.data else {
+        return Err(syn::Error::new_spanned(
+            input,
+            "MultipartForm can only be derived for structs",
+        ));
+    };
+    let Fields::Named(named) = &data.fields else {
+        return Err(syn::Error::new_spanned(
+            &data.fields,
+            "MultipartForm can only be derived for structs with named fields",
+        ));
+    };
+
+    named
+        .named
+        .iter()
+        .map(|field| {
+            let ident = field.ident.clone().unwrap();
+            Ok(Field {
+                name: field_name(field)?.unwrap_or_else(|| ident.to_string()),
+                ident,
+                ty: field.ty.clone(),
+            })
+        })
+        .collect()
+}
+
+pub fn expand(input: &DeriveInput) -> syn::Result<TokenStream> {
+    let limits = limits(input)?;
+    let fields = fields(input)?;
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let idents = fields.iter().map(|v| &v.ident).collect::<Vec<_>>();
+    let parts = fields
+        .iter()
+        .map(|v| format_ident!("{}_parts__", v.ident))
+        .collect::<Vec<_>>();
+    let names = fields.iter().map(|v| &v.name).collect::<Vec<_>>();
+    let tys = fields.iter().map(|v| &v.ty).collect::<Vec<_>>();
+
+    Ok(quote! {
+        impl #impl_generics ::worker_route::multipart::MultipartCollect for #name #ty_generics #where_clause {
+            #limits
+
+            #[allow(clippy::future_not_send)]
+            async fn collect(
+                mut multipart__: ::worker_route::multipart::Multipart,
+            ) -> ::core::result::Result<Self, ::worker_route::Error> {
+                use ::worker_route::multipart::{FieldCollect, FromField};
+
+                #(let mut #parts: ::std::vec::Vec<<#tys as FieldCollect>::Item> = ::std::vec::Vec::new();)*
+                while let Some(field__) = multipart__.next_field().await? {
+                    let key__ = field__.name().to_owned();
+                    match key__.as_str() {
+                        #(#names => #parts.push(<<#tys as FieldCollect>::Item as FromField>::from_field(field__).await?),)*
+                        _ => {}
+                    }
+                }
+
+                Ok(Self {
+                    #(#idents: <#tys as FieldCollect>::collect(#names, #parts)?,)*
+                })
+            }
+        }
+    })
+}
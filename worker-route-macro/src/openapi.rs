@@ -0,0 +1,278 @@
+use crate::route::Route;
+use crate::serde_attrs::{serde_attrs, serde_name, SerdeAttrs};
+use crate::wrapper::last_ident;
+
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{
+    parse_quote, Attribute, Data, DataEnum, DeriveInput, Expr, Fields, FieldsNamed,
+    GenericArgument, PathArguments, ReturnType, Type,
+};
+
+// the generic arguments of the last segment, eg: `Result<T, E>` -> [T, E]
+fn type_args(ty: &Type) -> Vec<&Type> {
+    let Type::Path(path) = ty else {
+        return Vec::new();
+    };
+    match path.path.segments.last().map(|v| &v.arguments) {
+        Some(PathArguments::AngleBracketed(args)) => args
+            .args
+            .iter()
+            .filter_map(|v| match v {
+                GenericArgument::Type(ty) => Some(ty),
+                _ => None,
+            })
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+fn first_arg(ty: &Type) -> Option<&Type> {
+    type_args(ty).into_iter().next()
+}
+
+// a type without `Schema` is left undocumented, see `worker_route::openapi::__private`
+fn probe(ty: &Type, method: &str, content_type: Option<&str>) -> TokenStream {
+    let method = syn::Ident::new(method, proc_macro2::Span::call_site());
+    let content_type = content_type.map(|v| quote!(, #v));
+    quote! {
+        (&::worker_route::openapi::__private::Probe::<#ty>::new())
+            .#method(&mut operation__ #content_type);
+    }
+}
+
+// documents an extractor, `Valid<E>` and `Option<E>` are documented as `E`
+fn input(ty: &Type) -> Option<TokenStream> {
+    let inner = first_arg(ty)?;
+    match last_ident(ty).as_deref()? {
+        "Valid" | "Option" => input(inner),
+        "Path" => Some(probe(inner, "path", None)),
+        "Query" => Some(probe(inner, "query", None)),
+        "Json" => Some(probe(inner, "body", Some("application/json"))),
+        "Form" => Some(probe(
+            inner,
+            "body",
+            Some("application/x-www-form-urlencoded"),
+        )),
+        _ => None,
+    }
+}
+
+// documents the success response, the body of a `Responder` which isn't listed is unknown
+fn response(ty: &Type) -> Option<TokenStream> {
+    let text: Type = parse_quote!(str);
+    let bytes: Type = parse_quote!([u8]);
+    if let Type::Reference(reference) = ty {
+        return match &*reference.elem {
+            Type::Path(path) if path.path.is_ident("str") => {
+                Some(probe(&text, "response", Some("text/plain")))
+            }
+            Type::Slice(_) => Some(probe(&bytes, "response", Some("application/octet-stream"))),
+            _ => None,
+        };
+    }
+
+    match last_ident(ty).as_deref()? {
+        "Json" => first_arg(ty).map(|v| probe(v, "response", Some("application/json"))),
+        "Value" => Some(probe(ty, "response", Some("application/json"))),
+        "String" | "Cow" => Some(probe(&text, "response", Some("text/plain"))),
+        "Vec" => Some(probe(&bytes, "response", Some("application/octet-stream"))),
+        _ => None,
+    }
+}
+
+// `Result<T, E>`, `worker::Result<T>` is `Result<T, worker::Error>`
+fn output(ret: &ReturnType) -> Vec<TokenStream> {
+    let ReturnType::Type(_, ty) = ret else {
+        return Vec::new();
+    };
+    if last_ident(ty).as_deref() != Some("Result") {
+        return Vec::new();
+    }
+
+    let args = type_args(ty);
+    let error: Type = args
+        .get(1)
+        .map_or_else(|| parse_quote!(::worker::Error), |v| (*v).clone());
+    let mut output = match args.first() {
+        Some(Type::Tuple(tuple)) if tuple.elems.is_empty() => {
+            vec![quote!(operation__.no_content();)]
+        }
+        Some(ty) => response(ty).into_iter().collect(),
+        None => Vec::new(),
+    };
+    output.push(quote!(operation__.errors::<#error>();));
+    output
+}
+
+// the operation of a handler, collected by `RouteTable`,
+// its error responses are those of its extractors and of its return type
+pub fn operations(
+    route: &Route,
+    extractors: &[Type],
+    data: &TokenStream,
+    ret: &ReturnType,
+) -> Option<TokenStream> {
+    if route.is_fallback {
+        return None;
+    }
+
+    let inputs = extractors.iter().filter_map(input);
+    let errors = extractors
+        .iter()
+        .map(|ty| quote!(operation__.extractor::<#ty, #data>();));
+    let output = output(ret);
+    Some(quote! {
+        fn operations(
+            &self,
+            operations__: &mut ::std::vec::Vec<::worker_route::openapi::Operation>
+        ) {
+            use ::worker_route::openapi::__private::{Documented as _, Undocumented as _};
+            let mut operation__ = ::worker_route::openapi::Operation::new(Self::ROUTE);
+            #(#inputs)*
+            #(#errors)*
+            #(#output)*
+            operations__.push(operation__);
+        }
+    })
+}
+
+// the doc comments of an item, joined by lines
+fn description(attrs: &[Attribute]) -> Option<String> {
+    let lines = attrs
+        .iter()
+        .filter(|v| v.path().is_ident("doc"))
+        .filter_map(|v| match &v.meta {
+            syn::Meta::NameValue(meta) => match &meta.value {
+                Expr::Lit(syn::ExprLit {
+                    lit: syn::Lit::Str(lit),
+                    ..
+                }) => Some(lit.value().trim().to_owned()),
+                _ => None,
+            },
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+
+    (!lines.is_empty()).then(|| lines.join("\n"))
+}
+
+fn described(schema: TokenStream, attrs: &[Attribute]) -> TokenStream {
+    match description(attrs) {
+        Some(description) => quote!(::worker_route::openapi::__describe(#schema, #description)),
+        None => schema,
+    }
+}
+
+fn named_fields(container: &SerdeAttrs, named: &FieldsNamed) -> syn::Result<TokenStream> {
+    let mut properties = Vec::new();
+    for field in &named.named {
+        let attrs = serde_attrs(&field.attrs)?;
+        if attrs.skip {
+            continue;
+        }
+        let ident = field.ident.as_ref().unwrap().to_string();
+        let default = attrs.default || container.default;
+        let name = serde_name(container, attrs, ident.trim_start_matches("r#"));
+        let ty = &field.ty;
+        let schema = described(quote!(components__.reference::<#ty>()), &field.attrs);
+        let required = if default {
+            quote!(false)
+        } else {
+            quote!(<#ty as ::worker_route::openapi::Schema>::required())
+        };
+        properties.push(quote! {
+            properties__.insert(#name.to_owned(), #schema);
+            if #required {
+                required__.push(::worker_route::openapi::__json::Value::from(#name));
+            }
+        });
+    }
+
+    Ok(quote! {
+        let mut properties__ = ::worker_route::openapi::__json::Map::new();
+        let mut required__ = ::std::vec::Vec::new();
+        #(#properties)*
+        ::worker_route::openapi::__json::json!({
+            "type": "object",
+            "properties": properties__,
+            "required": required__
+        })
+    })
+}
+
+fn unit_variants(container: &SerdeAttrs, data: &DataEnum) -> syn::Result<TokenStream> {
+    let mut variants = Vec::new();
+    for variant in &data.variants {
+        if !matches!(variant.fields, Fields::Unit) {
+            return Err(syn::Error::new_spanned(
+                variant,
+                "Schema can only be derived for enums of unit variants",
+            ));
+        }
+        let attrs = serde_attrs(&variant.attrs)?;
+        if !attrs.skip {
+            variants.push(serde_name(container, attrs, &variant.ident.to_string()));
+        }
+    }
+
+    Ok(quote!(
+        ::worker_route::openapi::__json::json!({ "type": "string", "enum": [#(#variants),*] })
+    ))
+}
+
+pub fn derive(input: &DeriveInput) -> syn::Result<TokenStream> {
+    let container = serde_attrs(&input.attrs)?;
+    let schema =
+        match &input.data {
+            Data::Struct(data) => match &data.fields {
+                Fields::Named(named) => named_fields(&container, named)?,
+                Fields::Unnamed(unnamed) if unnamed.unnamed.len() == 1 => {
+                    let ty = &unnamed.unnamed[0].ty;
+                    quote!(components__.reference::<#ty>())
+                }
+                _ => return Err(syn::Error::new_spanned(
+                    &data.fields,
+                    "Schema can only be derived for structs with named fields or newtype structs",
+                )),
+            },
+            Data::Enum(data) => unit_variants(&container, data)?,
+            Data::Union(_) => {
+                return Err(syn::Error::new_spanned(
+                    input,
+                    "Schema cannot be derived for unions",
+                ))
+            }
+        };
+
+    let ident = &input.ident;
+    let schema = described(quote!({ #schema }), &input.attrs);
+    // a generic type is inlined, its name would be shared by every instance
+    let name = input.generics.params.is_empty().then(|| {
+        let name = ident.to_string();
+        quote! {
+            fn name() -> ::core::option::Option<&'static str> {
+                ::core::option::Option::Some(#name)
+            }
+        }
+    });
+    let mut generics = input.generics.clone();
+    for param in generics.type_params_mut() {
+        param
+            .bounds
+            .push(parse_quote!(::worker_route::openapi::Schema));
+    }
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    Ok(quote! {
+        impl #impl_generics ::worker_route::openapi::Schema for #ident #ty_generics #where_clause {
+            #name
+
+            fn schema(
+                components__: &mut ::worker_route::openapi::Components
+            ) -> ::worker_route::openapi::__json::Value {
+                #schema
+            }
+        }
+    })
+}
@@ -0,0 +1,280 @@
+use crate::route::Route;
+use crate::serde_attrs::{serde_attrs, serde_name};
+use crate::wrapper::last_ident;
+
+use proc_macro2::{Ident, Span, TokenStream};
+use quote::{quote, quote_spanned};
+use syn::{Data, DataStruct, DeriveInput, Fields, GenericArgument, PathArguments, Type};
+
+// types deserialized from the params without being a struct, their fields can't be checked
+const NOT_STRUCTS: &[&str] = &[
+    "String", "Cow", "Box", "Rc", "Arc", "Vec", "HashMap", "BTreeMap", "Value", "Map",
+];
+
+/// Returns the names of the `:param` and `*wildcard` segments of a path, in order.
+///
+/// eg: `/post/:author/*rest` -> `["author", "rest"]`
+fn names(path: &str) -> Vec<&str> {
+    path.split('/')
+        .filter_map(|v| v.strip_prefix(':').or_else(|| v.strip_prefix('*')))
+        .collect()
+}
+
+fn inner(ty: &Type) -> Option<&Type> {
+    let Type::Path(path) = ty else {
+        return None;
+    };
+    match &path.path.segments.last()?.arguments {
+        PathArguments::AngleBracketed(args) => args.args.iter().find_map(|v| match v {
+            GenericArgument::Type(ty) => Some(ty),
+            _ => None,
+        }),
+        _ => None,
+    }
+}
+
+// the struct the params are deserialized into, `Valid<E>`, `Option<E>` and `Result<E, _>` are checked as `E`
+//
+// `Query<T>` is skipped, it only reads the query string since the params are extracted with `Path<T>`.
+// `Path<T>` of a primitive, eg: `Path<u64>`, has no fields, a type that doesn't implement
+// `PathParams`, eg: `Path<Uuid>`, passes the check.
+fn target(ty: &Type) -> Option<&Type> {
+    let target = match last_ident(ty)?.as_str() {
+        "Valid" | "Option" | "Result" => return target(inner(ty)?),
+        "Path" => inner(ty)?,
+        _ => return None,
+    };
+
+    let name = last_ident(target)?;
+    let is_struct = name.starts_with(char::is_uppercase) && !NOT_STRUCTS.contains(&name.as_str());
+    is_struct.then_some(target)
+}
+
+pub fn to_ident(name: &str, span: Span) -> Option<Ident> {
+    syn::parse_str::<Ident>(name)
+        .or_else(|_| syn::parse_str::<Ident>(&format!("r#{name}")))
+        .ok()
+        .map(|mut v| {
+            v.set_span(span);
+            v
+        })
+}
+
+/// Asserts at compile time that every param of the path is one of the fields of the struct
+/// extracted with `Path<T>`, reported at the path literal.
+///
+/// The fields are the names serde deserializes, listed by `#[derive(PathParams)]`,
+/// a struct without it isn't checked. `unchecked` skips the assertion.
+pub fn check(route: &Route, extractors: &[Type]) -> TokenStream {
+    if route.is_fallback || route.unchecked {
+        return TokenStream::new();
+    }
+
+    let span = route.span.unwrap_or_else(Span::call_site);
+    let names = names(route.path.as_deref().unwrap_or_default());
+    for (i, name) in names.iter().enumerate() {
+        if name.is_empty() {
+            return quote_spanned!(span => compile_error!("a path parameter must be named"););
+        }
+        if names[..i].contains(name) {
+            let message = format!("duplicate path parameter `{name}`");
+            return quote_spanned!(span => compile_error!(#message););
+        }
+    }
+
+    let checks = extractors.iter().filter_map(target).map(|ty| {
+        let messages = names.iter().map(|name| {
+            format!(
+                "path parameter `{name}` is not a field of `{}`",
+                quote!(#ty).to_string().replace(' ', "")
+            )
+        });
+        let names = names.iter();
+        quote_spanned! {span =>
+            const _: () = {
+                use ::worker_route::__private::NoPathParams as _;
+                #(::core::assert!(
+                    ::worker_route::__private::has_param(
+                        <::worker_route::__private::PathParamsOf<#ty>>::FIELDS,
+                        #names,
+                    ),
+                    #messages
+                );)*
+            };
+        }
+    });
+
+    quote!(#(#checks)*)
+}
+
+/// Implements `worker_route::PathParams` for a struct with named fields,
+/// with the names serde deserializes its fields from.
+pub fn derive(input: &DeriveInput) -> syn::Result<TokenStream> {
+    let Data::Struct(DataStruct {
+        fields: Fields::Named(named),
+        ..
+    }) = &input.data
+    else {
+        return Err(syn::Error::new_spanned(
+            input,
+            "PathParams can only be derived for structs with named fields",
+        ));
+    };
+
+    let container = serde_attrs(&input.attrs)?;
+    let mut fields = Vec::new();
+    for field in &named.named {
+        let attrs = serde_attrs(&field.attrs)?;
+        if attrs.flatten {
+            return Err(syn::Error::new_spanned(
+                field,
+                "PathParams cannot be derived with `#[serde(flatten)]`",
+            ));
+        }
+        if attrs.skip_deserializing {
+            continue;
+        }
+        let aliases = attrs.alias.clone();
+        let ident = field.ident.as_ref().unwrap().to_string();
+        fields.push(serde_name(
+            &container,
+            attrs,
+            ident.trim_start_matches("r#"),
+        ));
+        fields.extend(aliases);
+    }
+
+    let ident = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    Ok(quote! {
+        impl #impl_generics ::worker_route::PathParams for #ident #ty_generics #where_clause {
+            const FIELDS: &'static [&'static str] = &[#(#fields),*];
+        }
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::{check, derive, names};
+    use crate::route::Route;
+    use std::slice;
+    use syn::{parse_quote, DeriveInput, Type};
+
+    fn checked(path: &str, extractors: &[Type]) -> String {
+        let route = Route {
+            path: Some(path.to_owned()),
+            ..Route::default()
+        };
+        check(&route, extractors).to_string()
+    }
+
+    #[test]
+    fn param_names() {
+        assert_eq!(names("/post/:author/*rest"), ["author", "rest"]);
+        assert_eq!(names("/post/author:id/:"), [""]);
+        assert!(names("/").is_empty());
+    }
+
+    #[test]
+    fn fields() {
+        let checks = checked("/person/:name/:age", &[parse_quote!(Path<Person>)]);
+        assert!(
+            checks.contains(
+                "< :: worker_route :: __private :: PathParamsOf < Person >> :: FIELDS , \"name\""
+            ),
+            "{checks}"
+        );
+        assert!(checks.contains("\"age\""), "{checks}");
+        assert!(
+            checks.contains("\"path parameter `age` is not a field of `Person`\""),
+            "{checks}"
+        );
+
+        // a single param, the check of a type without fields passes
+        let checks = checked("/users/:id", &[parse_quote!(Path<User>)]);
+        assert!(checks.contains("PathParamsOf < User >"), "{checks}");
+        let checks = checked(
+            "/:type/:id",
+            &[parse_quote!(Result<Valid<Path<Item>>, Error>)],
+        );
+        assert!(checks.contains("PathParamsOf < Item >"), "{checks}");
+
+        // a primitive has no fields
+        assert!(checked("/users/:id", &[parse_quote!(Path<u64>)]).is_empty());
+        // a type deserialized from a map can't be checked
+        assert!(checked("/:a/:b", &[parse_quote!(Path<HashMap<String, String>>)]).is_empty());
+        assert!(checked("/:a/:b", &[parse_quote!(Path<(String, u32)>)]).is_empty());
+    }
+
+    #[test]
+    fn query() {
+        // a query is a query of its own, the params are never its fields
+        assert!(checked("/:a/:b", &[parse_quote!(Query<Search>)]).is_empty());
+        let checks = checked(
+            "/:a/:b",
+            &[
+                parse_quote!(Option<Query<Search>>),
+                parse_quote!(Path<Params>),
+            ],
+        );
+        assert!(checks.contains("PathParamsOf < Params >"), "{checks}");
+        assert!(!checks.contains("Search"), "{checks}");
+    }
+
+    #[test]
+    fn derived() {
+        let input: DeriveInput = parse_quote! {
+            #[serde(rename_all = "camelCase")]
+            struct Post {
+                user_id: u64,
+                #[serde(rename = "post-id", alias = "id")]
+                post_id: u64,
+                r#type: String,
+                #[serde(skip_serializing)]
+                slug: String,
+                #[serde(skip)]
+                cached: bool,
+            }
+        };
+        let derived = derive(&input).unwrap().to_string();
+        assert!(
+            derived.contains(r#"& ["userId" , "post-id" , "id" , "type" , "slug"]"#),
+            "{derived}"
+        );
+
+        let input: DeriveInput = parse_quote! {
+            struct Post {
+                #[serde(flatten)]
+                user: User,
+            }
+        };
+        assert_eq!(
+            derive(&input).unwrap_err().to_string(),
+            "PathParams cannot be derived with `#[serde(flatten)]`"
+        );
+        let input: DeriveInput = parse_quote!(
+            struct Id(u64);
+        );
+        assert!(derive(&input).is_err());
+    }
+
+    #[test]
+    fn errors() {
+        let path: Type = parse_quote!(Path<Params>);
+        assert!(
+            checked("/:id/:id", slice::from_ref(&path)).contains("duplicate path parameter `id`")
+        );
+        assert!(checked("/:/x", &[]).contains("a path parameter must be named"));
+        // not extracted into a struct, the names don't matter
+        assert!(checked("/:my-id/:x", &[]).is_empty());
+        // a renamed field may not be an identifier
+        assert!(checked("/:my-id/:x", slice::from_ref(&path)).contains("\"my-id\""));
+
+        let unchecked = Route {
+            path: Some("/:my-id/:x".to_owned()),
+            unchecked: true,
+            ..Route::default()
+        };
+        assert!(check(&unchecked, &[path]).is_empty());
+    }
+}
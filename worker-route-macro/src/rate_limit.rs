@@ -0,0 +1,21 @@
+use crate::route::Route;
+
+use proc_macro2::{Ident, TokenStream};
+use quote::quote_spanned;
+
+/// The `RateLimit` of `rate_limit = ".."`, its keys are prefixed with the handler's path.
+///
+/// The quota is parsed by `Quota::__from_attr` in a `const`, an invalid quota doesn't compile.
+pub fn middleware(route: &Route, name: &Ident) -> Option<TokenStream> {
+    let quota = route.rate_limit.as_ref()?;
+    let name = name.to_string();
+
+    Some(quote_spanned! {quota.span()=>
+        ::worker_route::RateLimit::new({
+            const QUOTA: ::worker_route::rate_limit::Quota =
+                ::worker_route::rate_limit::Quota::__from_attr(#quota);
+            QUOTA
+        })
+        .with_prefix(::core::concat!(::core::module_path!(), "::", #name))
+    })
+}
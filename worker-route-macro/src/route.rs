@@ -1,41 +1,60 @@
 use core::fmt::{Debug, Display};
 
 use crate::{
+    constraint::{self, Constraint},
     error::{to_error, ErrorSpan},
     expand::expand,
-    method::{Method, SUPPORTED_METHODS},
+    method::{is_custom, Method, REGISTERED_METHODS, SUPPORTED_METHODS},
 };
 
 use proc_macro::TokenStream as TokenStream_;
 use proc_macro2::{Ident, Span, TokenStream};
 use quote::{quote, ToTokens};
 use syn::{
-    parse, parse_macro_input, parse_quote, spanned::Spanned, token::Async, ExprArray, Lit, LitStr,
-    Token,
+    parse, parse_macro_input, parse_quote, spanned::Spanned, Expr, ExprArray, Lit, LitStr, Token,
 };
 
+// the `OPTIONS` handler of `preflight`, returning the cors through the route's middlewares
+fn preflight(route: &Route, cors: Option<&TokenStream>, data: &TokenStream) -> TokenStream {
+    let middleware = route.middleware.iter();
+    let response = cors.map_or_else(
+        || quote!(::worker::Response::empty()),
+        |cors| quote!(::worker::Response::empty()?.with_cors(&#cors)),
+    );
+
+    quote! {
+        |req__, ctx__| async move {
+            ::worker_route::__private::wrap(
+                req__,
+                ctx__,
+                &[#(::worker_route::__private::middleware::<#data, _>(&#middleware)),*],
+                |req__, _| async move { #response },
+            )
+            .await
+        }
+    }
+}
+
 pub fn gen_router(
-    asyncness: Option<Async>,
-    cors: &Option<TokenStream>,
-    path: &str,
+    route: &Route,
+    cors: Option<&TokenStream>,
+    data: &TokenStream,
     method: &str,
-    wrap: bool,
     is_last: Option<(usize, usize)>,
 ) -> TokenStream {
+    let path = route.path.as_ref().unwrap();
     let method_ = method;
-    let method: TokenStream = Method::new(&method.to_uppercase()).to_token().into();
-    let register = if asyncness.is_some() {
-        quote!(register_async)
-    } else {
-        quote!(register)
-    };
+    let method: TokenStream = Method::new(&method.to_uppercase())
+        .unwrap_or(Method::Default)
+        .to_token()
+        .into();
     let mut chain = None;
     let args: TokenStream = parse_quote!(
         #path,
         #method,
         __handler
     );
-    let mut call: TokenStream = parse_quote!(router__.#register);
+    let mut call: TokenStream = parse_quote!(router__.register_async);
 
     if let Some((i, len)) = is_last {
         if i + 1 != len {
@@ -43,24 +62,14 @@ pub fn gen_router(
         }
 
         if i > 0 {
-            call = parse_quote!(#register);
-            // args = parse_quote!(
-            //     #path,
-            //     #method,
-            //     __handler
-            // );
+            call = parse_quote!(register_async);
         }
 
-        if (method_ == "options" || i + 1 == len) && wrap && cors.is_some() {
-            let (options, move_) = if asyncness.is_some() {
-                (quote!(options_async), Some(quote!(async move)))
-            } else {
-                (quote!(options), None)
-            };
-
-            let register_ = parse_quote!(#options(#path, |req__, _| #move_ {
-                ::worker::Response::empty()?.with_cors(&#cors)
-            }));
+        if (method_ == "options" || i + 1 == len) && route.preflight {
+            let preflight = preflight(route, cors, data);
+            // registered like any handler, to be accounted for the allowed methods
+            let register_ =
+                parse_quote!(register_async(#path, ::worker::Method::Options, #preflight));
 
             if len == 7 {
                 return register_;
@@ -78,13 +87,26 @@ pub fn gen_router(
 }
 
 #[derive(Default)]
+#[allow(clippy::struct_excessive_bools)]
 pub struct Route {
     pub path: Option<String>,
     pub cors: Option<Ident>,
     pub lazy_cors: Option<Ident>,
     pub methods: Vec<LitStr>,
-    pub wrap: bool,
+    // `preflight`, or `wrap` without a list, registers an `OPTIONS` handler
+    // returning the cors through the route's middlewares
+    pub preflight: bool,
+    // `wrap = [..]`, the middlewares of the route
+    pub middleware: Vec<Expr>,
+    // `rate_limit = "100/min"`, the quota as written, parsed by `Quota` at compile time
+    pub rate_limit: Option<LitStr>,
     pub is_single: bool,
+    pub is_fallback: bool,
+    // the span of the path literal, where the checks of the path's params are reported
+    pub span: Option<Span>,
+    pub unchecked: bool,
+    // the constraints stripped from the path, eg: `u8` for `:age<u8>`
+    pub constraints: Vec<(String, Constraint)>,
 }
 
 impl Debug for Route {
@@ -107,22 +129,22 @@ impl Debug for Route {
                 .collect::<Vec<_>>();
 
             return f.write_str(&format!(
-                "route({:?}, method: [{:?}], cors: {}, lazy_cors: {}, wrap: {})",
+                "route({:?}, method: [{:?}], cors: {}, lazy_cors: {}, preflight: {})",
                 self.path.as_ref().unwrap(),
                 methods,
                 cors,
                 lazy_cors,
-                self.wrap
+                self.preflight
             ));
         }
         let attr = self.methods[0].value().to_lowercase();
 
         f.write_str(&format!(
-            "{attr}({:?}, cors: {:?}, lazy_cors: {:?}, wrap: {})",
+            "{attr}({:?}, cors: {:?}, lazy_cors: {:?}, preflight: {})",
             self.path.as_ref().unwrap(),
             cors,
             lazy_cors,
-            self.wrap
+            self.preflight
         ))
     }
 }
@@ -144,23 +166,23 @@ impl Display for Route {
             let methods = self.methods.iter().map(quote::ToTokens::to_token_stream);
             return write!(
                 f,
-                "route({:?}, method: [{}], cors: {}, lazy_cors: {}, wrap: {})",
+                "route({:?}, method: [{}], cors: {}, lazy_cors: {}, preflight: {})",
                 self.path.as_ref().unwrap(),
                 quote!(#(#methods)*),
                 cors,
                 lazy_cors,
-                self.wrap
+                self.preflight
             );
         }
         let attr = self.methods[0].value().to_lowercase();
 
         write!(
             f,
-            "{attr}({:?}, cors: {}, lazy_cors: {}, wrap: {})",
+            "{attr}({:?}, cors: {}, lazy_cors: {}, preflight: {})",
             self.path.as_ref().unwrap(),
             cors,
             lazy_cors,
-            self.wrap
+            self.preflight
         )
     }
 }
@@ -172,6 +194,34 @@ pub fn with_method<const T: usize>(attrs: TokenStream_, items: TokenStream_) ->
     expand(items.into(), &args.0).into()
 }
 
+// the pattern given to the fallback's extractors, eg: `Path<String>` is the unmatched path
+const FALLBACK_PATH: &str = "/*path";
+
+struct FallbackArgs(Route);
+
+impl FallbackArgs {
+    fn parse(input: parse::ParseStream) -> syn::Result<Self> {
+        let mut route = Route {
+            path: Some(FALLBACK_PATH.to_owned()),
+            is_fallback: true,
+            ..Route::default()
+        };
+        fill(&mut route, input)?;
+        if !route.methods.is_empty() || route.preflight || route.unchecked || !input.is_empty() {
+            return Err(
+                input.error("expected #[fallback] or #[fallback(cors/lazy_cors, wrap = [..])]")
+            );
+        }
+
+        Ok(Self(route))
+    }
+}
+
+pub fn fallback(attrs: TokenStream_, items: TokenStream_) -> TokenStream_ {
+    let args = parse_macro_input!(attrs with FallbackArgs::parse);
+    expand(items.into(), &args.0).into()
+}
+
 enum CorsVariant {
     Default,
     Lazy,
@@ -192,7 +242,16 @@ fn fill(route: &mut Route, input: parse::ParseStream) -> syn::Result<()> {
         match ident_.as_str() {
             "method" => route.get_method(input, &ident)?,
             "cors" | "lazy_cors" => route.get_cors(&ident_, input)?,
-            "wrap" => route.wrap(&ident)?,
+            "wrap" if input.peek(Token![=]) => route.get_middleware(input)?,
+            "rate_limit" => route.get_rate_limit(input)?,
+            "wrap" | "preflight" => {
+                route.preflight(&ident)?;
+                _ = input.parse::<Token![,]>().is_ok();
+            }
+            "unchecked" => {
+                route.unchecked = true;
+                _ = input.parse::<Token![,]>().is_ok();
+            }
             _ => {
                 let error = syn::Error::new(
                     ident.span(),
@@ -215,7 +274,12 @@ impl<T, const U: usize> RouteArgs<T, U> {
         let mut route = Route::new(Method::to_method(U));
 
         match input.parse::<LitStr>() {
-            Ok(path) => route.path = Some(path.value()),
+            Ok(path) => {
+                let (pattern, constraints) = constraint::parse(&path.value(), path.span())?;
+                route.path = Some(pattern);
+                route.span = Some(path.span());
+                route.constraints = constraints;
+            }
             Err(e) => {
                 return Err(input.error(format!(
                     r#"expected #[route("<path>")], found {}"#,
@@ -236,6 +300,22 @@ impl<T, const U: usize> RouteArgs<T, U> {
         } else if input.parse::<Token![,]>().is_ok() {
             fill(&mut route, input)?;
         }
+        if route.preflight
+            && route.cors.is_none()
+            && route.lazy_cors.is_none()
+            && route.middleware.is_empty()
+        {
+            return Err(syn::Error::new(
+                route.span.unwrap_or_else(Span::call_site),
+                r#"preflight cannot be used when "cors/lazy_cors" and "wrap" are ommitted"#,
+            ));
+        }
+        if route.preflight && route.is_any() {
+            return Err(syn::Error::new(
+                route.span.unwrap_or_else(Span::call_site),
+                "preflight cannot be used with connect, trace or a custom method, the catch-all dispatching it answers `OPTIONS`",
+            ));
+        }
 
         Ok(Self(route, None))
     }
@@ -274,7 +354,9 @@ impl Route {
             if self.is_single {
                 return Err(to_error(ErrorSpan::Method, expr.source_text(), expr));
             }
-            if !SUPPORTED_METHODS.contains(&method.value().to_uppercase().as_str()) {
+            if !SUPPORTED_METHODS.contains(&method.value().to_uppercase().as_str())
+                && !is_custom(&method.value())
+            {
                 return Err(to_error(
                     ErrorSpan::InvalidMethods,
                     Some(&method.value()),
@@ -295,31 +377,49 @@ impl Route {
         Ok(())
     }
 
-    fn wrap(&mut self, ident: &Ident) -> syn::Result<()> {
-        if self.cors.is_none() && self.lazy_cors.is_none() {
-            let error = syn::Error::new(
-                ident.span(),
-                r#"wrap cannot be used when "cors/lazy_cors" are ommitted"#,
-            );
-            return Err(error);
-        }
-        self.wrap = true;
-        if self.wrap
-            && self
-                .methods
-                .iter()
-                .any(|v| v.value().to_lowercase() == "options")
+    fn preflight(&mut self, ident: &Ident) -> syn::Result<()> {
+        self.preflight = true;
+        if self
+            .methods
+            .iter()
+            .any(|v| v.value().to_lowercase() == "options")
             && self.methods.len() != 7
         {
             let error = syn::Error::new(
                 ident.span(),
-                "wrap cannot be used when options handler is present",
+                format!("{ident} cannot be used when options handler is present"),
             );
             return Err(error);
         }
         Ok(())
     }
 
+    // wrap = [Auth, Timing]
+    fn get_middleware(&mut self, input: parse::ParseStream) -> syn::Result<()> {
+        _ = input.parse::<Token![=]>()?;
+        let array = input
+            .parse::<ExprArray>()
+            .map_err(|e| syn::Error::new(e.span(), "expected wrap = [<middleware>, ..]"))?;
+        self.middleware.extend(array.elems);
+
+        _ = input.parse::<Token![,]>().is_ok();
+
+        Ok(())
+    }
+
+    // rate_limit = "100/min"
+    fn get_rate_limit(&mut self, input: parse::ParseStream) -> syn::Result<()> {
+        _ = input.parse::<Token![=]>()?;
+        let lit = input.parse::<LitStr>().map_err(|e| {
+            syn::Error::new(e.span(), r#"expected rate_limit = "<limit>/<period>""#)
+        })?;
+        self.rate_limit = Some(lit);
+
+        _ = input.parse::<Token![,]>().is_ok();
+
+        Ok(())
+    }
+
     fn get_method(&mut self, input: parse::ParseStream, ident: &Ident) -> syn::Result<()> {
         _ = input.parse::<Token![=]>()?;
 
@@ -370,6 +470,14 @@ impl Route {
         Ok(())
     }
 
+    /// Returns `true` if a method can't be registered by itself,
+    /// the router's catch-all runs the handler for it, on the request's method.
+    pub fn is_any(&self) -> bool {
+        self.methods
+            .iter()
+            .any(|v| !REGISTERED_METHODS.contains(&v.value().to_uppercase().as_str()))
+    }
+
     pub fn cors(&self) -> Option<TokenStream> {
         match (&self.cors, &self.lazy_cors) {
             (None, Some(lazy_cors)) => Some(quote! {
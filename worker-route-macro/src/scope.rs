@@ -0,0 +1,528 @@
+use proc_macro2::{Delimiter, Group, Ident, Literal, TokenStream, TokenTree};
+use quote::{quote, ToTokens};
+use syn::{
+    parse::{Parse, ParseStream},
+    spanned::Spanned,
+    Attribute, Expr, ExprArray, Item, ItemMod, LitStr, Token,
+};
+
+const ROUTE_ATTRS: &[&str] = &[
+    "route", "delete", "get", "head", "options", "patch", "post", "put", "connect", "trace",
+];
+
+// the methods registered by themselves
+const METHODS: &[&str] = &["delete", "get", "head", "patch", "post", "put", "options"];
+
+// #[scope("/prefix", cors = Cors, preflight, wrap = [Auth])]
+pub struct Scope {
+    prefix: LitStr,
+    cors: Option<(Ident, Ident)>,
+    preflight: bool,
+    // run before the middlewares of each route
+    middleware: Vec<Expr>,
+    // #[handlers], every nested inline module is included
+    recursive: bool,
+}
+
+impl Parse for Scope {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let prefix = input
+            .parse::<LitStr>()
+            .map_err(|e| syn::Error::new(e.span(), r#"expected #[scope("<prefix>")]"#))?;
+        let mut scope = Self {
+            prefix,
+            cors: None,
+            preflight: false,
+            middleware: Vec::new(),
+            recursive: false,
+        };
+
+        while input.parse::<Token![,]>().is_ok() && !input.is_empty() {
+            let ident = input.parse::<Ident>()?;
+            match ident.to_string().as_str() {
+                "cors" | "lazy_cors" if scope.cors.is_none() => {
+                    input.parse::<Token![=]>()?;
+                    scope.cors = Some((ident, input.parse()?));
+                }
+                "wrap" if input.peek(Token![=]) => {
+                    input.parse::<Token![=]>()?;
+                    scope.middleware.extend(input.parse::<ExprArray>()?.elems);
+                }
+                "wrap" | "preflight" => scope.preflight = true,
+                _ => {
+                    return Err(syn::Error::new(
+                        ident.span(),
+                        r#"expected #[scope("<prefix>", cors/lazy_cors, preflight, wrap = [..])]"#,
+                    ))
+                }
+            }
+        }
+
+        if scope.preflight && scope.cors.is_none() && scope.middleware.is_empty() {
+            return Err(syn::Error::new(
+                scope.prefix.span(),
+                r#"preflight cannot be used when "cors/lazy_cors" and "wrap" are ommitted"#,
+            ));
+        }
+
+        Ok(scope)
+    }
+}
+
+impl ToTokens for Scope {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        let prefix = &self.prefix;
+        let cors = self.cors.as_ref().map(|(k, v)| quote!(, #k = #v));
+        let preflight = self.preflight.then(|| quote!(, preflight));
+        let middleware = &self.middleware;
+        let wrap = (!middleware.is_empty()).then(|| quote!(, wrap = [#(#middleware),*]));
+        tokens.extend(quote!(#prefix #cors #preflight #wrap));
+    }
+}
+
+// joins the scope's prefix with a route's path, eg: "/api/v1" + "/users"
+fn join(prefix: &str, path: &str) -> String {
+    let prefix = prefix.trim_end_matches('/');
+    match path.trim_start_matches('/') {
+        "" if prefix.is_empty() => "/".to_owned(),
+        "" => prefix.to_owned(),
+        path => format!("{prefix}/{path}"),
+    }
+}
+
+fn attr_name(attr: &Attribute) -> Option<String> {
+    attr.path().segments.last().map(|v| v.ident.to_string())
+}
+
+fn has_ident(tokens: &TokenStream, names: &[&str]) -> bool {
+    tokens
+        .clone()
+        .into_iter()
+        .any(|v| matches!(v, TokenTree::Ident(ident) if names.iter().any(|name| ident == name)))
+}
+
+// a flag is an ident without a value, eg: `wrap` but not `wrap = [..]`
+fn has_flag(tokens: &[TokenTree], names: &[&str]) -> bool {
+    tokens.iter().enumerate().any(|(i, v)| {
+        matches!(v, TokenTree::Ident(ident) if names.iter().any(|name| ident == name))
+            && !matches!(tokens.get(i + 1), Some(TokenTree::Punct(p)) if p.as_char() == '=')
+    })
+}
+
+// prepends the scope's middlewares to the route's `wrap = [..]`, returns `false` if the route has none
+fn prepend_middleware(tokens: &mut [TokenTree], middleware: &[Expr]) -> bool {
+    for i in 0..tokens.len().saturating_sub(2) {
+        if let (TokenTree::Ident(ident), TokenTree::Punct(p), TokenTree::Group(group)) =
+            (&tokens[i], &tokens[i + 1], &tokens[i + 2])
+        {
+            if ident == "wrap" && p.as_char() == '=' && group.delimiter() == Delimiter::Bracket {
+                let stream = group.stream();
+                let mut group_ = Group::new(Delimiter::Bracket, quote!(#(#middleware,)* #stream));
+                group_.set_span(group.span());
+                tokens[i + 2] = TokenTree::Group(group_);
+                return true;
+            }
+        }
+    }
+
+    false
+}
+
+impl Scope {
+    /// The scope of `#[handlers]`, without a prefix nor a cors.
+    pub fn handlers() -> Self {
+        Self {
+            prefix: LitStr::new("", proc_macro2::Span::call_site()),
+            cors: None,
+            preflight: false,
+            middleware: Vec::new(),
+            recursive: true,
+        }
+    }
+
+    fn prefixed(&self, path: &LitStr) -> LitStr {
+        LitStr::new(&join(&self.prefix.value(), &path.value()), path.span())
+    }
+
+    // prefixes the route's path, adds the scope's cors if the route has none
+    // and runs the scope's middlewares before the route's, returns the prefixed path and the methods of the route
+    fn route_attr(&self, name: &str, attr: &mut Attribute) -> syn::Result<(LitStr, Vec<String>)> {
+        let (path, rest) = attr.parse_args_with(|input: ParseStream| {
+            Ok((input.parse::<LitStr>()?, input.parse::<TokenStream>()?))
+        })?;
+        let path = self.prefixed(&path);
+        let methods = methods(name, &rest);
+        let has_cors = has_ident(&rest, &["cors", "lazy_cors"]);
+        let cors = match &self.cors {
+            Some((k, v)) if !has_cors => Some(quote!(, #k = #v)),
+            _ => None,
+        };
+        let has_options = methods.iter().any(|v| v == "options");
+        let has_middleware = !self.middleware.is_empty() || has_ident(&rest, &["wrap"]);
+        let mut rest = rest.into_iter().collect::<Vec<_>>();
+        // the catch-all dispatching a custom method answers `OPTIONS`
+        let any = methods.iter().any(|v| !METHODS.contains(&v.as_str()));
+        let preflight = (self.preflight
+            && !any
+            && !has_flag(&rest, &["wrap", "preflight"])
+            && !has_options
+            && (has_cors || cors.is_some() || has_middleware))
+            .then(|| quote!(, preflight));
+        let middleware = &self.middleware;
+        let wrap = (!middleware.is_empty() && !prepend_middleware(&mut rest, middleware))
+            .then(|| quote!(, wrap = [#(#middleware),*]));
+        if (preflight.is_some() || wrap.is_some())
+            && matches!(rest.last(), Some(TokenTree::Punct(p)) if p.as_char() == ',')
+        {
+            rest.pop();
+        }
+
+        let path_ = attr.path();
+        *attr = syn::parse_quote!(#[#path_(#path #cors #(#rest)* #wrap #preflight)]);
+        Ok((path, methods))
+    }
+
+    // a nested scope inherits the prefix, cors, preflight and middlewares of its parent
+    fn scope_attr(&self, attr: &mut Attribute) -> syn::Result<()> {
+        let mut scope = attr.parse_args::<Self>()?;
+        scope.prefix = self.prefixed(&scope.prefix);
+        if scope.cors.is_none() {
+            scope.cors.clone_from(&self.cors);
+            scope.preflight |= self.preflight;
+        }
+        scope
+            .middleware
+            .splice(0..0, self.middleware.iter().cloned());
+
+        let path_ = attr.path();
+        *attr = syn::parse_quote!(#[#path_(#scope)]);
+        Ok(())
+    }
+}
+
+fn is_route(attr: &Attribute) -> bool {
+    attr_name(attr).is_some_and(|v| ROUTE_ATTRS.contains(&v.as_str()))
+}
+
+fn is_scope(attr: &Attribute) -> bool {
+    attr_name(attr).is_some_and(|v| v == "scope")
+}
+
+fn is_handlers(attr: &Attribute) -> bool {
+    attr_name(attr).is_some_and(|v| v == "handlers")
+}
+
+// the methods a route attribute is registered for, eg: `get` or `method = ["get", "post"]` of `route`,
+// only the values of `method` are read, not those of `rate_limit`, `wrap` nor `cors`
+fn methods(name: &str, tokens: &TokenStream) -> Vec<String> {
+    fn push(lit: &Literal, methods: &mut Vec<String>) {
+        let lit = lit.to_string().trim_matches('"').to_lowercase();
+        if lit == "all" {
+            methods.extend(METHODS.iter().map(|v| (*v).to_owned()));
+        } else {
+            methods.push(lit);
+        }
+    }
+
+    let mut methods = Vec::new();
+    if name != "route" {
+        methods.push(name.to_owned());
+        return methods;
+    }
+
+    let tokens = tokens.clone().into_iter().collect::<Vec<_>>();
+    for i in 0..tokens.len().saturating_sub(2) {
+        if !matches!((&tokens[i], &tokens[i + 1]), (TokenTree::Ident(ident), TokenTree::Punct(p)) if ident == "method" && p.as_char() == '=')
+        {
+            continue;
+        }
+        match &tokens[i + 2] {
+            TokenTree::Literal(lit) => push(lit, &mut methods),
+            TokenTree::Group(group) if group.delimiter() == Delimiter::Bracket => {
+                for token in group.stream() {
+                    if let TokenTree::Literal(lit) = token {
+                        push(&lit, &mut methods);
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    // a custom method is dispatched on its own, it only conflicts with the same method
+    methods
+}
+
+// a method and pattern registered by two handlers of the module, which the router panics on
+fn duplicate(
+    routes: &mut Vec<(String, String)>,
+    path: &LitStr,
+    methods: Vec<String>,
+) -> syn::Result<()> {
+    let value = path.value();
+    // `/:id<u8>` and `/:id` are the same pattern
+    let pattern = crate::constraint::parse(&value, path.span()).map_or(value, |v| v.0);
+    for method in methods {
+        let route = (method, pattern.clone());
+        if routes.contains(&route) {
+            return Err(syn::Error::new(
+                path.span(),
+                format!(
+                    "duplicate route `{} {}` in this module",
+                    route.0.to_uppercase(),
+                    route.1
+                ),
+            ));
+        }
+        routes.push(route);
+    }
+
+    Ok(())
+}
+
+pub fn expand(scope: &Scope, mut item: ItemMod) -> syn::Result<TokenStream> {
+    let Some((_, items)) = &mut item.content else {
+        return Err(syn::Error::new(
+            item.span(),
+            "scope can only be used on an inline module",
+        ));
+    };
+
+    let mut handlers = Vec::new();
+    let mut scopes = Vec::new();
+    let mut routes = Vec::new();
+    for item in items.iter_mut() {
+        match item {
+            Item::Fn(item) if item.attrs.iter().any(is_route) => {
+                for attr in &mut item.attrs {
+                    if let Some(name) =
+                        attr_name(attr).filter(|v| ROUTE_ATTRS.contains(&v.as_str()))
+                    {
+                        let (path, methods) = scope.route_attr(&name, attr)?;
+                        duplicate(&mut routes, &path, methods)?;
+                    }
+                }
+                handlers.push(item.sig.ident.clone());
+            }
+            Item::Mod(item) if item.attrs.iter().any(is_scope) => {
+                for attr in item.attrs.iter_mut().filter(|v| is_scope(v)) {
+                    scope.scope_attr(attr)?;
+                }
+                scopes.push(item.ident.clone());
+            }
+            // its handlers aren't prefixed, it's included as is
+            Item::Mod(item) if item.attrs.iter().any(is_handlers) => {
+                scopes.push(item.ident.clone());
+            }
+            // a `#[cfg]` module may not exist, eg: `#[cfg(test)] mod test`
+            Item::Mod(item)
+                if scope.recursive
+                    && item.content.is_some()
+                    && !item.attrs.iter().any(|v| v.path().is_ident("cfg")) =>
+            {
+                item.attrs
+                    .push(syn::parse_quote!(#[::worker_route::handlers]));
+                scopes.push(item.ident.clone());
+            }
+            _ => {}
+        }
+    }
+
+    // each scope only names its direct handlers and children,
+    // the items of a nested scope may not be visible from its grandparent
+    items.push(syn::parse_quote! {
+        #[doc(hidden)]
+        #[allow(non_camel_case_types)]
+        pub struct __scope;
+    });
+    #[cfg(feature = "openapi")]
+    let operations = Some(quote! {
+        fn operations(
+            &self,
+            operations: &mut ::std::vec::Vec<::worker_route::openapi::Operation>,
+        ) {
+            use ::worker_route::__private::RouteFactory;
+            #(RouteFactory::operations(&#handlers, operations);)*
+            #(RouteFactory::operations(&#scopes::__scope, operations);)*
+        }
+    });
+    #[cfg(not(feature = "openapi"))]
+    let operations: Option<TokenStream> = None;
+    items.push(syn::parse_quote! {
+        impl<D> ::worker_route::__private::RouteFactory<D> for __scope
+        where
+            #(#handlers: ::worker_route::__private::RouteFactory<D>,)*
+            #(#scopes::__scope: ::worker_route::__private::RouteFactory<D>,)*
+        {
+            fn register<R__: ::worker_route::__private::AddHandler<D>>(self, router: R__) -> R__ {
+                use ::worker_route::__private::RouteFactory;
+                #(let router = RouteFactory::register(#handlers, router);)*
+                #(let router = RouteFactory::register(#scopes::__scope, router);)*
+                router
+            }
+
+            fn describe(&self, routes: &mut ::std::vec::Vec<::worker_route::RouteInfo>) {
+                use ::worker_route::__private::RouteFactory;
+                #(RouteFactory::describe(&#handlers, routes);)*
+                #(RouteFactory::describe(&#scopes::__scope, routes);)*
+            }
+
+            #operations
+        }
+    });
+    items.push(syn::parse_quote! {
+        /// Configures every handler of this scope, nested scopes included.
+        #[allow(dead_code)]
+        pub fn service<D, C>(router: C) -> C
+        where
+            C: ::worker_route::Configure<D>,
+            __scope: ::worker_route::__private::RouteFactory<D>,
+        {
+            ::worker_route::Configure::configure(router, __scope)
+        }
+    });
+
+    Ok(item.into_token_stream())
+}
+
+#[cfg(test)]
+mod test {
+    use super::{duplicate, expand, join, methods, Scope};
+    use proc_macro2::Span;
+    use quote::{quote, ToTokens};
+    use syn::{parse_quote, Attribute, ItemMod, LitStr};
+
+    fn lit(path: &str) -> LitStr {
+        LitStr::new(path, Span::call_site())
+    }
+
+    fn expanded(prefix: &str, item: ItemMod) -> Result<(), String> {
+        let scope = syn::parse_str::<Scope>(&format!("{prefix:?}")).unwrap();
+        expand(&scope, item).map(drop).map_err(|e| e.to_string())
+    }
+
+    #[test]
+    fn joined() {
+        assert_eq!(join("/api", "/users"), "/api/users");
+        assert_eq!(join("/api/", "users"), "/api/users");
+        assert_eq!(join("/api", "/"), "/api");
+        assert_eq!(join("/api", ""), "/api");
+        assert_eq!(join("", "/users/:id"), "/users/:id");
+        assert_eq!(join("/", "/"), "/");
+        assert_eq!(join("", ""), "/");
+    }
+
+    #[test]
+    fn route_methods() {
+        assert_eq!(methods("get", &quote!()), ["get"]);
+        assert_eq!(
+            methods("route", &quote!(method = "GET", method = ["post", "PURGE"])),
+            ["get", "post", "purge"]
+        );
+        assert_eq!(methods("route", &quote!(method = "all")).len(), 7);
+
+        // the literals of the other arguments aren't methods
+        assert_eq!(
+            methods(
+                "route",
+                &quote!(
+                    method = "get",
+                    rate_limit = "100/min",
+                    wrap = [Timeout::new("5s", 30)],
+                    cors = cors("*")
+                )
+            ),
+            ["get"]
+        );
+        assert_eq!(
+            methods(
+                "route",
+                &quote!(wrap = [Log], method = ["post", "put"], rate_limit = "10/s")
+            ),
+            ["post", "put"]
+        );
+    }
+
+    #[test]
+    fn preflights() {
+        let scope = syn::parse_str::<Scope>(r#""/api", cors = Cors, preflight"#).unwrap();
+        let mut attr: Attribute =
+            parse_quote!(#[route("/x", method = "get", rate_limit = "100/min")]);
+        scope.route_attr("route", &mut attr).unwrap();
+        assert!(attr.to_token_stream().to_string().ends_with("preflight)]"));
+    }
+
+    #[test]
+    fn duplicates() {
+        let mut routes = Vec::new();
+        let get = || vec!["get".to_owned()];
+        assert!(duplicate(&mut routes, &lit("/users/:id"), get()).is_ok());
+        assert!(duplicate(&mut routes, &lit("/users/:id"), vec!["post".to_owned()]).is_ok());
+        assert!(duplicate(&mut routes, &lit("/users"), get()).is_ok());
+
+        // the constraints are stripped
+        let err = duplicate(&mut routes, &lit("/users/:id<u64>"), get()).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "duplicate route `GET /users/:id` in this module"
+        );
+        let err = duplicate(
+            &mut routes,
+            &lit("/users"),
+            vec!["put".to_owned(), "get".to_owned()],
+        );
+        assert!(err.is_err());
+    }
+
+    #[test]
+    fn scopes() {
+        let item: ItemMod = parse_quote! {
+            mod users {
+                #[get("/")]
+                async fn list() {}
+                #[get("/:id")]
+                async fn get() {}
+                #[route("/", method = ["post", "get"])]
+                async fn create() {}
+            }
+        };
+        assert_eq!(
+            expanded("/users", item),
+            Err("duplicate route `GET /users` in this module".to_owned())
+        );
+
+        // a different prefix, or a method of its own, isn't a duplicate
+        let item: ItemMod = parse_quote! {
+            mod users {
+                #[get("/")]
+                async fn list() {}
+                #[post("/")]
+                async fn create() {}
+                #[scope("/admin")]
+                mod admin {
+                    #[get("/")]
+                    async fn list() {}
+                }
+            }
+        };
+        assert_eq!(expanded("/users", item), Ok(()));
+
+        // the same rate limit on two methods isn't a duplicate
+        let item: ItemMod = parse_quote! {
+            mod users {
+                #[route("/", method = "get", rate_limit = "100/min")]
+                async fn list() {}
+                #[route("/", method = "post", rate_limit = "100/min")]
+                async fn create() {}
+            }
+        };
+        assert_eq!(expanded("/users", item), Ok(()));
+
+        let item: ItemMod = parse_quote!(
+            mod users;
+        );
+        assert_eq!(
+            expanded("/users", item),
+            Err("scope can only be used on an inline module".to_owned())
+        );
+    }
+}
@@ -0,0 +1,108 @@
+use syn::{meta::ParseNestedMeta, Attribute, Expr, LitStr, Token};
+
+#[allow(clippy::struct_excessive_bools)]
+#[derive(Default)]
+pub struct SerdeAttrs {
+    pub rename: Option<String>,
+    pub rename_all: Option<String>,
+    pub alias: Vec<String>,
+    pub default: bool,
+    pub flatten: bool,
+    // skipped by the schema, `skip` or `skip_serializing`
+    pub skip: bool,
+    // never deserialized, `skip` or `skip_deserializing`
+    pub skip_deserializing: bool,
+}
+
+// any other serde attribute is skipped, eg: `with = "..."` or `rename(serialize = "...")`
+fn skip_meta(meta: &ParseNestedMeta) -> syn::Result<()> {
+    if meta.input.peek(Token![=]) {
+        meta.value()?.parse::<Expr>()?;
+    } else if meta.input.peek(syn::token::Paren) {
+        meta.parse_nested_meta(|meta| skip_meta(&meta))?;
+    }
+    Ok(())
+}
+
+pub fn serde_attrs(attrs: &[Attribute]) -> syn::Result<SerdeAttrs> {
+    let mut serde = SerdeAttrs::default();
+    for attr in attrs.iter().filter(|v| v.path().is_ident("serde")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("rename") && meta.input.peek(Token![=]) {
+                serde.rename = Some(meta.value()?.parse::<LitStr>()?.value());
+            } else if meta.path.is_ident("rename_all") && meta.input.peek(Token![=]) {
+                serde.rename_all = Some(meta.value()?.parse::<LitStr>()?.value());
+            } else if meta.path.is_ident("alias") {
+                serde.alias.push(meta.value()?.parse::<LitStr>()?.value());
+            } else if meta.path.is_ident("flatten") {
+                serde.flatten = true;
+            } else if meta.path.is_ident("default") {
+                serde.default = true;
+                skip_meta(&meta)?;
+            } else if meta.path.is_ident("skip") {
+                serde.skip = true;
+                serde.skip_deserializing = true;
+            } else if meta.path.is_ident("skip_serializing") {
+                serde.skip = true;
+            } else if meta.path.is_ident("skip_deserializing") {
+                serde.skip_deserializing = true;
+            } else {
+                skip_meta(&meta)?;
+            }
+            Ok(())
+        })?;
+    }
+
+    Ok(serde)
+}
+
+// the names serde gives with `rename_all`, from snake_case fields or PascalCase variants
+fn rename(name: &str, rule: &str) -> String {
+    let words = name
+        .split('_')
+        .flat_map(|v| {
+            let mut words = Vec::new();
+            for c in v.chars() {
+                if c.is_uppercase() || words.is_empty() {
+                    words.push(String::new());
+                }
+                words.last_mut().unwrap().push(c);
+            }
+            words
+        })
+        .map(|v| v.to_lowercase())
+        .collect::<Vec<_>>();
+    let capitalize = |v: &String| {
+        let mut chars = v.chars();
+        chars
+            .next()
+            .map(|c| c.to_uppercase().chain(chars).collect::<String>())
+            .unwrap_or_default()
+    };
+
+    match rule {
+        "lowercase" => name.to_lowercase(),
+        "UPPERCASE" => name.to_uppercase(),
+        "PascalCase" => words.iter().map(capitalize).collect(),
+        "camelCase" => words
+            .iter()
+            .enumerate()
+            .map(|(i, v)| if i == 0 { v.clone() } else { capitalize(v) })
+            .collect(),
+        "snake_case" => words.join("_"),
+        "SCREAMING_SNAKE_CASE" => words.join("_").to_uppercase(),
+        "kebab-case" => words.join("-"),
+        "SCREAMING-KEBAB-CASE" => words.join("-").to_uppercase(),
+        _ => name.to_owned(),
+    }
+}
+
+// the name serde gives to a field or a variant
+pub fn serde_name(container: &SerdeAttrs, attrs: SerdeAttrs, ident: &str) -> String {
+    attrs.rename.unwrap_or_else(|| {
+        container
+            .rename_all
+            .as_deref()
+            .map_or_else(|| ident.to_owned(), |v| rename(ident, v))
+    })
+}
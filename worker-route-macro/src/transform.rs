@@ -1,24 +1,27 @@
-use crate::wrapper::Wrapper;
+use crate::wrapper::{last_ident, Wrapper};
 
 use proc_macro2::{Ident, TokenStream};
-use quote::ToTokens;
+use quote::{quote, quote_spanned, ToTokens};
 use syn::{
-    parse2,
     punctuated::Punctuated,
+    spanned::Spanned,
     token::{Async, Comma},
-    FnArg, ItemFn, ReturnType, Stmt, TypePath, Visibility,
+    FnArg, ItemFn, PathArguments, ReturnType, Stmt, Type, Visibility,
 };
 
 pub struct FnWrapper<'a> {
     pub args: &'a Punctuated<FnArg, Comma>,
     pub asyncness: Option<Async>,
     pub data: TokenStream,
+    pub extractors: Vec<Type>,
+    pub generics: Option<TokenStream>,
     pub name: &'a Ident,
     pub path: &'a str,
     pub ret: &'a ReturnType,
     pub route_context: TokenStream,
     pub stmts: &'a [Stmt],
     pub vis: &'a Visibility,
+    pub where_clause: Option<TokenStream>,
     pub wrapper: Result<TokenStream, TokenStream>,
 }
 
@@ -30,55 +33,103 @@ impl<'a> FnWrapper<'a> {
         let stmts = &block.stmts;
         let name = &sig.ident;
         let args = &sig.inputs;
-        let binding = args.last();
-        let ctx = binding.unwrap();
-        let is_query = args.first().unwrap();
-        let query = is_query.to_token_stream();
         let asyncness = sig.asyncness;
         let ret = &sig.output;
-        let wrapper = Wrapper::new(query.to_string(), args.len());
-        let (route_context, data) = get_generic(ctx);
-
-        let wrapper = wrapper.to_token(asyncness.is_some(), name, args, cors);
+        let generic = get_generic(sig);
+        let is_generic = matches!(generic, Ok(None));
+        let (route_context, data, wrapper) = match generic {
+            Ok(Some((route_context, data))) => (route_context, data, Wrapper::new(args)),
+            // the handler doesn't take RouteContext<D>, it can be registered to any Router<D>
+            // as long as every extractor can be extracted with D
+            Ok(None) => (
+                quote!(::worker::RouteContext<D__>),
+                quote!(D__),
+                Wrapper::new(args),
+            ),
+            Err(e) => (TokenStream::new(), TokenStream::new(), Err(e)),
+        };
+        let (generics, where_clause) = match &wrapper {
+            Ok(w) if is_generic => {
+                let tys = w.extractor_types();
+                (
+                    Some(quote!(<D__: 'static>)),
+                    Some(quote!(where #(#tys: ::worker_route::FromRequest<D__>,)*)),
+                )
+            }
+            _ => (None, None),
+        };
+        let extractors = wrapper
+            .as_ref()
+            .map_or_else(|_| Vec::new(), |w| w.extractor_types().cloned().collect());
+        let wrapper = wrapper.map(|w| w.to_token(asyncness.is_some(), name, &data, path, cors));
 
         Self {
             args,
             asyncness,
             data,
+            extractors,
+            generics,
             name,
             path,
             ret,
             route_context,
             stmts,
             vis,
+            where_clause,
             wrapper,
         }
     }
 }
 
+// the type or expression as written, eg: `Query<Person>` rather than `Query < Person >`
+pub fn type_name(ty: &impl ToTokens) -> String {
+    let mut name = ty.to_token_stream().to_string();
+    for (from, to) in [
+        (" <", "<"),
+        ("< ", "<"),
+        (" >", ">"),
+        (" ::", "::"),
+        (":: ", "::"),
+        (" ,", ","),
+        ("& ", "&"),
+    ] {
+        name = name.replace(from, to);
+    }
+    name
+}
+
 // given an pub fn
 // pub fn foo(req: Query<FooQuery>, ctx: RouteContext<CtxData>) -> Result<Response>
 // get_generic() is used to extract RouteContext<CtxData>
 // returning the parent and the child
-fn get_generic(ctx: &FnArg) -> (TokenStream, TokenStream) {
-    match ctx {
-        FnArg::Typed(ty) => {
-            let parent = ty.ty.to_token_stream();
-            let parent = parse2::<TypePath>(parent).unwrap();
-            let child = parent.path.segments[0].arguments.to_token_stream();
-            let child = parse2::<syn::AngleBracketedGenericArguments>(child).unwrap();
-            let child = child.args.last().unwrap().to_token_stream();
+//
+// RouteContext<D> can be declared anywhere in the arguments, or not at all
+fn get_generic(sig: &syn::Signature) -> Result<Option<(TokenStream, TokenStream)>, TokenStream> {
+    let Some(ctx) = sig.inputs.iter().find_map(|v| match v {
+        FnArg::Typed(ty) if last_ident(&ty.ty).as_deref() == Some("RouteContext") => Some(&*ty.ty),
+        _ => None,
+    }) else {
+        return Ok(None);
+    };
+
+    let generic = match ctx {
+        Type::Path(parent) => parent
+            .path
+            .segments
+            .last()
+            .and_then(|v| match &v.arguments {
+                PathArguments::AngleBracketed(child) => Some((
+                    parent.to_token_stream(),
+                    child.args.last()?.to_token_stream(),
+                )),
+                _ => None,
+            }),
+        _ => None,
+    };
 
-            (parent.to_token_stream(), child)
+    generic.map(Some).ok_or_else(|| {
+        quote_spanned! {
+            ctx.span() => compile_error!("expected an argument of `RouteContext<D>`");
         }
-        // don't worry about it, you only get this in your development environment
-        // the correct arguments sequence are
-        // (Request, RouteContext<D>)
-        // or
-        // (Query<T>, RouteContext<D>)
-        // or
-        // (Query<T>, Request, RouteContext<D>)
-        // the sequence of the above must be in the correct order
-        FnArg::Receiver(_) => panic!(),
-    }
+    })
 }
@@ -0,0 +1,121 @@
+use crate::constraint::Constraint;
+use crate::params::to_ident;
+use crate::route::Route;
+
+use proc_macro2::{Ident, Span, TokenStream};
+use quote::{format_ident, quote};
+
+// the argument's name of a param, made a Rust identifier if it isn't one,
+// eg: `my_id` for `:my-id` or `self_` for `:self`, suffixed with `_` if it's already taken
+fn to_arg(name: &str, taken: &[Ident]) -> Option<Ident> {
+    if name.is_empty() {
+        return None;
+    }
+
+    let mut ident = to_ident(name, Span::call_site()).unwrap_or_else(|| {
+        let mut name = name
+            .chars()
+            .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+            .collect::<String>();
+        if name.starts_with(|c: char| c.is_ascii_digit()) {
+            name.insert(0, '_');
+        }
+        // `self`, `super`, `crate` and `_` can't be raw identifiers
+        to_ident(&name, Span::call_site()).unwrap_or_else(|| format_ident!("{name}_"))
+    });
+    while taken.contains(&ident) {
+        ident = format_ident!("{ident}_");
+    }
+
+    Some(ident)
+}
+
+// the argument of a param, its type if constrained to one, eg: `age: u8` for `:age<u8>`
+fn argument(route: &Route, name: &str, ident: &Ident) -> TokenStream {
+    let ty = route
+        .constraints
+        .iter()
+        .find_map(|(param, constraint)| match constraint {
+            Constraint::Type(ty) if param == name => {
+                let ty = format_ident!("{ty}");
+                Some(quote!(#ty))
+            }
+            _ => None,
+        })
+        .unwrap_or_else(|| quote!(impl ::core::fmt::Display));
+
+    quote!(#ident: #ty)
+}
+
+/// Generates the `url` function of a handler, taking a value for each param of its pattern,
+/// eg: `person::url(name, age)` for `/person/:name/:age`.
+pub fn url(route: &Route, path: &str) -> Option<TokenStream> {
+    if route.is_fallback {
+        return None;
+    }
+
+    let mut args = Vec::new();
+    let mut idents = Vec::new();
+    let mut pushes = Vec::new();
+    let mut static_ = String::new();
+    for (i, segment) in path.split('/').enumerate() {
+        if i > 0 {
+            static_.push('/');
+        }
+        let (name, push) = match segment.split_at(segment.len().min(1)) {
+            (":", name) => (name, quote!(__param)),
+            ("*", name) => (name, quote!(__wildcard)),
+            _ => {
+                static_.push_str(segment);
+                continue;
+            }
+        };
+        let ident = to_arg(name, &idents)?;
+        args.push(argument(route, name, &ident));
+        if !static_.is_empty() {
+            pushes.push(quote!(url__.__push(#static_);));
+            static_.clear();
+        }
+        pushes.push(quote!(url__.#push(#ident);));
+        idents.push(ident);
+    }
+    if !static_.is_empty() {
+        pushes.push(quote!(url__.__push(#static_);));
+    }
+
+    let doc = format!(" Returns the URL of `{path}`, the params are percent-encoded.");
+    Some(quote! {
+        #[doc = #doc]
+        #[allow(dead_code)]
+        pub fn url(#(#args),*) -> ::worker_route::RouteUrl {
+            let mut url__ = ::worker_route::RouteUrl::__new("");
+            #(#pushes)*
+            url__
+        }
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::to_arg;
+
+    fn arg(name: &str, taken: &[&str]) -> String {
+        let taken = taken
+            .iter()
+            .map(|v| to_arg(v, &[]).unwrap())
+            .collect::<Vec<_>>();
+        to_arg(name, &taken).unwrap().to_string()
+    }
+
+    #[test]
+    fn arguments() {
+        assert_eq!(arg("id", &[]), "id");
+        assert_eq!(arg("type", &[]), "r#type");
+        assert_eq!(arg("my-id", &[]), "my_id");
+        assert_eq!(arg("2fa", &[]), "_2fa");
+        assert_eq!(arg("self", &[]), "self_");
+        assert_eq!(arg("-", &[]), "__");
+        assert_eq!(arg("my-id", &["my_id"]), "my_id_");
+        assert_eq!(to_arg("", &[]), None);
+    }
+}
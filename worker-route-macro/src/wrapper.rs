@@ -1,118 +1,145 @@
 use proc_macro2::Ident;
 use proc_macro2::TokenStream;
-use quote::{quote, quote_spanned};
+use quote::{format_ident, quote, quote_spanned};
 use syn::punctuated::Punctuated;
 use syn::spanned::Spanned;
 use syn::token::Comma;
-use syn::{parse_quote, FnArg};
+use syn::{parse_quote, FnArg, Type};
 
-pub struct Wrapper {
-    query__: String,
-    len: usize,
+/// How a handler's argument is supplied to the handler.
+pub enum Arg {
+    /// The owned `worker::Request`.
+    Request,
+    /// The owned `worker::RouteContext<D>`.
+    Context,
+    /// Anything else, extracted with `worker_route::FromRequest`.
+    Extractor(Ident, Box<Type>),
 }
 
-impl Wrapper {
-    pub const fn new(query__: String, len: usize) -> Self {
-        Self { query__, len }
+impl Arg {
+    fn new(i: usize, arg: &FnArg) -> Result<Self, TokenStream> {
+        let ty = match arg {
+            FnArg::Typed(ty) => &*ty.ty,
+            FnArg::Receiver(_) => return Err(to_error(arg, "handlers cannot take `self`")),
+        };
+
+        Ok(match last_ident(ty).as_deref() {
+            Some("Request") => Self::Request,
+            Some("RouteContext") => Self::Context,
+            _ => Self::Extractor(format_ident!("arg{}__", i), Box::new(ty.clone())),
+        })
     }
 
+    fn to_param(&self) -> TokenStream {
+        match self {
+            Self::Request => quote!(req__),
+            Self::Context => quote!(ctx__),
+            Self::Extractor(ident, _) => quote!(#ident),
+        }
+    }
+}
+
+/// Returns the last segment's ident of a type path.
+///
+/// eg: `worker::RouteContext<()>` -> `RouteContext`
+pub fn last_ident(ty: &Type) -> Option<String> {
+    match ty {
+        Type::Path(path) => path.path.segments.last().map(|v| v.ident.to_string()),
+        _ => None,
+    }
+}
+
+fn to_error(span: &impl Spanned, message: &str) -> TokenStream {
+    quote_spanned! {
+        span.span() => compile_error!(#message);
+    }
+}
+
+pub struct Wrapper {
+    args: Vec<Arg>,
+}
+
+impl Wrapper {
     /*
-     * len is extracted from the given fn's args.
-     * Length:
-     *  2 - (Default):
-     *      fn my_fn(req__: Request, ctx__: RouteContext<()>)
+     * Every argument of the handler is either:
+     *  - Request:          moved into the handler as is.
+     *  - RouteContext<D>:  moved into the handler as is.
+     *  - anything else:    extracted with FromRequest<D>, in the declared order.
      *
-     *  2 - (With query__ without request):
-     *      fn my_fn(query__: Query<T>, ctx__: RouteContext<()>)
-     *
-     *  3 - (With query__ and request):
-     *      fn my_fn(query__: Query<T>, req__: Request, ctx__: RouteContext<()>)
-     *
-     * Anything below 2
-     *
-     * or
-     *
-     * the parameters do not follow the above's sequence
      * eg:
+     *  fn my_fn(query__: Query<T>, req__: Request, ctx__: RouteContext<()>)
+     *  fn my_fn(ctx__: RouteContext<()>, json__: Json<T>, query__: Query<T>)
      *
-     * Correct parameters order:
-     * fn my_fn(query__: Query<T>, req__: Request, ctx__: RouteContext<()>)
-     *
-     * Incorrect parameters order:
-     * fn my_fn(req__: Request, query__: Query<T>, ctx__: RouteContext<()>)
-     *
-     * will not compile, it will result a proc-macro panic.
-     *
-     * TODO:
-     * Allow random parameters' sequence by refactoring this fn,
-     * contribution is very much welcomed!
-     *
+     * Request and RouteContext are moved, therefore each of them can only be taken once.
      */
+    pub fn new(args: &Punctuated<FnArg, Comma>) -> Result<Self, TokenStream> {
+        let mut args_ = Vec::new();
+        for (i, arg) in args.iter().enumerate() {
+            let current = Arg::new(i, arg)?;
+            let is_duplicate = args_.iter().any(|v| {
+                matches!(
+                    (v, &current),
+                    (Arg::Request, Arg::Request) | (Arg::Context, Arg::Context)
+                )
+            });
+            if is_duplicate {
+                return Err(to_error(
+                    arg,
+                    "`Request` and `RouteContext` can only be taken once",
+                ));
+            }
+            args_.push(current);
+        }
+
+        Ok(Self { args: args_ })
+    }
+
+    /// Returns the types of the arguments extracted with `FromRequest`.
+    pub fn extractor_types(&self) -> impl Iterator<Item = &Type> {
+        self.args.iter().filter_map(|v| match v {
+            Arg::Extractor(_, ty) => Some(&**ty),
+            _ => None,
+        })
+    }
+
+    /// Returns `true` if any of the arguments has to be extracted with `FromRequest`.
+    pub fn has_extractors(&self) -> bool {
+        self.args.iter().any(|v| matches!(v, Arg::Extractor(..)))
+    }
+
     #[allow(clippy::wrong_self_convention)]
     pub fn to_token(
         self,
         is_async: bool,
         name: &Ident,
-        args: &Punctuated<FnArg, Comma>,
+        data: &TokenStream,
+        path: &str,
         cors: &Option<TokenStream>,
-    ) -> Result<TokenStream, TokenStream> {
-        if (2..=3).contains(&self.len) {
-            return Ok(if self.query__.contains("Query") {
-                Self::wrap(is_async, name, args, cors)
-            } else {
-                Self::wrap_default(is_async, name, args, cors)
-            });
+    ) -> TokenStream {
+        if !is_async && !self.has_extractors() {
+            return self.wrap_default(name, cors);
         }
 
-        Err(Self::to_error(args))
+        self.wrap(is_async, name, data, path, cors)
     }
 
-    fn call_fn(name: &Ident, is_async: bool, args_: &[TokenStream]) -> TokenStream {
+    fn call_fn(&self, name: &Ident, is_async: bool) -> TokenStream {
+        let args_ = self.args.iter().map(Arg::to_param);
         if is_async {
-            parse_quote!(#name(#(#args_)*).await)
+            parse_quote!(#name(#(#args_),*))
         } else {
-            parse_quote!(#name(#(#args_)*))
+            parse_quote!(::core::future::ready(#name(#(#args_),*)))
         }
     }
 
-    fn wrap_default(
-        is_async: bool,
-        name: &Ident,
-        args: &Punctuated<FnArg, Comma>,
-        cors: &Option<TokenStream>,
-    ) -> TokenStream {
-        let (args_, _) = collect_args(true, args);
+    fn wrap_default(&self, name: &Ident, cors: &Option<TokenStream>) -> TokenStream {
+        let args_ = self.args.iter().map(Arg::to_param);
         let (var, c) = Self::get_cors(cors);
 
-        let ret = Self::call_fn(name, is_async, &args_);
         parse_quote!(
             #var
-            ::worker_route::__private::responder(#c, ::worker_route::http::HttpRequest::from(&req__), #ret)
+            ::worker_route::__private::responder(#c, ::worker_route::http::HttpRequest::from(&req__), #name(#(#args_),*))
         )
-        // if let Some(cors) = cors {
-        //     return parse_quote!(::worker_route::__private::responder(&#cors, #ret));
-        // }
-
-        // ret
-    }
-
-    fn to_response(
-        is_async: bool,
-        name: &Ident,
-        var: &Option<TokenStream>,
-        cors: &TokenStream,
-        fn_: &TokenStream,
-    ) -> TokenStream {
-        if !is_async {
-            return parse_quote! {
-                #var
-                ::worker_route::__private::FnType::wrap(&#fn_(#name), req__, ctx__, #cors)
-            };
-        }
-        parse_quote! {
-            #var
-            ::worker_route::__private::respond_async(::worker_route::http::HttpRequest::from(&req__), ::worker_route::__private::FnType::wrap_async(&#fn_(#name), req__, ctx__), #cors).await
-        }
     }
 
     fn get_cors(cors: &Option<TokenStream>) -> (Option<TokenStream>, TokenStream) {
@@ -130,60 +157,35 @@ impl Wrapper {
     }
 
     fn wrap(
+        &self,
         is_async: bool,
         name: &Ident,
-        args: &Punctuated<FnArg, Comma>,
+        data: &TokenStream,
+        path: &str,
         cors: &Option<TokenStream>,
     ) -> TokenStream {
         let (var, c) = Self::get_cors(cors);
-        let (_, fn_) = collect_args(false, args);
-
-        Self::to_response(is_async, name, &var, &c, &fn_)
-    }
-
-    fn to_error(args: &Punctuated<FnArg, Comma>) -> TokenStream {
-        let error = syn::Error::new(args.last().unwrap().span(), "invalid token");
-        quote_spanned! {
-            error.span() => compile_error!("invalid arguments");
-        }
-    }
-}
+        let (idents, extracts): (Vec<_>, Vec<_>) = self
+            .args
+            .iter()
+            .filter_map(|v| match v {
+                Arg::Extractor(ident, ty) => Some((
+                    ident,
+                    quote!(::worker_route::__private::extract::<#data, #ty>(&req__, &ctx__, #path).await?),
+                )),
+                _ => None,
+            })
+            .unzip();
+        let call = self.call_fn(name, is_async);
 
-pub fn get_param(is_default: bool, i: usize, len: usize) -> TokenStream {
-    if is_default {
-        if i < 2 {
-            quote!(req__)
-        } else {
-            quote!(ctx__)
-        }
-    } else if i == 3 && len == 3 || i == 2 && len == 2 {
-        quote!(ctx__)
-    } else if i == 1 {
-        quote!(query__)
-    } else {
-        quote!(req__)
-    }
-}
-
-pub fn collect_args(
-    is_default: bool,
-    args: &Punctuated<FnArg, Comma>,
-) -> (Vec<TokenStream>, TokenStream) {
-    let len = args.len();
-    let mut map_ = Vec::new();
-    for (i, v) in args.iter().enumerate() {
-        if let FnArg::Typed(_) = v {
-            let ident_ = get_param(is_default, i + 1, len);
-            map_.push(parse_quote!(#ident_,));
+        parse_quote! {
+            #var
+            let http__ = ::worker_route::http::HttpRequest::from(&req__);
+            let args__ = async {
+                Ok::<_, ::std::boxed::Box<dyn ::worker_route::ResponseError>>((#(#extracts,)*))
+            }
+            .await;
+            ::worker_route::__private::respond_async(http__, args__.map(move |(#(#idents,)*)| #call), #c).await
         }
     }
-    assert_eq!(map_.len(), len);
-    (
-        map_,
-        if len < 3 {
-            quote!(::worker_route::__private::FnType::WithQuery)
-        } else {
-            quote!(::worker_route::__private::FnType::WithReq)
-        },
-    )
 }